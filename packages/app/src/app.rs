@@ -3,22 +3,37 @@ use std::{path::PathBuf, sync::Arc};
 use migration::{Migrator, MigratorTrait};
 use sea_orm::DatabaseConnection;
 
-use crate::result::AppResult;
+use crate::{
+    config::AppConfig, identity_provider::IdentityProviders, mailer::Mailer, result::AppResult,
+    services::auth::effective_permissions::PermissionCache,
+};
 
 #[derive(Clone)]
 pub struct App {
     #[allow(dead_code)]
     pub conn: DatabaseConnection,
     pub upload_dir: Arc<PathBuf>,
+    pub config: Arc<AppConfig>,
+    pub mailer: Arc<dyn Mailer>,
+    pub identity_providers: Arc<IdentityProviders>,
+    pub permission_cache: Arc<PermissionCache>,
 }
 
 impl App {
-    pub async fn init(conn: DatabaseConnection, upload_dir: PathBuf) -> AppResult<Self> {
+    pub async fn init(
+        conn: DatabaseConnection,
+        upload_dir: PathBuf,
+        config: AppConfig,
+    ) -> AppResult<Self> {
         Migrator::up(&conn, None).await?;
 
         let app = Self {
             conn,
             upload_dir: Arc::new(upload_dir),
+            mailer: config.mail.build_mailer(),
+            identity_providers: Arc::new(config.oauth.build_identity_providers()),
+            permission_cache: Arc::new(PermissionCache::default()),
+            config: Arc::new(config),
         };
 
         Ok(app)
@@ -0,0 +1,483 @@
+use std::{collections::HashMap, path::PathBuf};
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use shared::utils::PasswordHashParams;
+
+#[derive(Clone, Debug)]
+pub struct AppConfig {
+    pub session: SessionConfig,
+    pub jwt: JwtConfig,
+    pub login: LoginConfig,
+    pub mail: MailConfig,
+    pub oauth: OAuthConfig,
+    pub authorization_server: AuthorizationServerConfig,
+    pub app_signature: AppSignatureConfig,
+    pub password_policy: PasswordPolicyConfig,
+    pub password_hash: PasswordHashConfig,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct JwtConfig {
+    /// HMAC key used to sign and verify the tokens minted into `auth_tokens`, and to derive the
+    /// keys applications sign requests with.
+    pub secret: String,
+}
+
+impl JwtConfig {
+    /// The secret shipped in `settings.toml`, only fit for development.
+    pub const PLACEHOLDER_SECRET: &str = "change-me-in-production";
+    pub const MIN_SECRET_LEN: usize = 32;
+
+    /// Why the secret can't be trusted to sign tokens, `None` if it can.
+    pub fn secret_weakness(&self) -> Option<String> {
+        if self.secret == Self::PLACEHOLDER_SECRET {
+            Some("jwt.secret is still the placeholder of settings.toml".to_string())
+        } else if self.secret.len() < Self::MIN_SECRET_LEN {
+            Some(format!(
+                "jwt.secret is shorter than {} bytes",
+                Self::MIN_SECRET_LEN
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SessionConfig {
+    /// Seconds of inactivity after which a session expires.
+    pub idle_timeout: i64,
+    /// Seconds after creation after which a session expires regardless of activity.
+    pub absolute_lifetime: i64,
+    /// Remaining seconds under which the expiry of an active session is slid forward.
+    pub renew_threshold: i64,
+    /// Seconds for which an access token handed to bearer clients is valid before it has to be
+    /// refreshed.
+    pub access_token_lifetime: i64,
+}
+
+impl Default for SessionConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: 7 * 24 * 60 * 60,
+            absolute_lifetime: 30 * 24 * 60 * 60,
+            renew_threshold: 24 * 60 * 60,
+            access_token_lifetime: 15 * 60,
+        }
+    }
+}
+
+impl SessionConfig {
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::seconds(self.idle_timeout)
+    }
+
+    pub fn absolute_lifetime(&self) -> Duration {
+        Duration::seconds(self.absolute_lifetime)
+    }
+
+    pub fn renew_threshold(&self) -> Duration {
+        Duration::seconds(self.renew_threshold)
+    }
+
+    pub fn access_token_lifetime(&self) -> Duration {
+        Duration::seconds(self.access_token_lifetime)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct LoginConfig {
+    /// Consecutive failed logins after which an account is locked.
+    pub max_failed_attempts: i16,
+    /// Seconds of the first lockout, doubled with every further failure.
+    pub lockout_duration: i64,
+    /// Upper bound in seconds of a single lockout.
+    pub max_lockout_duration: i64,
+    /// Seconds a user has to finish a login after the password was accepted, by entering the
+    /// second factor or replacing an expired password.
+    pub second_factor_timeout: i64,
+    /// Issuer shown by authenticator apps next to the account.
+    pub totp_issuer: String,
+}
+
+impl Default for LoginConfig {
+    fn default() -> Self {
+        Self {
+            max_failed_attempts: 5,
+            lockout_duration: 60,
+            max_lockout_duration: 24 * 60 * 60,
+            second_factor_timeout: 5 * 60,
+            totp_issuer: "server-template".to_string(),
+        }
+    }
+}
+
+impl LoginConfig {
+    pub fn second_factor_timeout(&self) -> Duration {
+        Duration::seconds(self.second_factor_timeout)
+    }
+
+    /// How long an account is locked after its `failed_attempts`th consecutive failure, if at
+    /// all.
+    pub fn lockout_duration(&self, failed_attempts: i16) -> Option<Duration> {
+        let exceeded = failed_attempts.saturating_sub(self.max_failed_attempts);
+        if exceeded < 0 {
+            return None;
+        }
+
+        let seconds = self
+            .lockout_duration
+            .saturating_mul(1 << exceeded.min(32))
+            .min(self.max_lockout_duration);
+
+        Some(Duration::seconds(seconds))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct MailConfig {
+    /// Sender of every mail, e.g. `Server Template <noreply@example.com>`.
+    pub from: String,
+    pub transport: MailTransportConfig,
+    /// Link mailed to verify an address, `{token}` is replaced with the verification token.
+    pub verify_email_url: String,
+    /// Link mailed to reset a password, `{token}` is replaced with the reset token.
+    pub reset_password_url: String,
+    /// Link mailed to sign in without a password, `{token}` is replaced with the login token.
+    pub email_login_url: String,
+    /// Seconds an email verification token is valid.
+    pub email_verification_lifetime: i64,
+    /// Seconds a password reset token is valid.
+    pub password_reset_lifetime: i64,
+    /// Seconds an email login link or code is valid.
+    pub email_login_lifetime: i64,
+    /// Wrong codes tried before an email login code is void.
+    pub email_login_max_attempts: u32,
+}
+
+impl Default for MailConfig {
+    fn default() -> Self {
+        Self {
+            from: "noreply@localhost".to_string(),
+            transport: MailTransportConfig::File {
+                dir: PathBuf::from("mails"),
+            },
+            verify_email_url: "http://localhost:3000/verifyEmail?token={token}".to_string(),
+            reset_password_url: "http://localhost:3000/resetPassword?token={token}".to_string(),
+            email_verification_lifetime: 24 * 60 * 60,
+            password_reset_lifetime: 60 * 60,
+            email_login_url: "http://localhost:3000/emailLogin?token={token}".to_string(),
+            email_login_lifetime: 10 * 60,
+            email_login_max_attempts: 5,
+        }
+    }
+}
+
+impl MailConfig {
+    pub fn email_verification_lifetime(&self) -> Duration {
+        Duration::seconds(self.email_verification_lifetime)
+    }
+
+    pub fn password_reset_lifetime(&self) -> Duration {
+        Duration::seconds(self.password_reset_lifetime)
+    }
+
+    pub fn email_login_lifetime(&self) -> Duration {
+        Duration::seconds(self.email_login_lifetime)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MailTransportConfig {
+    Smtp(SmtpConfig),
+    /// Write mails into a directory instead of sending them.
+    File {
+        dir: PathBuf,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SmtpConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    #[serde(skip_serializing)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub security: SmtpSecurity,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    None,
+    /// Upgrade a plain connection with `STARTTLS`, usually on port 587.
+    #[default]
+    StartTls,
+    /// TLS from the start, usually on port 465.
+    Tls,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OAuthConfig {
+    /// Public base of the callback routes, `/{provider}/callback` is appended per provider.
+    pub redirect_base_url: String,
+    /// Seconds a started login may take to come back through the callback.
+    pub state_lifetime: i64,
+    /// Identity providers keyed by the name used in the routes.
+    pub providers: HashMap<String, IdentityProviderConfig>,
+}
+
+impl Default for OAuthConfig {
+    fn default() -> Self {
+        Self {
+            redirect_base_url: "http://localhost:4000/api/auth/oauth".to_string(),
+            state_lifetime: 10 * 60,
+            providers: HashMap::new(),
+        }
+    }
+}
+
+impl OAuthConfig {
+    pub fn state_lifetime(&self) -> Duration {
+        Duration::seconds(self.state_lifetime)
+    }
+
+    pub fn redirect_uri(&self, provider: &str) -> String {
+        format!(
+            "{}/{provider}/callback",
+            self.redirect_base_url.trim_end_matches('/')
+        )
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IdentityProviderConfig {
+    Oidc(OidcProviderConfig),
+    Github(GithubProviderConfig),
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct OidcProviderConfig {
+    /// Issuer identifier, the discovery document is read from `{issuer}/.well-known/openid-configuration`.
+    pub issuer: String,
+    pub client_id: String,
+    #[serde(skip_serializing)]
+    pub client_secret: String,
+    #[serde(default = "OidcProviderConfig::default_scopes")]
+    pub scopes: Vec<String>,
+}
+
+impl OidcProviderConfig {
+    fn default_scopes() -> Vec<String> {
+        ["openid", "email", "profile"].map(String::from).to_vec()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GithubProviderConfig {
+    pub client_id: String,
+    #[serde(skip_serializing)]
+    pub client_secret: String,
+    #[serde(default = "GithubProviderConfig::default_authorize_url")]
+    pub authorize_url: String,
+    #[serde(default = "GithubProviderConfig::default_token_url")]
+    pub token_url: String,
+    #[serde(default = "GithubProviderConfig::default_api_url")]
+    pub api_url: String,
+}
+
+impl GithubProviderConfig {
+    fn default_authorize_url() -> String {
+        "https://github.com/login/oauth/authorize".to_string()
+    }
+
+    fn default_token_url() -> String {
+        "https://github.com/login/oauth/access_token".to_string()
+    }
+
+    fn default_api_url() -> String {
+        "https://api.github.com".to_string()
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AuthorizationServerConfig {
+    /// Seconds an authorization code may wait to be exchanged.
+    pub authorization_code_lifetime: i64,
+    /// Seconds an access token issued to an application is valid.
+    pub access_token_lifetime: i64,
+}
+
+impl Default for AuthorizationServerConfig {
+    fn default() -> Self {
+        Self {
+            authorization_code_lifetime: 60,
+            access_token_lifetime: 60 * 60,
+        }
+    }
+}
+
+impl AuthorizationServerConfig {
+    pub fn authorization_code_lifetime(&self) -> Duration {
+        Duration::seconds(self.authorization_code_lifetime)
+    }
+
+    pub fn access_token_lifetime(&self) -> Duration {
+        Duration::seconds(self.access_token_lifetime)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct AppSignatureConfig {
+    /// Seconds a signed request's timestamp may be off from the server clock, either way. Nonces
+    /// are remembered for as long as a timestamp stays acceptable.
+    pub timestamp_window: i64,
+}
+
+impl Default for AppSignatureConfig {
+    fn default() -> Self {
+        Self {
+            timestamp_window: 5 * 60,
+        }
+    }
+}
+
+impl AppSignatureConfig {
+    pub fn timestamp_window(&self) -> Duration {
+        Duration::seconds(self.timestamp_window)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PasswordPolicyConfig {
+    pub min_length: usize,
+    /// How many of lowercase letters, uppercase letters, digits and symbols a password must mix.
+    pub min_character_classes: usize,
+    /// Passwords refused on top of the built-in list of common ones, compared case-insensitively.
+    pub blocklist: Vec<String>,
+    /// Refuse passwords containing the account name.
+    pub reject_account_name: bool,
+    /// How many of the previous passwords of a user may not be used again.
+    pub history_size: usize,
+    /// Seconds after which a password has to be changed on the next login, never if unset.
+    pub max_age: Option<i64>,
+}
+
+impl Default for PasswordPolicyConfig {
+    fn default() -> Self {
+        Self {
+            min_length: 8,
+            min_character_classes: 3,
+            blocklist: Vec::new(),
+            reject_account_name: true,
+            history_size: 5,
+            max_age: None,
+        }
+    }
+}
+
+impl PasswordPolicyConfig {
+    pub fn max_age(&self) -> Option<Duration> {
+        self.max_age.map(Duration::seconds)
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PasswordHashConfig {
+    /// Memory in KiB each Argon2id hash takes.
+    pub memory_cost: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    /// Secret mixed into every hash, kept in the settings rather than the database. Passwords
+    /// hashed with a different pepper can't be verified anymore.
+    #[serde(skip_serializing)]
+    pub pepper: Option<String>,
+}
+
+impl Default for PasswordHashConfig {
+    fn default() -> Self {
+        let params = PasswordHashParams::default();
+        Self {
+            memory_cost: params.memory_cost,
+            iterations: params.iterations,
+            parallelism: params.parallelism,
+            pepper: None,
+        }
+    }
+}
+
+impl PasswordHashConfig {
+    pub fn params(&self) -> PasswordHashParams<'_> {
+        PasswordHashParams {
+            memory_cost: self.memory_cost,
+            iterations: self.iterations,
+            parallelism: self.parallelism,
+            pepper: self.pepper(),
+        }
+    }
+
+    pub fn pepper(&self) -> Option<&[u8]> {
+        self.pepper.as_deref().map(str::as_bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_lockout_below_max_failed_attempts() {
+        let config = LoginConfig::default();
+
+        assert_eq!(config.lockout_duration(0), None);
+        assert_eq!(
+            config.lockout_duration(config.max_failed_attempts - 1),
+            None
+        );
+    }
+
+    #[test]
+    fn lockout_doubles_with_every_further_failure() {
+        let config = LoginConfig::default();
+        let max = config.max_failed_attempts;
+
+        assert_eq!(config.lockout_duration(max), Some(Duration::seconds(60)));
+        assert_eq!(
+            config.lockout_duration(max + 1),
+            Some(Duration::seconds(120))
+        );
+        assert_eq!(
+            config.lockout_duration(max + 2),
+            Some(Duration::seconds(240))
+        );
+        assert_eq!(
+            config.lockout_duration(max + 10),
+            Some(Duration::seconds(60 * 1024))
+        );
+    }
+
+    #[test]
+    fn lockout_is_capped() {
+        let config = LoginConfig::default();
+        let max = config.max_failed_attempts;
+        let cap = Some(Duration::seconds(config.max_lockout_duration));
+
+        // 60 * 2^10 is still under a day, 60 * 2^11 is over it
+        assert!(config.lockout_duration(max + 10) < cap);
+        assert_eq!(config.lockout_duration(max + 11), cap);
+        assert_eq!(config.lockout_duration(max + 40), cap);
+        assert_eq!(config.lockout_duration(i16::MAX), cap);
+    }
+}
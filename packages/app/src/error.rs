@@ -3,6 +3,8 @@ use std::fmt::Display;
 use sea_orm::{DbErr, SqlxError};
 use serde::Serialize;
 
+use crate::models::password_policy::PasswordPolicyViolation;
+
 #[derive(thiserror::Error, Debug)]
 pub enum AppError {
     #[error("AppException::{0}")]
@@ -17,6 +19,9 @@ pub enum AppError {
     #[error("SqlxError::{0}")]
     Sqlx(#[from] SqlxError),
 
+    #[error("HttpError::{0}")]
+    Http(#[from] reqwest::Error),
+
     #[error("Anyhow::{0}")]
     Anyhow(#[from] anyhow::Error),
 }
@@ -38,7 +43,29 @@ pub enum AppException {
     InvalidI32,
     InvalidBytes,
     AuthenticationFailed,
+    AccountLocked,
     InvalidCredentials,
+    PasswordPolicyViolated(Vec<PasswordPolicyViolation>),
+    InvalidRefreshToken,
+    RefreshTokenReused,
+    TotpAlreadyEnabled,
+    TotpNotEnrolled,
+    InvalidTotpCode,
+    InvalidEmail,
+    InvalidVerificationToken,
+    IdentityProviderNotFound,
+    ExternalAuthenticationFailed,
+    ApplicationNotFound,
+    InvalidClient,
+    UnauthorizedClient,
+    InvalidGrant,
+    InvalidScope,
+    InvalidRedirectUri,
+    UnsupportedGrantType,
+    UnsupportedResponseType,
+    InvalidRequest,
+    InvalidSignature,
+    RequestReplayed,
     UploadNotFound,
     UploadAlreadyMerged,
     UploadChunkIncomplete,
@@ -48,6 +75,13 @@ pub enum AppException {
     UserGroupNotFound,
     RoleGroupNotFound,
     PermissionNotFound,
+    PermissionNotGranted(String),
+    ImpersonationNotAllowed,
+    RegistrationDisabled,
+    InvitationRequired,
+    EmailDomainNotAllowed,
+    InvalidInvitation,
+    InvalidDataScope,
     PermissionGroupNotFound,
     GroupCircleDetected,
     DepartmentCircleDetected,
@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use reqwest::{
+    Client, Url,
+    header::{ACCEPT, USER_AGENT},
+};
+use serde::Deserialize;
+
+use crate::{config::GithubProviderConfig, error::AppException, result::AppResult};
+
+use super::{AuthorizationRequest, CodeExchange, ExternalIdentity, IdentityProvider};
+
+/// GitHub flavoured OAuth 2.0: no ID token, the identity is read from the REST API.
+pub struct GithubProvider {
+    config: GithubProviderConfig,
+    http: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    /// Missing when the code is rejected, GitHub answers that with `200 OK` and an `error`.
+    access_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubUser {
+    id: i64,
+    login: String,
+    name: Option<String>,
+    email: Option<String>,
+    avatar_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GithubEmail {
+    email: String,
+    primary: bool,
+    verified: bool,
+}
+
+impl GithubProvider {
+    pub fn new(config: GithubProviderConfig) -> Self {
+        Self {
+            config,
+            http: Client::new(),
+        }
+    }
+
+    async fn get_api<T: for<'de> Deserialize<'de>>(
+        &self,
+        path: &str,
+        access_token: &str,
+    ) -> AppResult<T> {
+        let url = format!("{}{path}", self.config.api_url.trim_end_matches('/'));
+        let value = self
+            .http
+            .get(url)
+            .bearer_auth(access_token)
+            .header(ACCEPT, "application/vnd.github+json")
+            .header(USER_AGENT, env!("CARGO_PKG_NAME"))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        Ok(value)
+    }
+}
+
+#[async_trait]
+impl IdentityProvider for GithubProvider {
+    async fn authorization_url(&self, request: AuthorizationRequest) -> AppResult<String> {
+        let url = Url::parse_with_params(
+            &self.config.authorize_url,
+            [
+                ("client_id", self.config.client_id.as_str()),
+                ("redirect_uri", &request.redirect_uri),
+                ("scope", "read:user user:email"),
+                ("state", &request.state),
+                ("code_challenge", &request.code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(anyhow::Error::from)?;
+
+        Ok(url.into())
+    }
+
+    async fn exchange_code(&self, exchange: CodeExchange) -> AppResult<ExternalIdentity> {
+        let response = self
+            .http
+            .post(&self.config.token_url)
+            .header(ACCEPT, "application/json")
+            .form(&[
+                ("client_id", &self.config.client_id),
+                ("client_secret", &self.config.client_secret),
+                ("code", &exchange.code),
+                ("redirect_uri", &exchange.redirect_uri),
+                ("code_verifier", &exchange.code_verifier),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(AppException::ExternalAuthenticationFailed.into());
+        }
+        let token: TokenResponse = response.json().await?;
+        let Some(access_token) = token.access_token else {
+            return Err(AppException::ExternalAuthenticationFailed.into());
+        };
+
+        let payload: serde_json::Value = self.get_api("/user", &access_token).await?;
+        let user: GithubUser =
+            serde_json::from_value(payload.clone()).map_err(anyhow::Error::from)?;
+
+        // The public profile email is unverified, only the primary address of the email API is
+        let emails: Vec<GithubEmail> = self
+            .get_api("/user/emails", &access_token)
+            .await
+            .unwrap_or_default();
+        let (email, email_verified) = match emails.into_iter().find(|x| x.primary && x.verified) {
+            Some(primary) => (Some(primary.email), true),
+            None => (user.email, false),
+        };
+
+        Ok(ExternalIdentity {
+            subject: user.id.to_string(),
+            union_id: None,
+            email,
+            email_verified,
+            name: user.name.or(Some(user.login)),
+            avatar_url: user.avatar_url,
+            access_token: Some(access_token),
+            payload,
+        })
+    }
+}
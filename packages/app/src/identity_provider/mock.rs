@@ -0,0 +1,249 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use axum::{
+    Form, Json, Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use chrono::Utc;
+use reqwest::Url;
+use rsa::{RsaPrivateKey, pkcs1::DecodeRsaPrivateKey, traits::PublicKeyParts};
+use serde_json::{Map, Value, json};
+use shared::utils::{encode_base64_url, generate_random_token, pkce_challenge, sign_rsa};
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{config::OidcProviderConfig, error::AppException, result::AppResult};
+
+use super::ExternalIdentity;
+
+const CLIENT_ID: &str = "mock-client";
+const CLIENT_SECRET: &str = "mock-secret";
+const KEY_ID: &str = "mock-key";
+
+/// An OpenID Connect provider served in-process on a local port, to run logins through
+/// `OidcProvider` without a real one. It serves the discovery document, the key set and the
+/// token endpoint, and checks what a real provider would: codes are spent once, and are only
+/// redeemed by the client, with the redirect URI and PKCE verifier of the authorization they
+/// were issued for. The ID token carries the nonce of the authorization.
+///
+/// Its keys are fixed and public, it only ever exists in tests.
+pub struct MockOidcServer {
+    state: Arc<MockOidcState>,
+    task: JoinHandle<()>,
+}
+
+struct MockOidcState {
+    issuer: String,
+    signing_key: RsaPrivateKey,
+    unknown_key: RsaPrivateKey,
+    /// Codes not redeemed yet.
+    codes: Mutex<HashMap<String, IssuedCode>>,
+}
+
+struct IssuedCode {
+    redirect_uri: String,
+    code_challenge: String,
+    nonce: String,
+    identity: ExternalIdentity,
+    options: MockIdTokenOptions,
+}
+
+/// How the ID token of an approved authorization deviates from a valid one.
+#[derive(Debug, Clone, Default)]
+pub struct MockIdTokenOptions {
+    /// Claims replacing the ones the token would carry, e.g. another `aud`.
+    pub claims: Map<String, Value>,
+    /// Sign the token with a key missing from the key set.
+    pub unknown_key: bool,
+}
+
+/// The redirect back from the provider once the user signed in.
+#[derive(Debug, Clone)]
+pub struct MockRedirect {
+    pub state: String,
+    pub code: String,
+}
+
+impl MockOidcServer {
+    pub async fn start() -> AppResult<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(anyhow::Error::from)?;
+        let addr = listener.local_addr().map_err(anyhow::Error::from)?;
+
+        let state = Arc::new(MockOidcState {
+            issuer: format!("http://{addr}"),
+            signing_key: mock_key(include_str!("mock_keys/signing.pem")),
+            unknown_key: mock_key(include_str!("mock_keys/unknown.pem")),
+            codes: Mutex::default(),
+        });
+        let router = Router::new()
+            .route("/.well-known/openid-configuration", get(discovery))
+            .route("/jwks", get(jwks))
+            .route("/token", post(token))
+            .with_state(state.clone());
+        let task = tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+
+        Ok(Self { state, task })
+    }
+
+    /// Configuration of an `OidcProvider` registered as the client of this server.
+    pub fn provider_config(&self) -> OidcProviderConfig {
+        OidcProviderConfig {
+            issuer: self.state.issuer.clone(),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: CLIENT_SECRET.to_string(),
+            scopes: vec!["openid".to_string(), "email".to_string()],
+        }
+    }
+
+    /// Sign `identity` in at the authorization URL built by the client, as the user would.
+    pub fn approve(
+        &self,
+        authorization_url: &str,
+        identity: ExternalIdentity,
+    ) -> AppResult<MockRedirect> {
+        self.approve_with(authorization_url, identity, MockIdTokenOptions::default())
+    }
+
+    /// Like `approve`, with an ID token deviating as `options` ask for.
+    pub fn approve_with(
+        &self,
+        authorization_url: &str,
+        identity: ExternalIdentity,
+        options: MockIdTokenOptions,
+    ) -> AppResult<MockRedirect> {
+        let url = Url::parse(authorization_url).map_err(anyhow::Error::from)?;
+        let query = url.query_pairs().into_owned().collect::<HashMap<_, _>>();
+        let param = |name: &str| query.get(name).cloned().unwrap_or_default();
+
+        if !authorization_url.starts_with(&self.state.issuer)
+            || param("response_type") != "code"
+            || param("client_id") != CLIENT_ID
+            || param("code_challenge_method") != "S256"
+        {
+            return Err(AppException::ExternalAuthenticationFailed.into());
+        }
+
+        let code = generate_random_token(32);
+        self.state.codes.lock().unwrap().insert(
+            code.clone(),
+            IssuedCode {
+                redirect_uri: param("redirect_uri"),
+                code_challenge: param("code_challenge"),
+                nonce: param("nonce"),
+                identity,
+                options,
+            },
+        );
+
+        Ok(MockRedirect {
+            state: param("state"),
+            code,
+        })
+    }
+}
+
+impl Drop for MockOidcServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn mock_key(pem: &str) -> RsaPrivateKey {
+    RsaPrivateKey::from_pkcs1_pem(pem).unwrap()
+}
+
+async fn discovery(State(state): State<Arc<MockOidcState>>) -> Json<Value> {
+    Json(json!({
+        "issuer": state.issuer,
+        "authorization_endpoint": format!("{}/authorize", state.issuer),
+        "token_endpoint": format!("{}/token", state.issuer),
+        "jwks_uri": format!("{}/jwks", state.issuer),
+    }))
+}
+
+async fn jwks(State(state): State<Arc<MockOidcState>>) -> Json<Value> {
+    let public_key = state.signing_key.to_public_key();
+
+    Json(json!({
+        "keys": [{
+            "kty": "RSA",
+            "use": "sig",
+            "alg": "RS256",
+            "kid": KEY_ID,
+            "n": encode_base64_url(&public_key.n().to_bytes_be()),
+            "e": encode_base64_url(&public_key.e().to_bytes_be()),
+        }]
+    }))
+}
+
+async fn token(
+    State(state): State<Arc<MockOidcState>>,
+    Form(form): Form<HashMap<String, String>>,
+) -> Response {
+    let field = |name: &str| form.get(name).map(String::as_str).unwrap_or_default();
+    let issued = state.codes.lock().unwrap().remove(field("code"));
+    let Some(issued) = issued.filter(|x| {
+        field("grant_type") == "authorization_code"
+            && field("client_id") == CLIENT_ID
+            && field("client_secret") == CLIENT_SECRET
+            && field("redirect_uri") == x.redirect_uri
+            && pkce_challenge(field("code_verifier")) == x.code_challenge
+    }) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({ "error": "invalid_grant" })),
+        )
+            .into_response();
+    };
+
+    let identity = &issued.identity;
+    let now = Utc::now().timestamp();
+    let mut claims = json!({
+        "iss": state.issuer,
+        "aud": CLIENT_ID,
+        "sub": identity.subject,
+        "iat": now,
+        "exp": now + 300,
+        "nonce": issued.nonce,
+        "email": identity.email,
+        "email_verified": identity.email_verified,
+        "name": identity.name,
+        "picture": identity.avatar_url,
+    });
+    claims
+        .as_object_mut()
+        .unwrap()
+        .extend(issued.options.claims);
+    let key = match issued.options.unknown_key {
+        true => &state.unknown_key,
+        false => &state.signing_key,
+    };
+
+    Json(json!({
+        "access_token": identity.access_token,
+        "token_type": "Bearer",
+        "id_token": sign_id_token(key, &claims),
+    }))
+    .into_response()
+}
+
+fn sign_id_token(key: &RsaPrivateKey, claims: &Value) -> String {
+    let header = json!({ "alg": "RS256", "typ": "JWT", "kid": KEY_ID });
+    let signing_input = format!(
+        "{}.{}",
+        encode_base64_url(header.to_string().as_bytes()),
+        encode_base64_url(claims.to_string().as_bytes())
+    );
+    let signature = sign_rsa(key, signing_input.as_bytes()).unwrap();
+
+    format!("{signing_input}.{}", encode_base64_url(&signature))
+}
@@ -0,0 +1,83 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_trait::async_trait;
+
+use crate::{
+    config::{IdentityProviderConfig, OAuthConfig},
+    result::AppResult,
+};
+
+pub mod github;
+#[cfg(any(test, feature = "test-util"))]
+pub mod mock;
+pub mod oidc;
+
+pub use github::GithubProvider;
+#[cfg(any(test, feature = "test-util"))]
+pub use mock::MockOidcServer;
+pub use oidc::OidcProvider;
+
+/// Identity providers keyed by the name used in the routes.
+pub type IdentityProviders = HashMap<String, Arc<dyn IdentityProvider>>;
+
+/// Where to send the user to sign in at the provider.
+#[derive(Debug, Clone)]
+pub struct AuthorizationRequest {
+    pub redirect_uri: String,
+    pub state: String,
+    /// Bound into the ID token by OpenID Connect providers, ignored by plain OAuth 2.0 ones.
+    pub nonce: String,
+    /// PKCE `S256` challenge of the code verifier handed to `exchange_code` later.
+    pub code_challenge: String,
+}
+
+/// The authorization code the provider redirected back with, and what is needed to redeem it.
+#[derive(Debug, Clone)]
+pub struct CodeExchange {
+    pub code: String,
+    pub redirect_uri: String,
+    pub code_verifier: String,
+    pub nonce: String,
+}
+
+/// The user as known to an identity provider.
+#[derive(Debug, Clone)]
+pub struct ExternalIdentity {
+    /// Stable id of the user at the provider.
+    pub subject: String,
+    pub union_id: Option<String>,
+    pub email: Option<String>,
+    /// Whether the provider vouches for the user owning `email`.
+    pub email_verified: bool,
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub access_token: Option<String>,
+    /// Claims or profile the identity was read from.
+    pub payload: serde_json::Value,
+}
+
+#[async_trait]
+pub trait IdentityProvider: Send + Sync {
+    async fn authorization_url(&self, request: AuthorizationRequest) -> AppResult<String>;
+
+    /// Redeem an authorization code. Fails with `ExternalAuthenticationFailed` if the provider
+    /// rejects the code or hands back an identity that doesn't check out.
+    async fn exchange_code(&self, exchange: CodeExchange) -> AppResult<ExternalIdentity>;
+}
+
+impl OAuthConfig {
+    pub fn build_identity_providers(&self) -> IdentityProviders {
+        self.providers
+            .iter()
+            .map(|(name, config)| {
+                let provider: Arc<dyn IdentityProvider> = match config {
+                    IdentityProviderConfig::Oidc(oidc) => Arc::new(OidcProvider::new(oidc.clone())),
+                    IdentityProviderConfig::Github(github) => {
+                        Arc::new(GithubProvider::new(github.clone()))
+                    }
+                };
+                (name.clone(), provider)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,202 @@
+use async_trait::async_trait;
+use jsonwebtoken::{
+    DecodingKey, Validation, decode, decode_header,
+    jwk::{AlgorithmParameters, JwkSet},
+};
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use tokio::sync::OnceCell;
+
+use crate::{
+    config::OidcProviderConfig,
+    error::{AppError, AppException},
+    result::AppResult,
+};
+
+use super::{AuthorizationRequest, CodeExchange, ExternalIdentity, IdentityProvider};
+
+/// A generic OpenID Connect provider, configured through its discovery document.
+pub struct OidcProvider {
+    config: OidcProviderConfig,
+    http: Client,
+    metadata: OnceCell<ProviderMetadata>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: Option<String>,
+    id_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+    email: Option<String>,
+    #[serde(default)]
+    email_verified: bool,
+    name: Option<String>,
+    picture: Option<String>,
+}
+
+impl OidcProvider {
+    pub fn new(config: OidcProviderConfig) -> Self {
+        Self {
+            config,
+            http: Client::new(),
+            metadata: OnceCell::new(),
+        }
+    }
+
+    /// The discovery document, fetched on first use.
+    async fn metadata(&self) -> AppResult<&ProviderMetadata> {
+        self.metadata
+            .get_or_try_init(|| async {
+                let url = format!(
+                    "{}/.well-known/openid-configuration",
+                    self.config.issuer.trim_end_matches('/')
+                );
+                let metadata: ProviderMetadata = self
+                    .http
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?;
+
+                if metadata.issuer != self.config.issuer {
+                    return Err(anyhow::anyhow!(
+                        "Issuer mismatch in discovery document: {}",
+                        metadata.issuer
+                    )
+                    .into());
+                }
+
+                Ok::<_, AppError>(metadata)
+            })
+            .await
+    }
+
+    /// Check the signature, issuer, audience, expiry and nonce of an ID token against the
+    /// provider's current key set.
+    fn verify_id_token(
+        &self,
+        id_token: &str,
+        jwks: &JwkSet,
+        issuer: &str,
+        nonce: &str,
+    ) -> AppResult<(IdTokenClaims, serde_json::Value)> {
+        let header = decode_header(id_token).map_err(failed)?;
+        let jwk = match &header.kid {
+            Some(kid) => jwks.find(kid),
+            None => jwks.keys.first(),
+        };
+        // The key set is public, so a symmetric key from it proves nothing
+        let Some(jwk) = jwk.filter(|x| !matches!(x.algorithm, AlgorithmParameters::OctetKey(_)))
+        else {
+            return Err(AppException::ExternalAuthenticationFailed.into());
+        };
+        let key = DecodingKey::from_jwk(jwk).map_err(failed)?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[&self.config.client_id]);
+        validation.set_issuer(&[issuer]);
+        validation.set_required_spec_claims(&["exp", "iss", "aud", "sub"]);
+
+        let payload = decode::<serde_json::Value>(id_token, &key, &validation)
+            .map_err(failed)?
+            .claims;
+        let claims: IdTokenClaims = serde_json::from_value(payload.clone()).map_err(failed)?;
+
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(AppException::ExternalAuthenticationFailed.into());
+        }
+
+        Ok((claims, payload))
+    }
+}
+
+fn failed<E>(_: E) -> AppError {
+    AppException::ExternalAuthenticationFailed.into()
+}
+
+#[async_trait]
+impl IdentityProvider for OidcProvider {
+    async fn authorization_url(&self, request: AuthorizationRequest) -> AppResult<String> {
+        let metadata = self.metadata().await?;
+
+        let url = Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            [
+                ("response_type", "code"),
+                ("client_id", &self.config.client_id),
+                ("redirect_uri", &request.redirect_uri),
+                ("scope", &self.config.scopes.join(" ")),
+                ("state", &request.state),
+                ("nonce", &request.nonce),
+                ("code_challenge", &request.code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(anyhow::Error::from)?;
+
+        Ok(url.into())
+    }
+
+    async fn exchange_code(&self, exchange: CodeExchange) -> AppResult<ExternalIdentity> {
+        let metadata = self.metadata().await?;
+
+        let response = self
+            .http
+            .post(&metadata.token_endpoint)
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", &exchange.code),
+                ("redirect_uri", &exchange.redirect_uri),
+                ("client_id", &self.config.client_id),
+                ("client_secret", &self.config.client_secret),
+                ("code_verifier", &exchange.code_verifier),
+            ])
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(AppException::ExternalAuthenticationFailed.into());
+        }
+        let token: TokenResponse = response.json().await?;
+        let Some(id_token) = token.id_token else {
+            return Err(AppException::ExternalAuthenticationFailed.into());
+        };
+
+        // Fetched per login rather than cached, so rotated keys are picked up right away
+        let jwks: JwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let (claims, payload) =
+            self.verify_id_token(&id_token, &jwks, &metadata.issuer, &exchange.nonce)?;
+
+        Ok(ExternalIdentity {
+            subject: claims.sub,
+            union_id: None,
+            email: claims.email,
+            email_verified: claims.email_verified,
+            name: claims.name,
+            avatar_url: claims.picture,
+            access_token: token.access_token,
+            payload,
+        })
+    }
+}
@@ -1,6 +1,9 @@
 pub mod services;
 pub mod app;
+pub mod config;
 pub mod error;
+pub mod identity_provider;
+pub mod mailer;
 pub mod result;
 pub mod utils;
 pub mod models;
@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::result::AppResult;
+
+use super::{Mail, Mailer, format_message};
+
+/// Writes every mail as an `.eml` file into a directory instead of delivering it. Meant for
+/// development and tests.
+pub struct FileMailer {
+    from: String,
+    dir: PathBuf,
+}
+
+impl FileMailer {
+    pub fn new(from: String, dir: PathBuf) -> Self {
+        Self { from, dir }
+    }
+}
+
+#[async_trait]
+impl Mailer for FileMailer {
+    async fn send(&self, mail: Mail) -> AppResult<()> {
+        let message = format_message(&self.from, &mail)?;
+
+        tokio::fs::create_dir_all(&self.dir).await?;
+        let file_name = format!(
+            "{}-{}.eml",
+            Utc::now().format("%Y%m%d%H%M%S"),
+            Uuid::new_v4()
+        );
+        tokio::fs::write(self.dir.join(file_name), message).await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{
+    config::{MailConfig, MailTransportConfig},
+    result::AppResult,
+};
+
+pub mod file;
+pub mod smtp;
+
+pub use file::FileMailer;
+pub use smtp::SmtpMailer;
+
+/// A plain text mail to a single recipient.
+#[derive(Debug, Clone)]
+pub struct Mail {
+    pub to: String,
+    pub subject: String,
+    pub body: String,
+}
+
+#[async_trait]
+pub trait Mailer: Send + Sync {
+    async fn send(&self, mail: Mail) -> AppResult<()>;
+}
+
+impl MailConfig {
+    pub fn build_mailer(&self) -> Arc<dyn Mailer> {
+        match &self.transport {
+            MailTransportConfig::Smtp(smtp) => {
+                Arc::new(SmtpMailer::new(self.from.clone(), smtp.clone()))
+            }
+            MailTransportConfig::File { dir } => {
+                Arc::new(FileMailer::new(self.from.clone(), dir.clone()))
+            }
+        }
+    }
+}
+
+/// Render a mail as an RFC 5322 message.
+pub(crate) fn format_message(from: &str, mail: &Mail) -> AppResult<String> {
+    // Line breaks in a header would let the value smuggle in headers of its own
+    for header in [from, &mail.to, &mail.subject] {
+        if header.contains(['\r', '\n']) {
+            return Err(anyhow::anyhow!("Line break in mail header: {header:?}").into());
+        }
+    }
+
+    let subject = if mail.subject.is_ascii() {
+        mail.subject.clone()
+    } else {
+        format!(
+            "=?utf-8?B?{}?=",
+            shared::utils::encode_base64(mail.subject.as_bytes())
+        )
+    };
+    let body = mail.body.lines().collect::<Vec<_>>().join("\r\n");
+
+    Ok(format!(
+        "From: {from}\r\n\
+         To: {to}\r\n\
+         Subject: {subject}\r\n\
+         Date: {date}\r\n\
+         MIME-Version: 1.0\r\n\
+         Content-Type: text/plain; charset=utf-8\r\n\
+         Content-Transfer-Encoding: 8bit\r\n\
+         \r\n\
+         {body}\r\n",
+        to = mail.to,
+        date = chrono::Utc::now().to_rfc2822(),
+    ))
+}
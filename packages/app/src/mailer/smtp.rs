@@ -0,0 +1,183 @@
+use std::sync::Arc;
+
+use anyhow::{Context, bail};
+use async_trait::async_trait;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+use tokio_rustls::{
+    TlsConnector,
+    rustls::{ClientConfig, RootCertStore, crypto::ring, pki_types::ServerName},
+};
+
+use crate::{
+    config::{SmtpConfig, SmtpSecurity},
+    result::AppResult,
+};
+
+use super::{Mail, Mailer, format_message};
+
+/// Delivers mails to an SMTP relay, one connection per mail.
+pub struct SmtpMailer {
+    from: String,
+    config: SmtpConfig,
+}
+
+impl SmtpMailer {
+    pub fn new(from: String, config: SmtpConfig) -> Self {
+        Self { from, config }
+    }
+
+    async fn deliver(&self, mail: &Mail) -> anyhow::Result<()> {
+        let message = format_message(&self.from, mail)?;
+        let config = &self.config;
+
+        let stream = TcpStream::connect((config.host.as_str(), config.port))
+            .await
+            .with_context(|| format!("Failed to connect to {}:{}", config.host, config.port))?;
+        let stream: Box<dyn Stream> = match config.security {
+            SmtpSecurity::Tls => Box::new(self.upgrade(stream).await?),
+            SmtpSecurity::None | SmtpSecurity::StartTls => Box::new(stream),
+        };
+
+        let mut connection = Connection::new(stream);
+        connection.expect(2).await?;
+        connection.command("EHLO localhost", 2).await?;
+
+        if config.security == SmtpSecurity::StartTls {
+            connection.command("STARTTLS", 2).await?;
+            let stream = self.upgrade(connection.into_inner()).await?;
+            connection = Connection::new(Box::new(stream));
+            connection.command("EHLO localhost", 2).await?;
+        }
+
+        if let Some(username) = &config.username {
+            let password = config.password.as_deref().unwrap_or_default();
+            let credentials =
+                shared::utils::encode_base64(format!("\0{username}\0{password}").as_bytes());
+            connection
+                .command(&format!("AUTH PLAIN {credentials}"), 2)
+                .await?;
+        }
+
+        connection
+            .command(&format!("MAIL FROM:<{}>", address(&self.from)), 2)
+            .await?;
+        connection
+            .command(&format!("RCPT TO:<{}>", address(&mail.to)), 2)
+            .await?;
+        connection.command("DATA", 3).await?;
+
+        // Lines starting with a dot are escaped, a lone dot ends the data
+        let data = message
+            .split("\r\n")
+            .map(|line| match line.starts_with('.') {
+                true => format!(".{line}"),
+                false => line.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join("\r\n");
+        connection.command(&format!("{data}."), 2).await?;
+        connection.command("QUIT", 2).await?;
+
+        Ok(())
+    }
+
+    async fn upgrade<S>(&self, stream: S) -> anyhow::Result<tokio_rustls::client::TlsStream<S>>
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let tls_config = ClientConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()?
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+
+        let server_name = ServerName::try_from(self.config.host.clone())?;
+        let stream = TlsConnector::from(Arc::new(tls_config))
+            .connect(server_name, stream)
+            .await?;
+
+        Ok(stream)
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    async fn send(&self, mail: Mail) -> AppResult<()> {
+        self.deliver(&mail).await?;
+
+        Ok(())
+    }
+}
+
+trait Stream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> Stream for T {}
+
+struct Connection {
+    stream: BufReader<Box<dyn Stream>>,
+}
+
+impl Connection {
+    fn new(stream: Box<dyn Stream>) -> Self {
+        Self {
+            stream: BufReader::new(stream),
+        }
+    }
+
+    fn into_inner(self) -> Box<dyn Stream> {
+        self.stream.into_inner()
+    }
+
+    /// Send a command and check the reply is of the given class, e.g. `2` for `250`.
+    async fn command(&mut self, command: &str, class: u16) -> anyhow::Result<String> {
+        let stream = self.stream.get_mut();
+        stream.write_all(command.as_bytes()).await?;
+        stream.write_all(b"\r\n").await?;
+        stream.flush().await?;
+
+        self.expect(class).await
+    }
+
+    async fn expect(&mut self, class: u16) -> anyhow::Result<String> {
+        let (code, message) = self.read_reply().await?;
+        if code / 100 != class {
+            bail!("SMTP server replied {code}: {message}");
+        }
+
+        Ok(message)
+    }
+
+    /// Read a possibly multiline reply, e.g. `250-first` followed by `250 last`.
+    async fn read_reply(&mut self) -> anyhow::Result<(u16, String)> {
+        let mut message = String::new();
+        loop {
+            let mut line = String::new();
+            if self.stream.read_line(&mut line).await? == 0 {
+                bail!("SMTP server closed the connection");
+            }
+
+            let line = line.trim_end();
+            let Some(code) = line.get(..3).and_then(|x| x.parse::<u16>().ok()) else {
+                bail!("Malformed SMTP reply: {line}");
+            };
+            message.push_str(line.get(4..).unwrap_or_default());
+
+            if line.as_bytes().get(3) != Some(&b'-') {
+                return Ok((code, message));
+            }
+            message.push('\n');
+        }
+    }
+}
+
+/// The bare address of a mailbox such as `Name <name@example.com>`.
+fn address(mailbox: &str) -> &str {
+    match (mailbox.rfind('<'), mailbox.rfind('>')) {
+        (Some(start), Some(end)) if start < end => &mailbox[start + 1..end],
+        _ => mailbox.trim(),
+    }
+}
@@ -0,0 +1,41 @@
+use chrono::{DateTime, Utc};
+use entity::applications;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// An application registered to obtain tokens from the authorization server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Application {
+    pub id: Uuid,
+    /// Public client id of the application.
+    pub app_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    /// Redirect URIs an authorization request may name, compared exactly.
+    pub redirect_uris: Vec<String>,
+    /// Permission codes the application may ask for.
+    pub scopes: Vec<String>,
+    /// Confidential applications authenticate with their secret, public ones rely on PKCE alone.
+    pub confidential: bool,
+    /// User the application acts as in the client credentials grant.
+    pub owner_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<applications::Model> for Application {
+    fn from(value: applications::Model) -> Self {
+        Self {
+            id: value.id,
+            app_id: value.app_id,
+            name: value.name,
+            description: value.description,
+            redirect_uris: serde_json::from_str(&value.redirect_uris).unwrap_or_default(),
+            scopes: serde_json::from_str(&value.scopes).unwrap_or_default(),
+            confidential: value.confidential,
+            owner_id: value.owner_id,
+            created_at: value.created_at.into(),
+            updated_at: value.updated_at.into(),
+        }
+    }
+}
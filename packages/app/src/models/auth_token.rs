@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 use strum::{Display, EnumString};
 use uuid::Uuid;
 
+use crate::{config::SessionConfig, models::data_scope::DataScopeRule};
+
 pub struct AuthToken {
     pub id: Uuid,
     pub kind: AuthTokenKind,
@@ -15,6 +17,12 @@ pub struct AuthToken {
     pub platform: Option<String>,
     pub agent: Option<String>,
     pub payload: String,
+    pub expired_at: Option<DateTime<Utc>>,
+    /// Last time the token was used, recorded at most once a minute.
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub last_seen_ip: Option<String>,
+    /// Name the user gave the device the session runs on.
+    pub device_name: Option<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -30,32 +38,282 @@ impl From<auth_tokens::Model> for AuthToken {
             ip: model.ip,
             platform: model.platform,
             payload: model.payload,
+            expired_at: model.expired_at.map(Into::into),
+            last_seen_at: model.last_seen_at.map(Into::into),
+            last_seen_ip: model.last_seen_ip,
+            device_name: model.device_name,
             created_at: model.created_at.into(),
             updated_at: model.updated_at.into(),
         }
     }
 }
 
-impl AuthToken {}
+impl AuthToken {
+    /// The moment after which the session is rejected: the idle expiry or the end of its
+    /// absolute lifetime, whichever comes first.
+    pub fn session_expired_at(&self, config: &SessionConfig) -> DateTime<Utc> {
+        let absolute_expired_at = self.absolute_expired_at(config);
+        match self.expired_at {
+            Some(expired_at) => expired_at.min(absolute_expired_at),
+            None => absolute_expired_at,
+        }
+    }
+
+    pub fn absolute_expired_at(&self, config: &SessionConfig) -> DateTime<Utc> {
+        self.created_at + config.absolute_lifetime()
+    }
+
+    pub fn is_session_expired(&self, config: &SessionConfig, now: DateTime<Utc>) -> bool {
+        self.session_expired_at(config) <= now
+    }
+
+    /// Sessions follow the idle and absolute lifetimes, every other kind only its own expiry.
+    pub fn is_expired(&self, config: &SessionConfig, now: DateTime<Utc>) -> bool {
+        match self.kind {
+            AuthTokenKind::Session => self.is_session_expired(config, now),
+            _ => self.expired_at.is_some_and(|x| x <= now),
+        }
+    }
+
+    /// Whether the session is close enough to its idle expiry to be slid forward, and sliding
+    /// would actually push the expiry further out.
+    pub fn should_renew_session(&self, config: &SessionConfig, now: DateTime<Utc>) -> bool {
+        let expired_at = self.session_expired_at(config);
+        let renewed_expired_at = self.renewed_session_expired_at(config, now);
+        expired_at - now < config.renew_threshold() && renewed_expired_at > expired_at
+    }
+
+    pub fn renewed_session_expired_at(
+        &self,
+        config: &SessionConfig,
+        now: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        (now + config.idle_timeout()).min(self.absolute_expired_at(config))
+    }
+}
 
-#[derive(Debug, Serialize, Deserialize, Display, EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "snake_case")]
 pub enum AuthTokenKind {
     Session,
+    Refresh,
+    PersonalAccess,
+    /// Handed out when the password was accepted but a second factor is still due.
+    SecondFactorChallenge,
+    /// Handed out when the password was accepted but has expired and must be replaced first.
+    PasswordChange,
+    /// Mailed to prove the user owns an email address.
+    EmailVerification,
+    /// Mailed to let the user set a new password.
+    PasswordReset,
+    /// Mailed to sign in without a password, as a link or as a code to type in instead, see
+    /// `EmailLoginPayload`.
+    EmailLogin,
+    /// Carried through an external identity provider as the `state` of an OAuth login.
+    OAuthState,
+    /// Handed to an application to redeem for an access token, see `AuthorizationCodePayload`.
+    AuthorizationCode,
+    /// Issued to an application, acting for a user within the scopes the application was
+    /// granted. Only ever sent as a bearer token.
+    OAuthAccess,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-pub struct SessionTokenClaims {
+pub struct AuthTokenClaims {
+    /// Id of the auth token the JWT was minted for.
+    pub jti: Uuid,
     pub sub: Uuid,
     pub exp: i64,
 }
 
+/// Payload of a refresh token. Every refresh token minted for the same session belongs to one
+/// family, which is revoked as a whole when a rotated token is replayed.
 #[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshTokenPayload {
+    pub session_id: Uuid,
+    pub rotated: bool,
+}
+
+/// Payload of an email verification token: the address it was mailed to, so the token is void
+/// once the user changes the address again.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmailVerificationPayload {
+    pub email: String,
+}
+
+/// Payload of an email login token. The code mailed along with the link is only stored as a
+/// digest, and the token is dropped once too many wrong codes were tried.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EmailLoginPayload {
+    /// Address the token was mailed to, the login fails if the user no longer has it verified.
+    pub email: String,
+    pub code_digest: String,
+    pub failed_attempts: u32,
+}
+
+/// Payload of an OAuth state token: what the callback needs to finish the login it belongs to.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OAuthStatePayload {
+    pub provider: String,
+    /// Redirect URI of the authorization request, which the code exchange has to repeat.
+    pub redirect_uri: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    /// User the external identity is linked to, set when the login was started from a session.
+    pub link_user_id: Option<Uuid>,
+}
+
+/// Payload of an authorization code: what the code was issued for, checked again when it is
+/// redeemed.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthorizationCodePayload {
+    pub application_id: Uuid,
+    pub redirect_uri: String,
+    pub scopes: Vec<String>,
+    /// PKCE `S256` challenge the code verifier has to match.
+    pub code_challenge: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SessionTokenPayload {
     pub user_id: Uuid,
     pub permissions: Vec<String>,
     pub roles: Vec<Uuid>,
     pub groups: Vec<Uuid>,
     pub departments: Vec<Uuid>,
+    /// Permission version the payload was resolved at; a stale payload is resolved again.
+    #[serde(default)]
+    pub permission_version: i64,
+    /// Name given to a personal access token.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Permissions a personal access token is restricted to; `None` for a full session.
+    #[serde(default)]
+    pub scope: Option<Vec<String>>,
+    /// Application an OAuth access token was issued to.
+    #[serde(default)]
+    pub application_id: Option<Uuid>,
+    /// User acting as `user_id` in an impersonation session.
+    #[serde(default)]
+    pub impersonator_id: Option<Uuid>,
+    /// Data permissions of the user, deciding which rows of scoped entities they see.
+    #[serde(default)]
+    pub data_scopes: Vec<DataScopeRule>,
+}
+
+impl SessionTokenPayload {
+    /// Drop every permission outside the scope of the token.
+    pub fn restrict_to_scope(&mut self) {
+        if let Some(scope) = &self.scope {
+            self.permissions.retain(|x| scope.contains(x));
+            self.data_scopes.retain(|x| scope.contains(&x.code));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone};
+
+    use super::*;
+
+    fn created_at() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    fn auth_token(kind: AuthTokenKind, expired_at: Option<DateTime<Utc>>) -> AuthToken {
+        AuthToken {
+            id: Uuid::nil(),
+            kind,
+            ref_id: Uuid::nil(),
+            token: String::new(),
+            ip: None,
+            platform: None,
+            agent: None,
+            payload: "{}".to_string(),
+            expired_at,
+            last_seen_at: None,
+            last_seen_ip: None,
+            device_name: None,
+            created_at: created_at(),
+            updated_at: created_at(),
+        }
+    }
+
+    fn session(expired_at: DateTime<Utc>) -> AuthToken {
+        auth_token(AuthTokenKind::Session, Some(expired_at))
+    }
+
+    #[test]
+    fn session_expires_at_idle_expiry() {
+        let config = SessionConfig::default();
+        let expired_at = created_at() + config.idle_timeout();
+        let token = session(expired_at);
+
+        assert!(!token.is_expired(&config, expired_at - Duration::seconds(1)));
+        assert!(token.is_expired(&config, expired_at));
+    }
+
+    #[test]
+    fn session_expires_at_absolute_lifetime() {
+        let config = SessionConfig::default();
+        let absolute_expired_at = created_at() + config.absolute_lifetime();
+        let token = session(absolute_expired_at + Duration::days(1));
+
+        assert_eq!(token.session_expired_at(&config), absolute_expired_at);
+        assert!(!token.is_expired(&config, absolute_expired_at - Duration::seconds(1)));
+        assert!(token.is_expired(&config, absolute_expired_at));
+    }
+
+    #[test]
+    fn other_kinds_only_follow_their_own_expiry() {
+        let config = SessionConfig::default();
+        let after_absolute_lifetime = created_at() + config.absolute_lifetime() * 2;
+
+        let refresh = auth_token(AuthTokenKind::Refresh, None);
+        assert!(!refresh.is_expired(&config, after_absolute_lifetime));
+
+        let expired_at = after_absolute_lifetime + Duration::days(1);
+        let personal_access = auth_token(AuthTokenKind::PersonalAccess, Some(expired_at));
+        assert!(!personal_access.is_expired(&config, after_absolute_lifetime));
+        assert!(!personal_access.is_expired(&config, expired_at - Duration::seconds(1)));
+        assert!(personal_access.is_expired(&config, expired_at));
+    }
+
+    #[test]
+    fn session_renews_within_threshold() {
+        let config = SessionConfig::default();
+        let now = created_at() + Duration::days(3);
+
+        let token = session(now + config.renew_threshold());
+        assert!(!token.should_renew_session(&config, now));
+
+        let token = session(now + config.renew_threshold() - Duration::seconds(1));
+        assert!(token.should_renew_session(&config, now));
+        assert_eq!(
+            token.renewed_session_expired_at(&config, now),
+            now + config.idle_timeout()
+        );
+    }
+
+    #[test]
+    fn session_renewal_stops_at_absolute_lifetime() {
+        let config = SessionConfig::default();
+        let absolute_expired_at = created_at() + config.absolute_lifetime();
+
+        // Renewing would push the expiry past the absolute lifetime, so it is capped
+        let now = absolute_expired_at - config.idle_timeout() + Duration::hours(1);
+        let token = session(now + Duration::hours(1));
+        assert!(token.should_renew_session(&config, now));
+        assert_eq!(
+            token.renewed_session_expired_at(&config, now),
+            absolute_expired_at
+        );
+
+        // Already expiring with the absolute lifetime, renewing would not change anything
+        let now = absolute_expired_at - Duration::hours(1);
+        let token = session(absolute_expired_at);
+        assert!(!token.should_renew_session(&config, now));
+    }
 }
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use shared::enums::DataScope;
+use uuid::Uuid;
+
+use super::permission::Permission;
+
+/// A data permission of a session, kept in its payload so scoped queries don't have to resolve
+/// the permission graph again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataScopeRule {
+    /// Code of the data permission granting the scope.
+    pub code: String,
+    pub scope: DataScope,
+    /// Departments covered by a `CustomDepartments` scope.
+    #[serde(default)]
+    pub department_ids: Vec<Uuid>,
+}
+
+impl DataScopeRule {
+    /// The rule a permission grants, if it is a data permission.
+    pub fn from_permission(permission: &Permission) -> Option<Self> {
+        permission.data_scope.map(|scope| Self {
+            code: permission.code.clone(),
+            scope,
+            department_ids: permission.data_department_ids.clone(),
+        })
+    }
+}
@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use entity::impersonations;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A session someone opened as another user, kept after the session is gone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Impersonation {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub impersonator_id: Uuid,
+    pub impersonator_account: String,
+    pub session_id: Uuid,
+    pub reason: Option<String>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// Unset while the session lasts, and for sessions that simply expired.
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl From<impersonations::Model> for Impersonation {
+    fn from(value: impersonations::Model) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            impersonator_id: value.impersonator_id,
+            impersonator_account: value.impersonator_account,
+            session_id: value.session_id,
+            reason: value.reason,
+            ip: value.ip,
+            platform: value.platform,
+            agent: value.agent,
+            started_at: value.created_at.into(),
+            ended_at: value.ended_at.map(Into::into),
+        }
+    }
+}
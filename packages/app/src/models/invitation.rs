@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use entity::invitations;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Lets someone register, whatever the registration mode, and puts the new user into the roles,
+/// departments and user groups it names. The code itself is only stored as a digest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Invitation {
+    pub id: Uuid,
+    pub note: Option<String>,
+    pub role_ids: Vec<Uuid>,
+    pub department_ids: Vec<Uuid>,
+    pub user_group_ids: Vec<Uuid>,
+    pub created_by: Option<Uuid>,
+    /// Valid forever if unset.
+    pub expired_at: Option<DateTime<Utc>>,
+    /// User who registered with the invitation.
+    pub used_by: Option<Uuid>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<invitations::Model> for Invitation {
+    fn from(value: invitations::Model) -> Self {
+        Self {
+            id: value.id,
+            note: value.note,
+            role_ids: serde_json::from_str(&value.role_ids).unwrap_or_default(),
+            department_ids: serde_json::from_str(&value.department_ids).unwrap_or_default(),
+            user_group_ids: serde_json::from_str(&value.user_group_ids).unwrap_or_default(),
+            created_by: value.created_by,
+            expired_at: value.expired_at.map(Into::into),
+            used_by: value.used_by,
+            used_at: value.used_at.map(Into::into),
+            created_at: value.created_at.into(),
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use entity::login_histories;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginHistory {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub account: String,
+    pub success: bool,
+    pub failure_reason: Option<String>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<login_histories::Model> for LoginHistory {
+    fn from(value: login_histories::Model) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            account: value.account,
+            success: value.success,
+            failure_reason: value.failure_reason,
+            ip: value.ip,
+            platform: value.platform,
+            agent: value.agent,
+            created_at: value.created_at.into(),
+        }
+    }
+}
@@ -1,11 +1,18 @@
 pub mod account_book;
+pub mod application;
 pub mod auth_token;
 pub mod category;
+pub mod data_scope;
 pub mod department;
+pub mod impersonation;
+pub mod invitation;
+pub mod login_history;
+pub mod password_policy;
 pub mod permission;
 pub mod permission_group;
 pub mod role;
 pub mod role_group;
+pub mod third_user;
 pub mod upload;
 pub mod upload_chunk;
 pub mod user;
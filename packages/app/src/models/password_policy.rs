@@ -0,0 +1,23 @@
+use serde::Serialize;
+
+/// A rule of the password policy a new password breaks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "rule",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum PasswordPolicyViolation {
+    TooShort {
+        min_length: usize,
+    },
+    TooFewCharacterClasses {
+        min_character_classes: usize,
+    },
+    /// One of the most commonly used passwords, or on the configured blocklist.
+    Common,
+    ContainsAccount,
+    RecentlyUsed {
+        history_size: usize,
+    },
+}
@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use entity::permissions;
 use serde::Serialize;
+use shared::enums::DataScope;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -10,6 +11,10 @@ pub struct Permission {
     pub code: String,
     pub kind: String,
     pub description: Option<String>,
+    /// Scope granted by a data permission.
+    pub data_scope: Option<DataScope>,
+    /// Departments a data permission with a custom scope covers.
+    pub data_department_ids: Vec<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -21,6 +26,11 @@ impl From<permissions::Model> for Permission {
             code: model.code,
             kind: model.kind,
             description: model.description,
+            data_scope: model.data_scope.and_then(|x| x.parse().ok()),
+            data_department_ids: model
+                .data_department_ids
+                .and_then(|x| serde_json::from_str(&x).ok())
+                .unwrap_or_default(),
             created_at: model.created_at.into(),
             updated_at: model.updated_at.into(),
         }
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+use entity::third_users;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// An external identity linked to a local user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThirdUser {
+    pub user_id: Uuid,
+    /// Name of the identity provider, as configured under `oauth.providers`.
+    pub platform: String,
+    /// Subject of the identity at the provider.
+    pub open_id: String,
+    pub union_id: Option<String>,
+    pub access_token: Option<String>,
+    /// Claims or profile last returned by the provider, as JSON.
+    pub payload: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<third_users::Model> for ThirdUser {
+    fn from(value: third_users::Model) -> Self {
+        Self {
+            user_id: value.user_id,
+            platform: value.platform,
+            open_id: value.open_id,
+            union_id: value.union_id,
+            access_token: value.access_token,
+            payload: value.payload,
+            created_at: value.created_at.into(),
+            updated_at: value.updated_at.into(),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use entity::users;
 use serde::{Deserialize, Serialize};
 use shared::enums::Gender;
@@ -16,10 +16,34 @@ pub struct User {
     pub email_verified: bool,
     pub avatar_url: Option<String>,
     pub gender: Gender,
+    pub birthday: Option<NaiveDate>,
+    pub bio: Option<String>,
+    pub failed_login_attempts: i16,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_login: Option<LastLogin>,
+    /// Base32 TOTP secret, set from enrollment on and only enforced once `totp_enabled`.
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    /// Time step of the last accepted TOTP code, so a code can't be replayed.
+    pub totp_last_used_step: Option<i64>,
+    /// When the password was last set, `None` for users without a password.
+    pub password_changed_at: Option<DateTime<Utc>>,
+    /// Registered with an email domain the registration was restricted to, and not let in
+    /// before that email is verified.
+    pub registration_pending: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Stored as JSON in `users.last_login`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastLogin {
+    pub at: DateTime<Utc>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+}
+
 impl From<users::Model> for User {
     fn from(value: users::Model) -> Self {
         Self {
@@ -33,6 +57,16 @@ impl From<users::Model> for User {
             email_verified: value.email_verified,
             avatar_url: value.avatar_url,
             gender: value.gender.as_str().try_into().unwrap(),
+            birthday: value.birthday,
+            bio: value.bio,
+            failed_login_attempts: value.failed_login_attempts,
+            locked_until: value.locked_until.map(Into::into),
+            last_login: value.last_login.and_then(|x| serde_json::from_str(&x).ok()),
+            totp_secret: value.totp_secret,
+            totp_enabled: value.totp_enabled,
+            totp_last_used_step: value.totp_last_used_step,
+            password_changed_at: value.password_changed_at.map(Into::into),
+            registration_pending: value.registration_pending,
             created_at: value.created_at.into(),
             updated_at: value.updated_at.into(),
         }
@@ -0,0 +1,63 @@
+use entity::application_consents;
+use sea_orm::{
+    ActiveValue::{NotSet, Set},
+    prelude::*,
+    sea_query::OnConflict,
+};
+
+use crate::result::AppResult;
+
+use super::ApplicationService;
+
+impl ApplicationService {
+    /// Scopes the user has already allowed the application.
+    pub async fn query_consented_scopes(
+        &self,
+        user_id: Uuid,
+        application_id: Uuid,
+    ) -> AppResult<Vec<String>> {
+        let consent = application_consents::Entity::find_by_id((user_id, application_id))
+            .one(&self.conn)
+            .await?;
+
+        Ok(consent
+            .and_then(|x| serde_json::from_str(&x.scopes).ok())
+            .unwrap_or_default())
+    }
+
+    /// Remember that the user allowed the application `scopes`, on top of what was allowed
+    /// before.
+    pub async fn grant_consent(
+        &self,
+        user_id: Uuid,
+        application_id: Uuid,
+        scopes: &[String],
+    ) -> AppResult<()> {
+        let mut consented = self.query_consented_scopes(user_id, application_id).await?;
+        consented.extend_from_slice(scopes);
+        consented.sort();
+        consented.dedup();
+
+        let active_model = application_consents::ActiveModel {
+            user_id: Set(user_id),
+            application_id: Set(application_id),
+            scopes: Set(serde_json::to_string(&consented).unwrap()),
+            is_deleted: NotSet,
+            created_at: NotSet,
+            updated_at: NotSet,
+        };
+        application_consents::Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([
+                    application_consents::Column::UserId,
+                    application_consents::Column::ApplicationId,
+                ])
+                .update_column(application_consents::Column::Scopes)
+                .to_owned(),
+            )
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+}
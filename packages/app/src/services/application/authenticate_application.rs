@@ -0,0 +1,35 @@
+use entity::applications;
+use sea_orm::ColumnTrait;
+use uuid::Uuid;
+
+use crate::{error::AppException, models::application::Application, result::AppResult};
+
+use super::{ApplicationService, digest_client_secret};
+
+impl ApplicationService {
+    /// Authenticate a client by its id and secret. Public applications have no secret, so
+    /// whatever is sent for one is ignored; anything else fails with `InvalidClient`.
+    pub async fn authenticate_application(
+        &self,
+        app_id: Uuid,
+        app_secret: Option<&str>,
+    ) -> AppResult<Application> {
+        let model = self
+            .crud
+            .find_one_by_condition(applications::Column::AppId.eq(app_id))
+            .await?;
+        let Some(model) = model else {
+            return Err(AppException::InvalidClient.into());
+        };
+
+        if model.confidential {
+            let secret_valid =
+                app_secret.is_some_and(|x| digest_client_secret(x) == model.secret_digest);
+            if !secret_valid {
+                return Err(AppException::InvalidClient.into());
+            }
+        }
+
+        Ok(model.into())
+    }
+}
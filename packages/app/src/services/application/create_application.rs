@@ -0,0 +1,65 @@
+use entity::applications;
+use sea_orm::ActiveValue::Set;
+use shared::utils::generate_random_token;
+use uuid::Uuid;
+
+use crate::result::AppResult;
+
+use super::{ApplicationService, derive_signing_key, digest_client_secret};
+
+#[derive(Debug, Default)]
+pub struct CreateApplicationParams {
+    pub name: String,
+    pub description: Option<String>,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+    pub confidential: bool,
+    pub owner_id: Option<Uuid>,
+}
+
+/// Credentials of a newly registered application. The secret and signing key are only ever
+/// shown here.
+pub struct ApplicationCredentials {
+    pub id: Uuid,
+    pub app_id: Uuid,
+    pub app_secret: Option<String>,
+    /// Key to sign requests with, see `verify_signed_request`.
+    pub signing_key: Option<String>,
+}
+
+impl ApplicationService {
+    pub async fn create_application(
+        &self,
+        params: CreateApplicationParams,
+    ) -> AppResult<ApplicationCredentials> {
+        self.validate_application(&params.redirect_uris, &params.scopes)
+            .await?;
+
+        let app_secret = params.confidential.then(|| generate_random_token(32));
+        let secret_digest = app_secret.as_deref().map(digest_client_secret);
+        let signing_key = secret_digest
+            .as_deref()
+            .map(|x| derive_signing_key(&self.app.config.jwt.secret, x));
+
+        let active_model = applications::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            app_id: Set(Uuid::new_v4()),
+            secret_digest: Set(secret_digest.unwrap_or_default()),
+            name: Set(params.name),
+            description: Set(params.description),
+            redirect_uris: Set(serde_json::to_string(&params.redirect_uris).unwrap()),
+            scopes: Set(serde_json::to_string(&params.scopes).unwrap()),
+            confidential: Set(params.confidential),
+            owner_id: Set(params.owner_id),
+            ..Default::default()
+        };
+        let model = self.crud.create(active_model).await?;
+
+        Ok(ApplicationCredentials {
+            id: model.id,
+            app_id: model.app_id,
+            app_secret,
+            signing_key,
+        })
+    }
+}
@@ -0,0 +1,37 @@
+use entity::{application_consents, application_nonces, applications};
+use sea_orm::{TransactionTrait, prelude::*};
+
+use crate::{result::AppResult, services::auth_token::AuthTokenService};
+
+use super::ApplicationService;
+
+#[derive(Debug)]
+pub struct DeleteApplicationsParams(pub Vec<Uuid>);
+
+impl ApplicationService {
+    /// Delete applications together with the consents given to them and the tokens they hold.
+    pub async fn delete_applications(&self, params: DeleteApplicationsParams) -> AppResult<()> {
+        let txn = self.conn.begin().await?;
+
+        application_consents::Entity::delete_many()
+            .filter(application_consents::Column::ApplicationId.is_in(params.0.clone()))
+            .exec(&txn)
+            .await?;
+        application_nonces::Entity::delete_many()
+            .filter(application_nonces::Column::ApplicationId.is_in(params.0.clone()))
+            .exec(&txn)
+            .await?;
+        applications::Entity::delete_many()
+            .filter(applications::Column::Id.is_in(params.0.clone()))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+
+        AuthTokenService::new(self.app.clone())
+            .delete_auth_tokens_by_application_id(&params.0)
+            .await?;
+
+        Ok(())
+    }
+}
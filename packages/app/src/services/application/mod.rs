@@ -0,0 +1,61 @@
+use entity::{applications, permissions};
+use reqwest::Url;
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+
+use crate::{error::AppException, impl_service, result::AppResult};
+
+pub mod application_consent;
+pub mod authenticate_application;
+pub mod create_application;
+pub mod delete_applications;
+pub mod query_applications;
+pub mod update_application;
+pub mod verify_signature;
+
+impl_service!(ApplicationService, applications::Entity);
+
+/// Client secrets are random, so a plain digest is enough to keep them from being read back.
+pub(crate) fn digest_client_secret(secret: &str) -> String {
+    shared::utils::hash_blake3(secret.as_bytes())
+}
+
+/// Key a confidential application signs requests with, handed out along with its secret. It is
+/// derived from the secret digest with `server_key` instead of being stored, so the
+/// `applications` table alone is not enough to sign requests, and it is replaced along with the
+/// secret.
+pub(crate) fn derive_signing_key(server_key: &str, secret_digest: &str) -> String {
+    shared::utils::hmac_sha256(
+        server_key.as_bytes(),
+        format!("app-signature:{secret_digest}").as_bytes(),
+    )
+}
+
+impl ApplicationService {
+    /// Redirect URIs must be absolute and without a fragment, and every scope must be the code of
+    /// an existing permission.
+    async fn validate_application(
+        &self,
+        redirect_uris: &[String],
+        scopes: &[String],
+    ) -> AppResult<()> {
+        for redirect_uri in redirect_uris {
+            let valid = Url::parse(redirect_uri).is_ok_and(|x| x.fragment().is_none());
+            if !valid {
+                return Err(AppException::InvalidRedirectUri.into());
+            }
+        }
+
+        let known = permissions::Entity::find()
+            .filter(permissions::Column::Code.is_in(scopes))
+            .count(&self.conn)
+            .await?;
+        let mut distinct = scopes.to_vec();
+        distinct.sort();
+        distinct.dedup();
+        if known != distinct.len() as u64 {
+            return Err(AppException::InvalidScope.into());
+        }
+
+        Ok(())
+    }
+}
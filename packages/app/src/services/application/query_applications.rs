@@ -0,0 +1,58 @@
+use entity::applications;
+use sea_orm::{Condition, prelude::*};
+use uuid::Uuid;
+
+use crate::{
+    error::AppException, models::application::Application, result::AppResult,
+    utils::query::PageableQuery,
+};
+
+use super::ApplicationService;
+
+pub struct FilterApplicationsParams {
+    pub name: Option<String>,
+    pub owner_id: Option<Uuid>,
+}
+
+impl From<FilterApplicationsParams> for Condition {
+    fn from(value: FilterApplicationsParams) -> Self {
+        Condition::all()
+            .add_option(value.name.map(|x| applications::Column::Name.like(x)))
+            .add_option(value.owner_id.map(|x| applications::Column::OwnerId.eq(x)))
+    }
+}
+
+impl ApplicationService {
+    pub async fn query_applications_by_page(
+        &self,
+        params: PageableQuery<FilterApplicationsParams>,
+    ) -> AppResult<(Vec<Application>, i64)> {
+        let (records, total) = self.crud.find_by_condition_with_count(params).await?;
+        let records = records.into_iter().map(Application::from).collect();
+
+        Ok((records, total))
+    }
+
+    pub async fn query_application_by_id(&self, id: Uuid) -> AppResult<Application> {
+        let application = self.crud.find_by_id(id).await?;
+
+        let Some(application) = application else {
+            return Err(AppException::ApplicationNotFound.into());
+        };
+
+        Ok(application.into())
+    }
+
+    pub async fn query_application_by_app_id(&self, app_id: Uuid) -> AppResult<Application> {
+        let application = self
+            .crud
+            .find_one_by_condition(applications::Column::AppId.eq(app_id))
+            .await?;
+
+        let Some(application) = application else {
+            return Err(AppException::ApplicationNotFound.into());
+        };
+
+        Ok(application.into())
+    }
+}
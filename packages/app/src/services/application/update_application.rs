@@ -0,0 +1,83 @@
+use entity::applications;
+use sea_orm::{ActiveValue::Set, IntoActiveModel, prelude::*};
+use shared::utils::generate_random_token;
+
+use crate::{error::AppException, result::AppResult};
+
+use super::{
+    ApplicationService, create_application::ApplicationCredentials, derive_signing_key,
+    digest_client_secret,
+};
+
+#[derive(Debug, Default)]
+pub struct UpdateApplicationParams {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub redirect_uris: Option<Vec<String>>,
+    pub scopes: Option<Vec<String>>,
+    pub owner_id: Option<Uuid>,
+}
+
+impl ApplicationService {
+    pub async fn update_application(&self, params: UpdateApplicationParams) -> AppResult<()> {
+        let Some(model) = self.crud.find_by_id(params.id).await? else {
+            return Err(AppException::ApplicationNotFound.into());
+        };
+
+        let redirect_uris = params
+            .redirect_uris
+            .unwrap_or_else(|| serde_json::from_str(&model.redirect_uris).unwrap_or_default());
+        let scopes = params
+            .scopes
+            .unwrap_or_else(|| serde_json::from_str(&model.scopes).unwrap_or_default());
+        self.validate_application(&redirect_uris, &scopes).await?;
+
+        let mut active_model = model.into_active_model();
+        if let Some(name) = params.name {
+            active_model.name = Set(name);
+        }
+        if let Some(description) = params.description {
+            active_model.description = Set(Some(description));
+        }
+        if let Some(owner_id) = params.owner_id {
+            active_model.owner_id = Set(Some(owner_id));
+        }
+        active_model.redirect_uris = Set(serde_json::to_string(&redirect_uris).unwrap());
+        active_model.scopes = Set(serde_json::to_string(&scopes).unwrap());
+
+        self.crud.update(active_model).await?;
+
+        Ok(())
+    }
+
+    /// Replace the secret of a confidential application, the old one and the signing key derived
+    /// from it stop working at once.
+    pub async fn reset_application_secret(&self, id: Uuid) -> AppResult<ApplicationCredentials> {
+        let Some(model) = self.crud.find_by_id(id).await? else {
+            return Err(AppException::ApplicationNotFound.into());
+        };
+        if !model.confidential {
+            return Err(AppException::UnauthorizedClient.into());
+        }
+
+        let app_secret = generate_random_token(32);
+        let secret_digest = digest_client_secret(&app_secret);
+        let signing_key = derive_signing_key(&self.app.config.jwt.secret, &secret_digest);
+        applications::Entity::update_many()
+            .col_expr(
+                applications::Column::SecretDigest,
+                Expr::value(secret_digest),
+            )
+            .filter(applications::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(ApplicationCredentials {
+            id: model.id,
+            app_id: model.app_id,
+            app_secret: Some(app_secret),
+            signing_key: Some(signing_key),
+        })
+    }
+}
@@ -26,6 +26,8 @@ impl AuthService {
         .exec(&self.conn)
         .await?;
 
+        self.bump_permission_version().await?;
+
         Ok(())
     }
 }
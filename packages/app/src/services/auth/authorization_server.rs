@@ -0,0 +1,323 @@
+use chrono::Utc;
+use reqwest::Url;
+use shared::utils::pkce_challenge;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppException},
+    models::{
+        application::Application,
+        auth_token::{AuthToken, AuthTokenKind, AuthorizationCodePayload, SessionTokenPayload},
+    },
+    result::AppResult,
+    services::{
+        application::ApplicationService,
+        auth::AuthService,
+        auth_token::{
+            AuthTokenService,
+            create_auth_token::{CreateOAuthAccessTokenParams, CreateOneTimeTokenParams},
+        },
+    },
+};
+
+/// An authorization request of an application, on behalf of the signed in user.
+pub struct AuthorizationParams {
+    pub user_id: Uuid,
+    pub response_type: String,
+    pub client_id: Uuid,
+    pub redirect_uri: String,
+    /// Space separated permission codes, all the application may ask for if missing.
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: String,
+    pub code_challenge_method: String,
+}
+
+/// What the user is asked to agree to.
+pub struct AuthorizationPrompt {
+    pub application: Application,
+    pub scopes: Vec<String>,
+    /// Whether the user already allowed every one of the scopes before.
+    pub consented: bool,
+}
+
+/// How a client authenticates at the token, introspection and revocation endpoints.
+pub struct ClientCredentials {
+    pub client_id: Uuid,
+    pub client_secret: Option<String>,
+}
+
+pub struct ExchangeAuthorizationCodeParams {
+    pub client: ClientCredentials,
+    pub code: String,
+    pub redirect_uri: String,
+    pub code_verifier: String,
+}
+
+pub struct OAuthAccessToken {
+    pub access_token: AuthToken,
+    pub scopes: Vec<String>,
+}
+
+/// An access token that is still valid, with its current grants.
+pub struct OAuthTokenIntrospection {
+    pub access_token: AuthToken,
+    pub payload: SessionTokenPayload,
+}
+
+impl AuthService {
+    /// Check an authorization request and tell what it asks for. Fails before anything is
+    /// redirected if the client or redirect URI is unknown.
+    pub async fn query_authorization_prompt(
+        &self,
+        params: &AuthorizationParams,
+    ) -> AppResult<AuthorizationPrompt> {
+        let (application, scopes) = self.validate_authorization_request(params).await?;
+
+        let consented_scopes = ApplicationService::new(self.app.clone())
+            .query_consented_scopes(params.user_id, application.id)
+            .await?;
+        let consented = scopes.iter().all(|x| consented_scopes.contains(x));
+
+        Ok(AuthorizationPrompt {
+            application,
+            scopes,
+            consented,
+        })
+    }
+
+    /// Answer an authorization request with the URI to send the user back to: carrying an
+    /// authorization code if the user approved, `access_denied` otherwise.
+    pub async fn authorize_application(
+        &self,
+        params: AuthorizationParams,
+        approved: bool,
+    ) -> AppResult<String> {
+        let (application, scopes) = self.validate_authorization_request(&params).await?;
+
+        let mut redirect_uri = Url::parse(&params.redirect_uri).map_err(anyhow::Error::from)?;
+        if !approved {
+            redirect_uri
+                .query_pairs_mut()
+                .append_pair("error", "access_denied");
+        } else {
+            ApplicationService::new(self.app.clone())
+                .grant_consent(params.user_id, application.id, &scopes)
+                .await?;
+
+            let payload = AuthorizationCodePayload {
+                application_id: application.id,
+                redirect_uri: params.redirect_uri,
+                scopes,
+                code_challenge: params.code_challenge,
+            };
+            let code = AuthTokenService::new(self.app.clone())
+                .create_one_time_token(CreateOneTimeTokenParams {
+                    kind: AuthTokenKind::AuthorizationCode,
+                    user_id: params.user_id,
+                    payload: serde_json::to_string(&payload).unwrap(),
+                    expired_at: Utc::now()
+                        + self
+                            .app
+                            .config
+                            .authorization_server
+                            .authorization_code_lifetime(),
+                })
+                .await?;
+
+            redirect_uri
+                .query_pairs_mut()
+                .append_pair("code", &code.token);
+        }
+        if let Some(state) = &params.state {
+            redirect_uri.query_pairs_mut().append_pair("state", state);
+        }
+
+        Ok(redirect_uri.into())
+    }
+
+    /// The `authorization_code` grant. A code is spent by the first attempt to redeem it, even a
+    /// failed one.
+    pub async fn exchange_authorization_code(
+        &self,
+        params: ExchangeAuthorizationCodeParams,
+    ) -> AppResult<OAuthAccessToken> {
+        let application = self.authenticate_client(&params.client).await?;
+
+        let Some(code) = AuthTokenService::new(self.app.clone())
+            .take_one_time_token(&params.code, AuthTokenKind::AuthorizationCode)
+            .await?
+        else {
+            return Err(AppException::InvalidGrant.into());
+        };
+        let payload: AuthorizationCodePayload =
+            serde_json::from_str(&code.payload).map_err(anyhow::Error::from)?;
+
+        let valid = payload.application_id == application.id
+            && payload.redirect_uri == params.redirect_uri
+            && payload.code_challenge == pkce_challenge(&params.code_verifier);
+        if !valid {
+            return Err(AppException::InvalidGrant.into());
+        }
+
+        self.issue_oauth_access_token(&application, code.ref_id, payload.scopes)
+            .await
+    }
+
+    /// The `client_credentials` grant: a confidential application acting as its owner.
+    pub async fn issue_client_credentials_token(
+        &self,
+        client: ClientCredentials,
+        scope: Option<&str>,
+    ) -> AppResult<OAuthAccessToken> {
+        let application = self.authenticate_client(&client).await?;
+        let Some(owner_id) = application.owner_id.filter(|_| application.confidential) else {
+            return Err(AppException::UnauthorizedClient.into());
+        };
+        let scopes = parse_scope(scope, &application)?;
+
+        self.issue_oauth_access_token(&application, owner_id, scopes)
+            .await
+    }
+
+    /// Look up an access token for the application it was issued to. Tokens of other
+    /// applications, expired or unknown ones all come back as `None`.
+    pub async fn introspect_oauth_token(
+        &self,
+        client: ClientCredentials,
+        token: &str,
+    ) -> AppResult<Option<OAuthTokenIntrospection>> {
+        let application = self.authenticate_client(&client).await?;
+        if !application.confidential {
+            return Err(AppException::UnauthorizedClient.into());
+        }
+
+        let Some(access_token) = self.query_oauth_token(&application, token).await? else {
+            return Ok(None);
+        };
+        let Some(payload) = self.query_session_payload(&access_token).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(OAuthTokenIntrospection {
+            access_token,
+            payload,
+        }))
+    }
+
+    /// Revoke an access token of the application. Unknown tokens are ignored.
+    pub async fn revoke_oauth_token(
+        &self,
+        client: ClientCredentials,
+        token: &str,
+    ) -> AppResult<()> {
+        let application = self.authenticate_client(&client).await?;
+
+        if let Some(access_token) = self.query_oauth_token(&application, token).await? {
+            AuthTokenService::new(self.app.clone())
+                .delete_auth_token_by_id(access_token.id)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn authenticate_client(&self, client: &ClientCredentials) -> AppResult<Application> {
+        ApplicationService::new(self.app.clone())
+            .authenticate_application(client.client_id, client.client_secret.as_deref())
+            .await
+    }
+
+    async fn validate_authorization_request(
+        &self,
+        params: &AuthorizationParams,
+    ) -> AppResult<(Application, Vec<String>)> {
+        let application = match ApplicationService::new(self.app.clone())
+            .query_application_by_app_id(params.client_id)
+            .await
+        {
+            Ok(application) => application,
+            Err(AppError::Exception(AppException::ApplicationNotFound)) => {
+                return Err(AppException::InvalidClient.into());
+            }
+            Err(err) => return Err(err),
+        };
+
+        if !application.redirect_uris.contains(&params.redirect_uri) {
+            return Err(AppException::InvalidRedirectUri.into());
+        }
+        if params.response_type != "code" {
+            return Err(AppException::UnsupportedResponseType.into());
+        }
+        // PKCE is required of every client, confidential or not
+        if params.code_challenge_method != "S256" || params.code_challenge.is_empty() {
+            return Err(AppException::InvalidRequest.into());
+        }
+
+        let scopes = parse_scope(params.scope.as_deref(), &application)?;
+
+        Ok((application, scopes))
+    }
+
+    async fn issue_oauth_access_token(
+        &self,
+        application: &Application,
+        user_id: Uuid,
+        scopes: Vec<String>,
+    ) -> AppResult<OAuthAccessToken> {
+        let mut payload = self.build_session_payload(user_id).await?;
+        payload.scope = Some(scopes.clone());
+        payload.application_id = Some(application.id);
+        payload.restrict_to_scope();
+
+        let expired_at = Utc::now() + self.app.config.authorization_server.access_token_lifetime();
+        let access_token = AuthTokenService::new(self.app.clone())
+            .create_oauth_access_token(CreateOAuthAccessTokenParams {
+                expired_at,
+                payload,
+            })
+            .await?;
+
+        Ok(OAuthAccessToken {
+            access_token,
+            scopes,
+        })
+    }
+
+    async fn query_oauth_token(
+        &self,
+        application: &Application,
+        token: &str,
+    ) -> AppResult<Option<AuthToken>> {
+        let access_token = AuthTokenService::new(self.app.clone())
+            .query_auth_token_by_jwt(token)
+            .await?;
+
+        Ok(access_token.filter(|x| {
+            x.kind == AuthTokenKind::OAuthAccess
+                && !x.is_expired(&self.app.config.session, Utc::now())
+                && serde_json::from_str::<SessionTokenPayload>(&x.payload)
+                    .is_ok_and(|payload| payload.application_id == Some(application.id))
+        }))
+    }
+}
+
+/// Scopes asked for, each of which the application must be allowed. No scope at all asks for
+/// everything the application is allowed.
+fn parse_scope(scope: Option<&str>, application: &Application) -> AppResult<Vec<String>> {
+    let mut scopes = match scope.map(str::split_whitespace) {
+        Some(scopes) => scopes.map(String::from).collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+    if scopes.is_empty() {
+        return Ok(application.scopes.clone());
+    }
+    scopes.sort();
+    scopes.dedup();
+
+    if scopes.iter().any(|x| !application.scopes.contains(x)) {
+        return Err(AppException::InvalidScope.into());
+    }
+
+    Ok(scopes)
+}
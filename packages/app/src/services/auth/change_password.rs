@@ -0,0 +1,109 @@
+use chrono::Utc;
+use shared::utils::verify_password;
+use uuid::Uuid;
+
+use crate::{
+    error::AppException,
+    models::auth_token::AuthTokenKind,
+    result::AppResult,
+    services::{
+        auth::{AuthService, login::LoginOutcome},
+        auth_token::AuthTokenService,
+        login_history::create_login_history::CreateLoginHistoryParams,
+        user::UserService,
+    },
+};
+
+pub struct ChangePasswordParams {
+    pub user_id: Uuid,
+    /// Session the change is made from, kept when other sessions are revoked.
+    pub session_id: Uuid,
+    pub old_password: String,
+    pub new_password: String,
+    pub revoke_other_sessions: bool,
+}
+
+pub struct ChangeExpiredPasswordParams {
+    pub challenge_token: String,
+    pub new_password: String,
+}
+
+impl AuthService {
+    pub async fn change_password(&self, params: ChangePasswordParams) -> AppResult<()> {
+        let user_service = UserService::new(self.app.clone());
+        let user = user_service.query_user_by_id(params.user_id).await?;
+
+        // A wrong old password counts like a failed login, so a hijacked session can't be used
+        // to guess the password
+        let password_digest = user.password_digest.as_deref().unwrap_or_default();
+        if !verify_password(
+            &params.old_password,
+            password_digest,
+            self.app.config.password_hash.pepper(),
+        ) {
+            self.count_failed_login(user.id).await?;
+            return Err(AppException::InvalidCredentials.into());
+        }
+
+        user_service
+            .update_password(user.id, &params.new_password)
+            .await?;
+
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+        auth_token_service
+            .delete_auth_tokens_by_ref_id_and_kind(user.id, AuthTokenKind::PasswordReset)
+            .await?;
+        if params.revoke_other_sessions {
+            self.logout_others(user.id, params.session_id).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Continue a login that `login` answered with `PasswordChangeRequired`. A new password the
+    /// policy refuses leaves the challenge usable, so the user can try another one.
+    pub async fn change_expired_password(
+        &self,
+        params: ChangeExpiredPasswordParams,
+    ) -> AppResult<LoginOutcome> {
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+
+        let Some(challenge_token) = auth_token_service
+            .query_auth_token_by_jwt(&params.challenge_token)
+            .await?
+        else {
+            return Err(AppException::AuthenticationFailed.into());
+        };
+        if challenge_token.kind != AuthTokenKind::PasswordChange
+            || challenge_token.is_expired(&self.app.config.session, Utc::now())
+        {
+            return Err(AppException::AuthenticationFailed.into());
+        }
+
+        let user_service = UserService::new(self.app.clone());
+        user_service
+            .update_password(challenge_token.ref_id, &params.new_password)
+            .await?;
+        auth_token_service
+            .delete_auth_token_by_id(challenge_token.id)
+            .await?;
+
+        let user = user_service
+            .query_user_by_id(challenge_token.ref_id)
+            .await?;
+        let attempt = CreateLoginHistoryParams {
+            user_id: Some(user.id),
+            account: user.account.clone(),
+            ip: challenge_token.ip,
+            platform: challenge_token.platform,
+            agent: challenge_token.agent,
+            ..Default::default()
+        };
+
+        if user.locked_until.is_some_and(|x| x > Utc::now()) {
+            return self.fail_login(attempt, AppException::AccountLocked).await;
+        }
+
+        self.pass_first_factor(user, attempt).await
+    }
+}
@@ -0,0 +1,118 @@
+use entity::permission_denials;
+use sea_orm::{ActiveValue::Set, prelude::*, sea_query::OnConflict};
+use shared::enums::PermissionSubjectKind;
+use uuid::Uuid;
+
+use crate::{
+    models::permission::Permission,
+    result::AppResult,
+    services::{
+        department::DepartmentService, permission::PermissionService, role::RoleService,
+        role_group::RoleGroupService, user::UserService, user_group::UserGroupService,
+    },
+};
+
+use super::AuthService;
+
+pub struct PermissionDenialsParams {
+    pub subject_kind: PermissionSubjectKind,
+    pub subject_id: Uuid,
+    pub permission_id_list: Vec<Uuid>,
+}
+
+impl AuthService {
+    /// Deny permissions to a subject, taking precedence over any grant of them to the users
+    /// reaching it. Denying a permission twice is a no-op.
+    pub async fn deny_permissions(&self, params: PermissionDenialsParams) -> AppResult<()> {
+        self.assert_subject_exists(params.subject_kind, params.subject_id)
+            .await?;
+        if params.permission_id_list.is_empty() {
+            return Ok(());
+        }
+
+        permission_denials::Entity::insert_many(params.permission_id_list.iter().map(
+            |permission_id| permission_denials::ActiveModel {
+                permission_id: Set(*permission_id),
+                subject_kind: Set(params.subject_kind.to_string()),
+                subject_id: Set(params.subject_id),
+                ..Default::default()
+            },
+        ))
+        .on_conflict(
+            OnConflict::columns([
+                permission_denials::Column::PermissionId,
+                permission_denials::Column::SubjectKind,
+                permission_denials::Column::SubjectId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .do_nothing()
+        .exec(&self.conn)
+        .await?;
+
+        self.bump_permission_version().await?;
+
+        Ok(())
+    }
+
+    /// Lift denials of permissions to a subject, so grants of them apply again.
+    pub async fn delete_permission_denials(
+        &self,
+        params: PermissionDenialsParams,
+    ) -> AppResult<()> {
+        permission_denials::Entity::delete_many()
+            .filter(permission_denials::Column::SubjectKind.eq(params.subject_kind.to_string()))
+            .filter(permission_denials::Column::SubjectId.eq(params.subject_id))
+            .filter(permission_denials::Column::PermissionId.is_in(params.permission_id_list))
+            .exec(&self.conn)
+            .await?;
+
+        self.bump_permission_version().await?;
+
+        Ok(())
+    }
+
+    pub async fn query_permission_denials(
+        &self,
+        subject_kind: PermissionSubjectKind,
+        subject_id: Uuid,
+    ) -> AppResult<Vec<Permission>> {
+        PermissionService::new(self.app.clone())
+            .query_denied_permissions(subject_kind, subject_id)
+            .await
+    }
+
+    async fn assert_subject_exists(
+        &self,
+        subject_kind: PermissionSubjectKind,
+        subject_id: Uuid,
+    ) -> AppResult<()> {
+        let app = self.app.clone();
+        match subject_kind {
+            PermissionSubjectKind::User => {
+                UserService::new(app).query_user_by_id(subject_id).await?;
+            }
+            PermissionSubjectKind::UserGroup => {
+                UserGroupService::new(app)
+                    .query_user_group_by_id(subject_id)
+                    .await?;
+            }
+            PermissionSubjectKind::Department => {
+                DepartmentService::new(app)
+                    .query_department_by_id(subject_id)
+                    .await?;
+            }
+            PermissionSubjectKind::Role => {
+                RoleService::new(app).query_role_by_id(subject_id).await?;
+            }
+            PermissionSubjectKind::RoleGroup => {
+                RoleGroupService::new(app)
+                    .query_role_group_by_id(subject_id)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use entity::permissions;
+use sea_orm::{ConnectionTrait, EntityTrait, Statement};
+use uuid::Uuid;
+
+use crate::{models::permission::Permission, result::AppResult, services::user::UserService};
+
+use super::AuthService;
+
+/// Every permission a user holds, resolved in one statement. Follows the same paths as
+/// `query_permission_tree`: the user's departments, user groups and their ancestors, role
+/// groups and permission groups with their descendants, and the roles reached through any of
+/// them. A permission denied to any of the subjects reached is left out, whatever grants it.
+const EFFECTIVE_PERMISSIONS_SQL: &str = r#"
+    WITH RECURSIVE
+    user_departments AS (
+        SELECT d.id FROM departments d
+        JOIN relation_users_departments r ON r.department_id = d.id
+        WHERE r.user_id = $1
+    ),
+    user_groups_tree AS (
+        SELECT g.id, g.parent_id FROM user_groups g
+        JOIN relation_users_user_groups r ON r.user_group_id = g.id
+        WHERE r.user_id = $1
+        UNION
+        SELECT g.id, g.parent_id FROM user_groups g
+        JOIN user_groups_tree t ON g.id = t.parent_id
+    ),
+    role_groups_tree AS (
+        SELECT g.id FROM role_groups g
+        WHERE g.id IN (
+            SELECT role_group_id FROM relation_role_groups_users
+            WHERE user_id = $1
+            UNION
+            SELECT role_group_id FROM relation_role_groups_departments
+            WHERE department_id IN (SELECT id FROM user_departments)
+            UNION
+            SELECT role_group_id FROM relation_role_groups_user_groups
+            WHERE user_group_id IN (SELECT id FROM user_groups_tree)
+        )
+        UNION
+        SELECT g.id FROM role_groups g
+        JOIN role_groups_tree t ON g.parent_id = t.id
+    ),
+    user_roles AS (
+        SELECT r.id FROM roles r
+        WHERE r.id IN (
+            SELECT role_id FROM relation_roles_users
+            WHERE user_id = $1
+            UNION
+            SELECT role_id FROM relation_roles_departments
+            WHERE department_id IN (SELECT id FROM user_departments)
+            UNION
+            SELECT role_id FROM relation_roles_user_groups
+            WHERE user_group_id IN (SELECT id FROM user_groups_tree)
+            UNION
+            SELECT role_id FROM relation_roles_role_groups
+            WHERE role_group_id IN (SELECT id FROM role_groups_tree)
+        )
+    ),
+    permission_groups_tree AS (
+        SELECT g.id FROM permission_groups g
+        WHERE g.id IN (
+            SELECT permission_group_id FROM relation_permission_groups_users
+            WHERE user_id = $1
+            UNION
+            SELECT permission_group_id FROM relation_permission_groups_departments
+            WHERE department_id IN (SELECT id FROM user_departments)
+            UNION
+            SELECT permission_group_id FROM relation_permission_groups_user_groups
+            WHERE user_group_id IN (SELECT id FROM user_groups_tree)
+            UNION
+            SELECT permission_group_id FROM relation_permission_groups_roles
+            WHERE role_id IN (SELECT id FROM user_roles)
+        )
+        UNION
+        SELECT g.id FROM permission_groups g
+        JOIN permission_groups_tree t ON g.parent_id = t.id
+    )
+    SELECT p.* FROM permissions p
+    WHERE p.id IN (
+        SELECT permission_id FROM relation_permissions_users
+        WHERE user_id = $1
+        UNION
+        SELECT permission_id FROM relation_permissions_departments
+        WHERE department_id IN (SELECT id FROM user_departments)
+        UNION
+        SELECT permission_id FROM relation_permissions_user_groups
+        WHERE user_group_id IN (SELECT id FROM user_groups_tree)
+        UNION
+        SELECT permission_id FROM relation_permissions_roles
+        WHERE role_id IN (SELECT id FROM user_roles)
+        UNION
+        SELECT permission_id FROM relation_permissions_permission_groups
+        WHERE permission_group_id IN (SELECT id FROM permission_groups_tree)
+    )
+    AND p.id NOT IN (
+        SELECT permission_id FROM permission_denials
+        WHERE (subject_kind = 'user' AND subject_id = $1)
+        OR (subject_kind = 'department' AND subject_id IN (SELECT id FROM user_departments))
+        OR (subject_kind = 'user_group' AND subject_id IN (SELECT id FROM user_groups_tree))
+        OR (subject_kind = 'role' AND subject_id IN (SELECT id FROM user_roles))
+        OR (subject_kind = 'role_group' AND subject_id IN (SELECT id FROM role_groups_tree))
+    )
+"#;
+
+/// Users whose effective permissions are cached at most, the least recently used are dropped
+/// beyond that.
+pub const PERMISSION_CACHE_CAPACITY: usize = 10_000;
+
+/// Effective permissions of users, kept for as long as the permission version they were
+/// resolved at is current. Bumping the version drops every entry at the next write.
+pub struct PermissionCache {
+    capacity: usize,
+    inner: Mutex<PermissionCacheEntries>,
+}
+
+#[derive(Default)]
+struct PermissionCacheEntries {
+    permission_version: i64,
+    users: HashMap<Uuid, CachedPermissions>,
+    /// Counts lookups, to tell which entry was used least recently.
+    clock: u64,
+}
+
+struct CachedPermissions {
+    permissions: Arc<Vec<Permission>>,
+    last_used: u64,
+}
+
+impl Default for PermissionCache {
+    fn default() -> Self {
+        Self::new(PERMISSION_CACHE_CAPACITY)
+    }
+}
+
+impl PermissionCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::default(),
+        }
+    }
+
+    pub fn get(&self, user_id: Uuid, permission_version: i64) -> Option<Arc<Vec<Permission>>> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.permission_version != permission_version {
+            return None;
+        }
+
+        inner.clock += 1;
+        let clock = inner.clock;
+        let entry = inner.users.get_mut(&user_id)?;
+        entry.last_used = clock;
+
+        Some(entry.permissions.clone())
+    }
+
+    /// Entries resolved at an older version than the cached ones are not kept, so a resolution
+    /// racing with a change can't overwrite a fresher one.
+    pub fn insert(
+        &self,
+        user_id: Uuid,
+        permission_version: i64,
+        permissions: Arc<Vec<Permission>>,
+    ) {
+        let mut inner = self.inner.lock().unwrap();
+        if permission_version < inner.permission_version {
+            return;
+        }
+        if permission_version > inner.permission_version {
+            inner.permission_version = permission_version;
+            inner.users.clear();
+        }
+
+        if !inner.users.contains_key(&user_id) && inner.users.len() >= self.capacity {
+            // Only a cache miss of a full cache pays for the scan
+            let least_recently_used = inner
+                .users
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(user_id, _)| *user_id);
+            if let Some(least_recently_used) = least_recently_used {
+                inner.users.remove(&least_recently_used);
+            }
+        }
+
+        inner.clock += 1;
+        let last_used = inner.clock;
+        inner.users.insert(
+            user_id,
+            CachedPermissions {
+                permissions,
+                last_used,
+            },
+        );
+    }
+
+    /// Drop the entry of a user, who is resolved again on the next request.
+    pub fn remove(&self, user_id: Uuid) {
+        self.inner.lock().unwrap().users.remove(&user_id);
+    }
+}
+
+impl AuthService {
+    /// Flattened permissions of a user, as `query_user_permissions` would resolve them, from
+    /// the cache when `permission_version` is still current.
+    pub async fn query_effective_permissions(
+        &self,
+        user_id: Uuid,
+        permission_version: i64,
+    ) -> AppResult<Arc<Vec<Permission>>> {
+        let cache = &self.app.permission_cache;
+        if let Some(permissions) = cache.get(user_id, permission_version) {
+            return Ok(permissions);
+        }
+
+        let permissions = Arc::new(self.resolve_effective_permissions(user_id).await?);
+        cache.insert(user_id, permission_version, permissions.clone());
+
+        Ok(permissions)
+    }
+
+    /// Flattened permissions of a user, straight from the database.
+    pub async fn resolve_effective_permissions(&self, user_id: Uuid) -> AppResult<Vec<Permission>> {
+        // Fails for unknown users, as resolving the permission tree does
+        UserService::new(self.app.clone())
+            .query_user_by_id(user_id)
+            .await?;
+
+        let models = permissions::Entity::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                self.conn.get_database_backend(),
+                EFFECTIVE_PERMISSIONS_SQL,
+                vec![user_id.into()],
+            ))
+            .all(&self.conn)
+            .await?;
+
+        Ok(models.into_iter().map(Permission::from).collect())
+    }
+}
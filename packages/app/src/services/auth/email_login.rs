@@ -0,0 +1,219 @@
+use chrono::Utc;
+use shared::utils::{generate_numeric_code, hash_blake3};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppException},
+    mailer::Mail,
+    models::{
+        auth_token::{AuthToken, AuthTokenKind, EmailLoginPayload},
+        user::User,
+    },
+    result::AppResult,
+    services::{
+        auth::{AuthService, login::LoginOutcome},
+        auth_token::{AuthTokenService, create_auth_token::CreateOneTimeTokenParams},
+        login_history::create_login_history::CreateLoginHistoryParams,
+        user::UserService,
+    },
+};
+
+const EMAIL_LOGIN_CODE_DIGITS: u32 = 6;
+
+pub struct EmailLinkLoginParams {
+    pub token: String,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+}
+
+pub struct EmailCodeLoginParams {
+    pub email: String,
+    pub code: String,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+}
+
+impl AuthService {
+    /// Mail a login link and code to the user who verified `email`. Links and codes mailed
+    /// before are void. Unknown addresses are silently ignored, as for password resets.
+    pub async fn request_email_login(&self, email: &str) -> AppResult<()> {
+        let user = match UserService::new(self.app.clone())
+            .query_user_by_verified_email(email.trim())
+            .await
+        {
+            Ok(user) => user,
+            Err(AppError::Exception(AppException::UserNotFound)) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        let Some(email) = user.email else {
+            return Ok(());
+        };
+
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+        auth_token_service
+            .delete_auth_tokens_by_ref_id_and_kind(user.id, AuthTokenKind::EmailLogin)
+            .await?;
+
+        let mail_config = &self.app.config.mail;
+        let code = generate_numeric_code(EMAIL_LOGIN_CODE_DIGITS);
+        let payload = EmailLoginPayload {
+            email: email.clone(),
+            code_digest: digest_email_login_code(user.id, &code),
+            failed_attempts: 0,
+        };
+        let auth_token = auth_token_service
+            .create_one_time_token(CreateOneTimeTokenParams {
+                kind: AuthTokenKind::EmailLogin,
+                user_id: user.id,
+                payload: serde_json::to_string(&payload).unwrap(),
+                expired_at: Utc::now() + mail_config.email_login_lifetime(),
+            })
+            .await?;
+
+        let link = mail_config
+            .email_login_url
+            .replace("{token}", &auth_token.token);
+        self.app
+            .mailer
+            .send(Mail {
+                to: email,
+                subject: "Your login code".to_string(),
+                body: format!(
+                    "Open the link below to sign in as {}:\n\n{link}\n\n\
+                     Or enter this code: {code}\n\n\
+                     Both work once, for {} minutes. If you didn't ask for this, you can ignore \
+                     this mail.\n",
+                    user.account,
+                    mail_config.email_login_lifetime().num_minutes(),
+                ),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sign in with a mailed login link. A user with a second factor is still asked for it.
+    pub async fn login_with_email_link(
+        &self,
+        params: EmailLinkLoginParams,
+    ) -> AppResult<LoginOutcome> {
+        let Some(auth_token) = AuthTokenService::new(self.app.clone())
+            .take_one_time_token(&params.token, AuthTokenKind::EmailLogin)
+            .await?
+        else {
+            return Err(AppException::InvalidVerificationToken.into());
+        };
+
+        let user = UserService::new(self.app.clone())
+            .query_user_by_id(auth_token.ref_id)
+            .await?;
+        let attempt = CreateLoginHistoryParams {
+            user_id: Some(user.id),
+            account: user.account.clone(),
+            ip: params.ip,
+            platform: params.platform,
+            agent: params.agent,
+            ..Default::default()
+        };
+
+        if !is_email_login_for(&auth_token, &user) {
+            return self
+                .fail_login(attempt, AppException::InvalidVerificationToken)
+                .await;
+        }
+        if user.locked_until.is_some_and(|x| x > Utc::now()) {
+            return self.fail_login(attempt, AppException::AccountLocked).await;
+        }
+
+        self.pass_first_factor(user, attempt).await
+    }
+
+    /// Sign in with the code mailed along with a login link. Wrong codes count towards the
+    /// lockout of the user, and void the code once `email_login_max_attempts` is reached.
+    pub async fn login_with_email_code(
+        &self,
+        params: EmailCodeLoginParams,
+    ) -> AppResult<LoginOutcome> {
+        let mut attempt = CreateLoginHistoryParams {
+            account: params.email.trim().to_string(),
+            ip: params.ip,
+            platform: params.platform,
+            agent: params.agent,
+            ..Default::default()
+        };
+
+        let user = match UserService::new(self.app.clone())
+            .query_user_by_verified_email(&attempt.account)
+            .await
+        {
+            Ok(user) => user,
+            Err(AppError::Exception(AppException::UserNotFound)) => {
+                return self
+                    .fail_login(attempt, AppException::AuthenticationFailed)
+                    .await;
+            }
+            Err(err) => return Err(err),
+        };
+        attempt.user_id = Some(user.id);
+        attempt.account = user.account.clone();
+
+        if user.locked_until.is_some_and(|x| x > Utc::now()) {
+            return self.fail_login(attempt, AppException::AccountLocked).await;
+        }
+
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+        let now = Utc::now();
+        let auth_token = auth_token_service
+            .query_auth_tokens_by_ref_id_and_kind(user.id, AuthTokenKind::EmailLogin)
+            .await?
+            .into_iter()
+            .filter(|x| !x.is_expired(&self.app.config.session, now))
+            .max_by_key(|x| x.created_at);
+        let Some(auth_token) = auth_token.filter(|x| is_email_login_for(x, &user)) else {
+            return self
+                .fail_login(attempt, AppException::AuthenticationFailed)
+                .await;
+        };
+
+        let payload = serde_json::from_str::<EmailLoginPayload>(&auth_token.payload).unwrap();
+        if payload.code_digest != digest_email_login_code(user.id, params.code.trim()) {
+            auth_token_service
+                .count_failed_email_login_code(
+                    &auth_token,
+                    self.app.config.mail.email_login_max_attempts,
+                )
+                .await?;
+            self.count_failed_login(user.id).await?;
+            return self
+                .fail_login(attempt, AppException::AuthenticationFailed)
+                .await;
+        }
+
+        if !auth_token_service
+            .spend_one_time_token(auth_token.id)
+            .await?
+        {
+            return self
+                .fail_login(attempt, AppException::AuthenticationFailed)
+                .await;
+        }
+
+        self.pass_first_factor(user, attempt).await
+    }
+}
+
+/// Whether the token was mailed to the address the user still has verified.
+fn is_email_login_for(auth_token: &AuthToken, user: &User) -> bool {
+    let Ok(payload) = serde_json::from_str::<EmailLoginPayload>(&auth_token.payload) else {
+        return false;
+    };
+
+    user.email_verified && user.email.as_deref() == Some(payload.email.as_str())
+}
+
+/// Digest of a login code, bound to the user so equal codes of different users don't match.
+fn digest_email_login_code(user_id: Uuid, code: &str) -> String {
+    hash_blake3(format!("{user_id}:{code}").as_bytes())
+}
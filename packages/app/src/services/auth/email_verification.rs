@@ -0,0 +1,99 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppException},
+    mailer::Mail,
+    models::auth_token::{AuthTokenKind, EmailVerificationPayload},
+    result::AppResult,
+    services::{
+        auth::AuthService,
+        auth_token::{AuthTokenService, create_auth_token::CreateOneTimeTokenParams},
+        user::UserService,
+    },
+};
+
+impl AuthService {
+    /// Set the email of the user and mail a link to verify it. Links mailed before are void.
+    pub async fn send_email_verification(&self, user_id: Uuid, email: &str) -> AppResult<()> {
+        let email = email.trim();
+        if !is_valid_email(email) {
+            return Err(AppException::InvalidEmail.into());
+        }
+
+        let user_service = UserService::new(self.app.clone());
+        match user_service.query_user_by_verified_email(email).await {
+            Ok(other) if other.id != user_id => return Err(AppException::AlreadyExists.into()),
+            Ok(_) | Err(AppError::Exception(AppException::UserNotFound)) => {}
+            Err(err) => return Err(err),
+        }
+
+        user_service.update_email(user_id, email).await?;
+
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+        auth_token_service
+            .delete_auth_tokens_by_ref_id_and_kind(user_id, AuthTokenKind::EmailVerification)
+            .await?;
+
+        let mail_config = &self.app.config.mail;
+        let payload = EmailVerificationPayload {
+            email: email.to_string(),
+        };
+        let auth_token = auth_token_service
+            .create_one_time_token(CreateOneTimeTokenParams {
+                kind: AuthTokenKind::EmailVerification,
+                user_id,
+                payload: serde_json::to_string(&payload).unwrap(),
+                expired_at: Utc::now() + mail_config.email_verification_lifetime(),
+            })
+            .await?;
+
+        let link = mail_config
+            .verify_email_url
+            .replace("{token}", &auth_token.token);
+        self.app
+            .mailer
+            .send(Mail {
+                to: email.to_string(),
+                subject: "Verify your email address".to_string(),
+                body: format!("Open the link below to verify your email address:\n\n{link}\n"),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn verify_email(&self, token: &str) -> AppResult<()> {
+        let Some(auth_token) = AuthTokenService::new(self.app.clone())
+            .take_one_time_token(token, AuthTokenKind::EmailVerification)
+            .await?
+        else {
+            return Err(AppException::InvalidVerificationToken.into());
+        };
+
+        let payload = serde_json::from_str::<EmailVerificationPayload>(&auth_token.payload)
+            .map_err(|_| AppException::InvalidVerificationToken)?;
+
+        let verified = UserService::new(self.app.clone())
+            .verify_email(auth_token.ref_id, &payload.email)
+            .await?;
+        if !verified {
+            return Err(AppException::InvalidVerificationToken.into());
+        }
+
+        Ok(())
+    }
+}
+
+/// Deliberately loose: the verification mail is what proves the address works.
+pub(super) fn is_valid_email(email: &str) -> bool {
+    let Some((local, domain)) = email.split_once('@') else {
+        return false;
+    };
+
+    !local.is_empty()
+        && domain.contains('.')
+        && !domain.starts_with('.')
+        && !domain.ends_with('.')
+        && !email.contains(|c: char| c.is_whitespace() || c.is_control() || "<>,;".contains(c))
+}
@@ -0,0 +1,299 @@
+use std::sync::{Arc, Mutex};
+
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{models::permission::Permission, result::AppResult};
+
+use super::{
+    AuthService,
+    query_permissions::{
+        AssignedDepartmentPermissions, AssignedPermissionGroupPermissions,
+        AssignedRoleGroupPermissions, AssignedRolePermissions, AssignedUserGroupPermissions,
+        AssignedUserPermissions,
+    },
+};
+
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum PermissionSourceKind {
+    User,
+    Department,
+    UserGroup,
+    RoleGroup,
+    Role,
+    PermissionGroup,
+    Permission,
+}
+
+/// One step of a chain of grants.
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionSource {
+    pub kind: PermissionSourceKind,
+    pub id: Uuid,
+    /// Name of the step, the account of a user or the code of a permission.
+    pub name: String,
+}
+
+impl PermissionSource {
+    fn new(kind: PermissionSourceKind, id: Uuid, name: &str) -> Self {
+        Self {
+            kind,
+            id,
+            name: name.to_string(),
+        }
+    }
+}
+
+/// Chain of grants from a user down to a permission, e.g. user → department → role →
+/// permission group → permission.
+pub type PermissionPath = Vec<PermissionSource>;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionExplanation {
+    /// Whether the user has the permission: granted through some path and denied through none.
+    pub granted: bool,
+    pub grants: Vec<PermissionPath>,
+    /// Paths ending in a denial of the permission, e.g. user → role → permission.
+    pub denials: Vec<PermissionPath>,
+}
+
+impl AuthService {
+    /// Every chain of grants giving the user the permission `code`, and every chain denying it.
+    pub async fn explain_permission(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> AppResult<PermissionExplanation> {
+        let user_permissions = self.query_user_permissions(user_id).await?;
+        let grants = user_permissions.explain(code);
+        let denials = user_permissions.explain_denials(code);
+
+        Ok(PermissionExplanation {
+            granted: !grants.is_empty() && denials.is_empty(),
+            grants,
+            denials,
+        })
+    }
+}
+
+trait ExplainPermission {
+    /// Paths from `self` down to the permission `code`, `self` included.
+    fn explain(&self, code: &str) -> Vec<PermissionPath>;
+
+    /// Paths from `self` down to a denial of the permission `code`, `self` included.
+    fn explain_denials(&self, _code: &str) -> Vec<PermissionPath> {
+        vec![]
+    }
+}
+
+/// Put `source` in front of every path found below it.
+fn prepend(source: PermissionSource, paths: Vec<PermissionPath>) -> Vec<PermissionPath> {
+    paths
+        .into_iter()
+        .map(|path| {
+            let mut prepended = Vec::with_capacity(path.len() + 1);
+            prepended.push(source.clone());
+            prepended.extend(path);
+            prepended
+        })
+        .collect()
+}
+
+fn explain_permissions<'a>(
+    permissions: impl IntoIterator<Item = &'a Arc<Mutex<Permission>>>,
+    code: &str,
+) -> Vec<PermissionPath> {
+    permissions
+        .into_iter()
+        .filter_map(|permission| {
+            let permission = permission.lock().unwrap();
+            (permission.code == code).then(|| {
+                vec![PermissionSource::new(
+                    PermissionSourceKind::Permission,
+                    permission.id,
+                    &permission.code,
+                )]
+            })
+        })
+        .collect()
+}
+
+fn explain_all<'a, T: ExplainPermission + 'a>(
+    sources: impl IntoIterator<Item = &'a Arc<Mutex<T>>>,
+    code: &str,
+) -> Vec<PermissionPath> {
+    sources
+        .into_iter()
+        .flat_map(|x| x.lock().unwrap().explain(code))
+        .collect()
+}
+
+fn explain_all_denials<'a, T: ExplainPermission + 'a>(
+    sources: impl IntoIterator<Item = &'a Arc<Mutex<T>>>,
+    code: &str,
+) -> Vec<PermissionPath> {
+    sources
+        .into_iter()
+        .flat_map(|x| x.lock().unwrap().explain_denials(code))
+        .collect()
+}
+
+impl ExplainPermission for AssignedPermissionGroupPermissions {
+    fn explain(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_permissions(&self.permissions, code);
+        paths.extend(explain_all(self.children.values(), code));
+
+        let group = &self.permission_group;
+        prepend(
+            PermissionSource::new(PermissionSourceKind::PermissionGroup, group.id, &group.name),
+            paths,
+        )
+    }
+}
+
+impl ExplainPermission for AssignedRolePermissions {
+    fn explain(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_permissions(&self.permissions, code);
+        paths.extend(explain_all(&self.permission_groups, code));
+
+        let role = &self.role;
+        prepend(
+            PermissionSource::new(PermissionSourceKind::Role, role.id, &role.name),
+            paths,
+        )
+    }
+
+    fn explain_denials(&self, code: &str) -> Vec<PermissionPath> {
+        let paths = explain_permissions(&self.denied_permissions, code);
+
+        let role = &self.role;
+        prepend(
+            PermissionSource::new(PermissionSourceKind::Role, role.id, &role.name),
+            paths,
+        )
+    }
+}
+
+impl ExplainPermission for AssignedRoleGroupPermissions {
+    fn explain(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_all(&self.roles, code);
+        paths.extend(explain_all(self.children.values(), code));
+
+        let group = &self.role_group;
+        prepend(
+            PermissionSource::new(PermissionSourceKind::RoleGroup, group.id, &group.name),
+            paths,
+        )
+    }
+
+    fn explain_denials(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_permissions(&self.denied_permissions, code);
+        paths.extend(explain_all_denials(&self.roles, code));
+        paths.extend(explain_all_denials(self.children.values(), code));
+
+        let group = &self.role_group;
+        prepend(
+            PermissionSource::new(PermissionSourceKind::RoleGroup, group.id, &group.name),
+            paths,
+        )
+    }
+}
+
+impl ExplainPermission for AssignedUserGroupPermissions {
+    fn explain(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_permissions(&self.permissions, code);
+        paths.extend(explain_all(&self.inherited_group, code));
+        paths.extend(explain_all(&self.permission_groups, code));
+        paths.extend(explain_all(&self.roles, code));
+        paths.extend(explain_all(&self.role_groups, code));
+
+        let group = &self.user_group;
+        prepend(
+            PermissionSource::new(PermissionSourceKind::UserGroup, group.id, &group.name),
+            paths,
+        )
+    }
+
+    fn explain_denials(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_permissions(&self.denied_permissions, code);
+        paths.extend(explain_all_denials(&self.inherited_group, code));
+        paths.extend(explain_all_denials(&self.roles, code));
+        paths.extend(explain_all_denials(&self.role_groups, code));
+
+        let group = &self.user_group;
+        prepend(
+            PermissionSource::new(PermissionSourceKind::UserGroup, group.id, &group.name),
+            paths,
+        )
+    }
+}
+
+impl ExplainPermission for AssignedDepartmentPermissions {
+    fn explain(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_permissions(&self.permissions, code);
+        paths.extend(explain_all(&self.permission_groups, code));
+        paths.extend(explain_all(&self.roles, code));
+        paths.extend(explain_all(&self.role_groups, code));
+
+        let department = &self.department;
+        prepend(
+            PermissionSource::new(
+                PermissionSourceKind::Department,
+                department.id,
+                &department.name,
+            ),
+            paths,
+        )
+    }
+
+    fn explain_denials(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_permissions(&self.denied_permissions, code);
+        paths.extend(explain_all_denials(&self.roles, code));
+        paths.extend(explain_all_denials(&self.role_groups, code));
+
+        let department = &self.department;
+        prepend(
+            PermissionSource::new(
+                PermissionSourceKind::Department,
+                department.id,
+                &department.name,
+            ),
+            paths,
+        )
+    }
+}
+
+impl ExplainPermission for AssignedUserPermissions {
+    fn explain(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_permissions(&self.permissions, code);
+        paths.extend(explain_all(&self.departments, code));
+        paths.extend(explain_all(&self.user_groups, code));
+        paths.extend(explain_all(&self.role_groups, code));
+        paths.extend(explain_all(&self.roles, code));
+        paths.extend(explain_all(&self.permission_groups, code));
+
+        let user = &self.user;
+        prepend(
+            PermissionSource::new(PermissionSourceKind::User, user.id, &user.account),
+            paths,
+        )
+    }
+
+    fn explain_denials(&self, code: &str) -> Vec<PermissionPath> {
+        let mut paths = explain_permissions(&self.denied_permissions, code);
+        paths.extend(explain_all_denials(&self.departments, code));
+        paths.extend(explain_all_denials(&self.user_groups, code));
+        paths.extend(explain_all_denials(&self.role_groups, code));
+        paths.extend(explain_all_denials(&self.roles, code));
+
+        let user = &self.user;
+        prepend(
+            PermissionSource::new(PermissionSourceKind::User, user.id, &user.account),
+            paths,
+        )
+    }
+}
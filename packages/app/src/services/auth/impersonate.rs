@@ -0,0 +1,114 @@
+use shared::enums::OperationPermission;
+use uuid::Uuid;
+
+use crate::{
+    error::AppException,
+    models::{
+        auth_token::{AuthToken, SessionTokenPayload},
+        user::User,
+    },
+    result::AppResult,
+    services::{
+        auth::AuthService,
+        auth_token::{AuthTokenService, create_auth_token::CreateSessionTokenParams},
+        data_scope::DataScopedService,
+        impersonation::{ImpersonationService, create_impersonation::CreateImpersonationParams},
+        user::UserService,
+    },
+};
+
+pub struct ImpersonateParams {
+    pub impersonator_id: Uuid,
+    pub user_id: Uuid,
+    /// Why support needs to act as the user, e.g. a ticket number.
+    pub reason: Option<String>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+}
+
+pub struct ImpersonationResult {
+    pub session: AuthToken,
+    pub user: User,
+}
+
+impl AuthService {
+    /// Open a session as another user, recorded among the impersonations of that user. The
+    /// session comes without a refresh token and ends like any other session.
+    pub async fn impersonate(&self, params: ImpersonateParams) -> AppResult<ImpersonationResult> {
+        if params.impersonator_id == params.user_id {
+            return Err(AppException::ImpersonationNotAllowed.into());
+        }
+
+        let user_service = UserService::new(self.app.clone());
+        let impersonator = user_service
+            .query_user_by_id(params.impersonator_id)
+            .await?;
+        let user = user_service.query_user_by_id(params.user_id).await?;
+
+        let impersonator_payload = self.build_session_payload(impersonator.id).await?;
+        let payload = self.build_session_payload(user.id).await?;
+        if !self
+            .may_impersonate(&impersonator_payload, &payload)
+            .await?
+        {
+            return Err(AppException::ImpersonationNotAllowed.into());
+        }
+
+        let session = AuthTokenService::new(self.app.clone())
+            .create_session_token(CreateSessionTokenParams {
+                ip: params.ip.clone(),
+                platform: params.platform.clone(),
+                agent: params.agent.clone(),
+                expired_at: None,
+                payload: SessionTokenPayload {
+                    impersonator_id: Some(impersonator.id),
+                    ..payload
+                },
+            })
+            .await?;
+
+        ImpersonationService::new(self.app.clone())
+            .create_impersonation(CreateImpersonationParams {
+                user_id: user.id,
+                impersonator_id: impersonator.id,
+                impersonator_account: impersonator.account,
+                session_id: session.id,
+                reason: params.reason,
+                ip: params.ip,
+                platform: params.platform,
+                agent: params.agent,
+            })
+            .await?;
+
+        Ok(ImpersonationResult { session, user })
+    }
+
+    /// Whether the impersonator may act as the user of `payload`: acting as someone must not
+    /// lend permissions the impersonator doesn't hold already, nor show rows outside their data
+    /// scope. Checked again whenever the session is resolved anew, as either side may change.
+    pub(crate) async fn may_impersonate(
+        &self,
+        impersonator_payload: &SessionTokenPayload,
+        payload: &SessionTokenPayload,
+    ) -> AppResult<bool> {
+        let impersonate_user = OperationPermission::ImpersonateUser.to_string();
+        if !impersonator_payload.permissions.contains(&impersonate_user)
+            || payload
+                .permissions
+                .iter()
+                .any(|x| !impersonator_payload.permissions.contains(x))
+        {
+            return Ok(false);
+        }
+
+        let in_scope = UserService::new(self.app.clone())
+            .scope_to(impersonator_payload)
+            .await?
+            .assert_users_in_scope(&[payload.user_id])
+            .await
+            .is_ok();
+
+        Ok(in_scope)
+    }
+}
@@ -1,17 +1,22 @@
-use shared::utils::verify_password;
+use chrono::Utc;
+use shared::utils::{hash_password, password_needs_rehash, verify_password};
 use uuid::Uuid;
 
 use crate::{
-    error::AppException,
-    models::user::User,
+    error::{AppError, AppException},
+    models::{
+        auth_token::{AuthToken, AuthTokenKind},
+        user::{LastLogin, User},
+    },
     result::AppResult,
     services::{
         auth::AuthService,
-        auth_token::{AuthTokenService, create_auth_token::CreateSessionTokenParams},
-        department::DepartmentService,
-        role::RoleService,
+        auth_token::{
+            AuthTokenService,
+            create_auth_token::{CreateLoginChallengeTokenParams, CreateSessionTokenParams},
+        },
+        login_history::{LoginHistoryService, create_login_history::CreateLoginHistoryParams},
         user::UserService,
-        user_group::UserGroupService,
     },
 };
 
@@ -23,48 +28,204 @@ pub struct LoginParams {
     pub agent: Option<String>,
 }
 
+pub struct LoginResult {
+    pub session: AuthToken,
+    pub refresh_token: AuthToken,
+    pub user: User,
+}
+
+pub enum LoginOutcome {
+    Authenticated(Box<LoginResult>),
+    /// The password was accepted but the user has TOTP enabled. The login is finished by
+    /// handing the challenge token back together with a code to `login_with_second_factor`.
+    SecondFactorRequired {
+        challenge_token: AuthToken,
+    },
+    /// The password was accepted but is older than the password policy allows. The login is
+    /// continued by handing the challenge token back together with a new password to
+    /// `change_expired_password`.
+    PasswordChangeRequired {
+        challenge_token: AuthToken,
+    },
+    /// The user registered with an email of a domain the registration is restricted to, and
+    /// is only let in once the link mailed to that address is opened.
+    EmailVerificationRequired,
+}
+
 impl AuthService {
-    pub async fn login(&self, params: LoginParams) -> AppResult<(Uuid, User)> {
+    pub async fn login(&self, params: LoginParams) -> AppResult<LoginOutcome> {
         let user_service = UserService::new(self.app.clone());
-        let auth_service = AuthService::new(self.app.clone());
-        let role_service = RoleService::new(self.app.clone());
-        let group_service = UserGroupService::new(self.app.clone());
-        let department_service = DepartmentService::new(self.app.clone());
-        let auth_token_service = AuthTokenService::new(self.app.clone());
 
-        let user = user_service.query_user_by_account(&params.account).await?;
+        let mut attempt = CreateLoginHistoryParams {
+            account: params.account,
+            ip: params.ip,
+            platform: params.platform,
+            agent: params.agent,
+            ..Default::default()
+        };
+
+        // Unknown accounts fail like wrong passwords, so they count towards throttling too
+        let user = match user_service.query_user_by_account(&attempt.account).await {
+            Ok(user) => user,
+            Err(AppError::Exception(AppException::UserNotFound)) => {
+                return self
+                    .fail_login(attempt, AppException::AuthenticationFailed)
+                    .await;
+            }
+            Err(err) => return Err(err),
+        };
+        attempt.user_id = Some(user.id);
+
+        // A locked account doesn't even get its password checked
+        if user.locked_until.is_some_and(|x| x > Utc::now()) {
+            return self.fail_login(attempt, AppException::AccountLocked).await;
+        }
+
         let password_digest = user.password_digest.as_deref().unwrap_or_default();
-        let password_valid = verify_password(&params.password, &password_digest);
+        let password_hash = &self.app.config.password_hash;
+        let password_valid =
+            verify_password(&params.password, password_digest, password_hash.pepper());
 
         if !password_valid {
-            return Err(AppException::AuthenticationFailed.into());
+            self.count_failed_login(user.id).await?;
+            return self
+                .fail_login(attempt, AppException::AuthenticationFailed)
+                .await;
+        }
+
+        // Only now is the plain password at hand to hash again with the current cost and pepper
+        if password_needs_rehash(password_digest, &password_hash.params()) {
+            let password_digest = hash_password(&params.password, &password_hash.params());
+            user_service
+                .update_password_digest(user.id, password_digest)
+                .await?;
+        }
+
+        if user_service.is_password_expired(&user) {
+            let challenge_token = AuthTokenService::new(self.app.clone())
+                .create_login_challenge_token(CreateLoginChallengeTokenParams {
+                    kind: AuthTokenKind::PasswordChange,
+                    user_id: user.id,
+                    ip: attempt.ip,
+                    platform: attempt.platform,
+                    agent: attempt.agent,
+                })
+                .await?;
+
+            return Ok(LoginOutcome::PasswordChangeRequired { challenge_token });
+        }
+
+        self.pass_first_factor(user, attempt).await
+    }
+
+    /// Count a failed login towards the lockout of the user.
+    pub(super) async fn count_failed_login(&self, user_id: Uuid) -> AppResult<()> {
+        let user_service = UserService::new(self.app.clone());
+
+        let failed_attempts = user_service.increase_failed_login_attempts(user_id).await?;
+        if let Some(lockout_duration) = self.app.config.login.lockout_duration(failed_attempts) {
+            user_service
+                .lock_user(user_id, Utc::now() + lockout_duration)
+                .await?;
         }
 
-        let user_permissions = auth_service.query_user_permissions(user.id).await?;
-        let roles = role_service.query_roles_by_user_id(user.id).await?;
-        let groups = group_service.query_user_groups_by_user_id(user.id).await?;
-        let departments = department_service
-            .query_departments_by_user_id(user.id)
+        Ok(())
+    }
+
+    /// Record a failed login in the login history and return `exception` as the error.
+    pub(super) async fn fail_login<T>(
+        &self,
+        mut attempt: CreateLoginHistoryParams,
+        exception: AppException,
+    ) -> AppResult<T> {
+        attempt.success = false;
+        attempt.failure_reason = Some(exception.to_string());
+
+        LoginHistoryService::new(self.app.clone())
+            .create_login_history(attempt)
+            .await?;
+
+        Err(exception.into())
+    }
+
+    /// Continue a login whose first factor was accepted: ask for the second factor if the user
+    /// has one, otherwise let the user in.
+    pub(super) async fn pass_first_factor(
+        &self,
+        user: User,
+        attempt: CreateLoginHistoryParams,
+    ) -> AppResult<LoginOutcome> {
+        if user.registration_pending {
+            return Ok(LoginOutcome::EmailVerificationRequired);
+        }
+
+        // Failed attempts are only forgotten once the second factor is passed as well,
+        // otherwise the password alone would lift the limit on guessing codes
+        if user.totp_enabled {
+            let challenge_token = AuthTokenService::new(self.app.clone())
+                .create_login_challenge_token(CreateLoginChallengeTokenParams {
+                    kind: AuthTokenKind::SecondFactorChallenge,
+                    user_id: user.id,
+                    ip: attempt.ip,
+                    platform: attempt.platform,
+                    agent: attempt.agent,
+                })
+                .await?;
+
+            return Ok(LoginOutcome::SecondFactorRequired { challenge_token });
+        }
+
+        let result = self.complete_login(user, attempt).await?;
+
+        Ok(LoginOutcome::Authenticated(Box::new(result)))
+    }
+
+    /// Let a fully authenticated user in: record the login and mint the session.
+    pub(super) async fn complete_login(
+        &self,
+        user: User,
+        mut attempt: CreateLoginHistoryParams,
+    ) -> AppResult<LoginResult> {
+        let user_service = UserService::new(self.app.clone());
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+
+        if user.failed_login_attempts > 0 || user.locked_until.is_some() {
+            user_service.unlock_user(user.id).await?;
+        }
+
+        user_service
+            .update_last_login(
+                user.id,
+                &LastLogin {
+                    at: Utc::now(),
+                    ip: attempt.ip.clone(),
+                    platform: attempt.platform.clone(),
+                    agent: attempt.agent.clone(),
+                },
+            )
+            .await?;
+
+        attempt.success = true;
+        let session_params = CreateSessionTokenParams {
+            ip: attempt.ip.clone(),
+            platform: attempt.platform.clone(),
+            agent: attempt.agent.clone(),
+            expired_at: None,
+            payload: self.build_session_payload(user.id).await?,
+        };
+        LoginHistoryService::new(self.app.clone())
+            .create_login_history(attempt)
             .await?;
 
-        let session_id = auth_token_service
-            .create_session_token(CreateSessionTokenParams {
-                ip: params.ip,
-                platform: params.platform,
-                agent: params.agent,
-                expired_at: None,
-                user_id: user.id,
-                permissions: user_permissions
-                    .flatten_permissions()
-                    .into_iter()
-                    .map(|x| x.lock().unwrap().code.clone())
-                    .collect(),
-                roles: roles.into_iter().map(|x| x.id).collect(),
-                groups: groups.into_iter().map(|x| x.id).collect(),
-                departments: departments.into_iter().map(|x| x.id).collect(),
-            })
+        let session = auth_token_service
+            .create_session_token(session_params)
             .await?;
+        let refresh_token = auth_token_service.create_refresh_token(&session).await?;
 
-        Ok((session_id, user))
+        Ok(LoginResult {
+            session,
+            refresh_token,
+            user,
+        })
     }
 }
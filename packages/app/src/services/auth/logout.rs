@@ -1,15 +1,27 @@
 use uuid::Uuid;
 
 use crate::{
+    models::auth_token::AuthTokenKind,
     result::AppResult,
-    services::{auth::AuthService, auth_token::AuthTokenService},
+    services::{
+        auth::AuthService, auth_token::AuthTokenService, impersonation::ImpersonationService,
+    },
 };
 
 impl AuthService {
     pub async fn logout(&self, session_id: Uuid) -> AppResult<()> {
         let auth_token_service = AuthTokenService::new(self.app.clone());
+        let Some(session) = auth_token_service
+            .query_auth_token_by_id(session_id)
+            .await?
+        else {
+            return Ok(());
+        };
         auth_token_service
-            .delete_auth_token_by_id(session_id)
+            .delete_session_token_family(session.ref_id, session.id)
+            .await?;
+        ImpersonationService::new(self.app.clone())
+            .end_impersonation_by_session_id(session.id)
             .await?;
 
         Ok(())
@@ -20,6 +32,40 @@ impl AuthService {
         auth_token_service
             .delete_auth_token_by_ref_id(user_id)
             .await?;
+        ImpersonationService::new(self.app.clone())
+            .end_impersonations_by_user_id(user_id)
+            .await?;
+        // Not needed before the user signs in again
+        self.app.permission_cache.remove(user_id);
+
+        Ok(())
+    }
+
+    /// Sign the user out everywhere but in `session_id`.
+    pub async fn logout_others(&self, user_id: Uuid, session_id: Uuid) -> AppResult<()> {
+        AuthTokenService::new(self.app.clone())
+            .delete_other_session_tokens(user_id, session_id)
+            .await?;
+        ImpersonationService::new(self.app.clone())
+            .end_other_impersonations(user_id, session_id)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Sign the user out of every session, unlike `logout_all` leaving personal access and
+    /// OAuth access tokens alone.
+    pub async fn logout_sessions(&self, user_id: Uuid) -> AppResult<()> {
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+        for kind in [AuthTokenKind::Session, AuthTokenKind::Refresh] {
+            auth_token_service
+                .delete_auth_tokens_by_ref_id_and_kind(user_id, kind)
+                .await?;
+        }
+        ImpersonationService::new(self.app.clone())
+            .end_impersonations_by_user_id(user_id)
+            .await?;
+        self.app.permission_cache.remove(user_id);
 
         Ok(())
     }
@@ -1,8 +1,24 @@
 use crate::impl_service;
 pub mod assign_permissions;
+pub mod authorization_server;
+pub mod change_password;
+pub mod deny_permissions;
+pub mod effective_permissions;
+pub mod email_login;
+pub mod email_verification;
+pub mod explain_permission;
+pub mod impersonate;
 pub mod login;
 pub mod logout;
+pub mod oauth;
+pub mod password_reset;
+pub mod permission_version;
+pub mod personal_access_token;
 pub mod query_permissions;
+pub mod refresh;
 pub mod register;
+pub mod second_factor;
+pub mod session_payload;
+pub mod totp;
 
 impl_service!(AuthService);
@@ -0,0 +1,210 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use shared::utils::{generate_random_token, pkce_challenge};
+use uuid::Uuid;
+
+use crate::{
+    error::{AppError, AppException},
+    identity_provider::{AuthorizationRequest, CodeExchange, ExternalIdentity, IdentityProvider},
+    models::auth_token::{AuthTokenKind, OAuthStatePayload},
+    result::AppResult,
+    services::{
+        auth::AuthService,
+        auth_token::{AuthTokenService, create_auth_token::CreateOneTimeTokenParams},
+        login_history::create_login_history::CreateLoginHistoryParams,
+        third_user::{ThirdUserService, save_third_user::SaveThirdUserParams},
+        user::{UserService, create_user::CreateExternalUserParams},
+    },
+};
+
+use super::login::LoginOutcome;
+
+pub struct StartOAuthLoginParams {
+    pub provider: String,
+    /// Link the external identity to this user instead of signing in whoever it belongs to.
+    pub link_user_id: Option<Uuid>,
+}
+
+/// Where to send the user, and the state the callback has to come back with.
+pub struct OAuthAuthorization {
+    pub url: String,
+    pub state: String,
+    pub expired_at: DateTime<Utc>,
+}
+
+pub struct OAuthLoginParams {
+    pub provider: String,
+    pub code: String,
+    pub state: String,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+}
+
+impl AuthService {
+    fn identity_provider(&self, name: &str) -> AppResult<Arc<dyn IdentityProvider>> {
+        self.app
+            .identity_providers
+            .get(name)
+            .cloned()
+            .ok_or_else(|| AppException::IdentityProviderNotFound.into())
+    }
+
+    /// Begin an authorization code flow with PKCE at the provider. The verifier and nonce stay
+    /// on the server, in the state token.
+    pub async fn start_oauth_login(
+        &self,
+        params: StartOAuthLoginParams,
+    ) -> AppResult<OAuthAuthorization> {
+        let provider = self.identity_provider(&params.provider)?;
+        let oauth_config = &self.app.config.oauth;
+
+        let payload = OAuthStatePayload {
+            redirect_uri: oauth_config.redirect_uri(&params.provider),
+            provider: params.provider,
+            nonce: generate_random_token(32),
+            code_verifier: generate_random_token(32),
+            link_user_id: params.link_user_id,
+        };
+        let expired_at = Utc::now() + oauth_config.state_lifetime();
+        let state = AuthTokenService::new(self.app.clone())
+            .create_one_time_token(CreateOneTimeTokenParams {
+                kind: AuthTokenKind::OAuthState,
+                user_id: params.link_user_id.unwrap_or_default(),
+                payload: serde_json::to_string(&payload).unwrap(),
+                expired_at,
+            })
+            .await?;
+
+        let url = provider
+            .authorization_url(AuthorizationRequest {
+                redirect_uri: payload.redirect_uri,
+                state: state.token.clone(),
+                nonce: payload.nonce,
+                code_challenge: pkce_challenge(&payload.code_verifier),
+            })
+            .await?;
+
+        Ok(OAuthAuthorization {
+            url,
+            state: state.token,
+            expired_at,
+        })
+    }
+
+    /// Finish an authorization code flow: redeem the code, find or create the local user of the
+    /// external identity and log the user in.
+    pub async fn login_with_oauth(&self, params: OAuthLoginParams) -> AppResult<LoginOutcome> {
+        let provider = self.identity_provider(&params.provider)?;
+
+        let Some(state) = AuthTokenService::new(self.app.clone())
+            .take_one_time_token(&params.state, AuthTokenKind::OAuthState)
+            .await?
+        else {
+            return Err(AppException::ExternalAuthenticationFailed.into());
+        };
+        let payload: OAuthStatePayload =
+            serde_json::from_str(&state.payload).map_err(anyhow::Error::from)?;
+        if payload.provider != params.provider {
+            return Err(AppException::ExternalAuthenticationFailed.into());
+        }
+
+        let identity = provider
+            .exchange_code(CodeExchange {
+                code: params.code,
+                redirect_uri: payload.redirect_uri,
+                code_verifier: payload.code_verifier,
+                nonce: payload.nonce,
+            })
+            .await?;
+
+        let user_id = self
+            .resolve_external_user(&params.provider, &identity, payload.link_user_id)
+            .await?;
+        ThirdUserService::new(self.app.clone())
+            .save_third_user(SaveThirdUserParams {
+                user_id,
+                platform: params.provider,
+                open_id: identity.subject,
+                union_id: identity.union_id,
+                access_token: identity.access_token,
+                payload: Some(identity.payload.to_string()),
+            })
+            .await?;
+
+        let user = UserService::new(self.app.clone())
+            .query_user_by_id(user_id)
+            .await?;
+        let attempt = CreateLoginHistoryParams {
+            user_id: Some(user.id),
+            account: user.account.clone(),
+            ip: params.ip,
+            platform: params.platform,
+            agent: params.agent,
+            ..Default::default()
+        };
+
+        if user.locked_until.is_some_and(|x| x > Utc::now()) {
+            return self.fail_login(attempt, AppException::AccountLocked).await;
+        }
+
+        self.pass_first_factor(user, attempt).await
+    }
+
+    /// The local user of an external identity: the one it is already linked to, the one asking
+    /// to link it, the one who verified the same email, or else a new user if registration is
+    /// open to them.
+    async fn resolve_external_user(
+        &self,
+        provider: &str,
+        identity: &ExternalIdentity,
+        link_user_id: Option<Uuid>,
+    ) -> AppResult<Uuid> {
+        let linked = ThirdUserService::new(self.app.clone())
+            .query_third_user_by_open_id(provider, &identity.subject)
+            .await?;
+
+        match (linked, link_user_id) {
+            (Some(linked), Some(user_id)) if linked.user_id != user_id => {
+                Err(AppException::AlreadyExists.into())
+            }
+            (Some(linked), _) => Ok(linked.user_id),
+            (None, Some(user_id)) => Ok(user_id),
+            (None, None) => {
+                let user_service = UserService::new(self.app.clone());
+
+                // Only an address verified on both sides is taken as the same person
+                if identity.email_verified
+                    && let Some(email) = &identity.email
+                {
+                    match user_service.query_user_by_verified_email(email).await {
+                        Ok(user) => return Ok(user.id),
+                        Err(AppError::Exception(AppException::UserNotFound)) => {}
+                        Err(err) => return Err(err),
+                    }
+                }
+
+                // Signing in with a provider registers like the form does, without invitations,
+                // and with an address of an allowed domain only if the provider verified it
+                let verified_email = identity
+                    .email
+                    .as_deref()
+                    .filter(|_| identity.email_verified);
+                self.query_registration_settings()
+                    .await?
+                    .check_registration(false, verified_email)?;
+
+                user_service
+                    .create_external_user(CreateExternalUserParams {
+                        account: format!("{provider}:{}", identity.subject),
+                        nickname: identity.name.clone(),
+                        email: identity.email.clone(),
+                        email_verified: identity.email_verified,
+                        avatar_url: identity.avatar_url.clone(),
+                    })
+                    .await
+            }
+        }
+    }
+}
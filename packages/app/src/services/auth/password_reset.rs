@@ -0,0 +1,94 @@
+use chrono::Utc;
+
+use crate::{
+    error::{AppError, AppException},
+    mailer::Mail,
+    models::auth_token::AuthTokenKind,
+    result::AppResult,
+    services::{
+        auth::AuthService,
+        auth_token::{AuthTokenService, create_auth_token::CreateOneTimeTokenParams},
+        user::UserService,
+    },
+};
+
+pub struct ResetPasswordParams {
+    pub token: String,
+    pub password: String,
+}
+
+impl AuthService {
+    /// Mail a password reset link to the user who verified `email`. Unknown addresses are
+    /// silently ignored so the endpoint can't be used to probe for accounts.
+    pub async fn request_password_reset(&self, email: &str) -> AppResult<()> {
+        let user = match UserService::new(self.app.clone())
+            .query_user_by_verified_email(email.trim())
+            .await
+        {
+            Ok(user) => user,
+            Err(AppError::Exception(AppException::UserNotFound)) => return Ok(()),
+            Err(err) => return Err(err),
+        };
+        let Some(email) = user.email else {
+            return Ok(());
+        };
+
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+        auth_token_service
+            .delete_auth_tokens_by_ref_id_and_kind(user.id, AuthTokenKind::PasswordReset)
+            .await?;
+
+        let mail_config = &self.app.config.mail;
+        let auth_token = auth_token_service
+            .create_one_time_token(CreateOneTimeTokenParams {
+                kind: AuthTokenKind::PasswordReset,
+                user_id: user.id,
+                payload: "{}".to_string(),
+                expired_at: Utc::now() + mail_config.password_reset_lifetime(),
+            })
+            .await?;
+
+        let link = mail_config
+            .reset_password_url
+            .replace("{token}", &auth_token.token);
+        self.app
+            .mailer
+            .send(Mail {
+                to: email,
+                subject: "Reset your password".to_string(),
+                body: format!(
+                    "Open the link below to choose a new password for {}:\n\n{link}\n\n\
+                     If you didn't ask for this, you can ignore this mail.\n",
+                    user.account
+                ),
+            })
+            .await?;
+
+        Ok(())
+    }
+
+    /// Set a new password with a mailed reset token. Every session of the user is signed out,
+    /// as is any lockout lifted.
+    pub async fn reset_password(&self, params: ResetPasswordParams) -> AppResult<()> {
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+        let Some(auth_token) = auth_token_service
+            .take_one_time_token(&params.token, AuthTokenKind::PasswordReset)
+            .await?
+        else {
+            return Err(AppException::InvalidVerificationToken.into());
+        };
+        let user_id = auth_token.ref_id;
+
+        let user_service = UserService::new(self.app.clone());
+        user_service
+            .update_password(user_id, &params.password)
+            .await?;
+        user_service.unlock_user(user_id).await?;
+
+        auth_token_service
+            .delete_auth_token_by_ref_id(user_id)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use shared::enums::SettingName;
+
+use crate::{
+    result::AppResult,
+    services::{auth::AuthService, setting::SettingService},
+};
+
+impl AuthService {
+    /// Current version of the permission graph, compared against the version stored in each
+    /// session payload to detect stale grants.
+    pub async fn query_permission_version(&self) -> AppResult<i64> {
+        let setting_service = SettingService::new(self.app.clone());
+        let version = setting_service
+            .query_setting_value(SettingName::PermissionVersion)
+            .await?;
+
+        Ok(version.and_then(|x| x.parse().ok()).unwrap_or_default())
+    }
+
+    /// Mark every session payload as stale. Must be called after any change to the
+    /// assignments or hierarchy that permissions are resolved through.
+    pub async fn bump_permission_version(&self) -> AppResult<()> {
+        let setting_service = SettingService::new(self.app.clone());
+        setting_service
+            .increase_setting_value(SettingName::PermissionVersion)
+            .await
+    }
+}
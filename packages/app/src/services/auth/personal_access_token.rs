@@ -0,0 +1,76 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    error::AppException,
+    models::auth_token::{AuthToken, AuthTokenKind},
+    result::AppResult,
+    services::{
+        auth::AuthService,
+        auth_token::{AuthTokenService, create_auth_token::CreatePersonalAccessTokenParams},
+    },
+};
+
+pub struct IssuePersonalAccessTokenParams {
+    pub user_id: Uuid,
+    pub name: String,
+    pub permissions: Vec<String>,
+    pub expired_at: DateTime<Utc>,
+    /// Scope of the token the request is made with, which the new token can't exceed.
+    pub caller_scope: Option<Vec<String>>,
+}
+
+impl AuthService {
+    /// Mint a named token for API clients. The token can only be granted permissions the user
+    /// currently holds, within the scope of the caller's own token, and loses any of them the
+    /// user is later stripped of.
+    pub async fn issue_personal_access_token(
+        &self,
+        params: IssuePersonalAccessTokenParams,
+    ) -> AppResult<AuthToken> {
+        let mut payload = self.build_session_payload(params.user_id).await?;
+
+        if let Some(permission) = params.permissions.iter().find(|x| {
+            !payload.permissions.contains(x)
+                || params
+                    .caller_scope
+                    .as_ref()
+                    .is_some_and(|scope| !scope.contains(x))
+        }) {
+            return Err(AppException::PermissionNotGranted(permission.clone()).into());
+        }
+
+        payload.name = Some(params.name);
+        payload.scope = Some(params.permissions);
+        payload.restrict_to_scope();
+
+        AuthTokenService::new(self.app.clone())
+            .create_personal_access_token(CreatePersonalAccessTokenParams {
+                expired_at: params.expired_at,
+                payload,
+            })
+            .await
+    }
+
+    pub async fn query_personal_access_tokens(&self, user_id: Uuid) -> AppResult<Vec<AuthToken>> {
+        AuthTokenService::new(self.app.clone())
+            .query_auth_tokens_by_ref_id_and_kind(user_id, AuthTokenKind::PersonalAccess)
+            .await
+    }
+
+    pub async fn delete_personal_access_token(&self, user_id: Uuid, id: Uuid) -> AppResult<()> {
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+
+        let auth_token = auth_token_service
+            .query_auth_token_by_id(id)
+            .await?
+            .filter(|x| x.kind == AuthTokenKind::PersonalAccess && x.ref_id == user_id);
+        let Some(auth_token) = auth_token else {
+            return Err(AppException::NotFound.into());
+        };
+
+        auth_token_service
+            .delete_auth_token_by_id(auth_token.id)
+            .await
+    }
+}
@@ -1,9 +1,10 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     sync::{Arc, Mutex},
 };
 
 use serde::Serialize;
+use shared::enums::PermissionSubjectKind;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -31,6 +32,20 @@ fn distinct_permission(permissions: Vec<Arc<Mutex<Permission>>>) -> Vec<Arc<Mute
     permission_map.into_values().collect()
 }
 
+fn exclude_denied_permissions(
+    permissions: Vec<Arc<Mutex<Permission>>>,
+    denied_permissions: Vec<Arc<Mutex<Permission>>>,
+) -> Vec<Arc<Mutex<Permission>>> {
+    let denied_id_list = denied_permissions
+        .iter()
+        .map(|x| x.lock().unwrap().id)
+        .collect::<HashSet<_>>();
+    permissions
+        .into_iter()
+        .filter(|x| !denied_id_list.contains(&x.lock().unwrap().id))
+        .collect()
+}
+
 #[derive(Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AssignedPermissionGroupPermissions {
@@ -57,10 +72,11 @@ pub struct AssignedRolePermissions {
     pub role: Role,
     pub permissions: Vec<Arc<Mutex<Permission>>>,
     pub permission_groups: Vec<Arc<Mutex<AssignedPermissionGroupPermissions>>>,
+    pub denied_permissions: Vec<Arc<Mutex<Permission>>>,
 }
 
 impl AssignedRolePermissions {
-    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+    fn flatten_granted_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
         let mut permissions = self.permissions.clone();
 
         for permission_group in self.permission_groups.iter() {
@@ -69,6 +85,17 @@ impl AssignedRolePermissions {
 
         distinct_permission(permissions)
     }
+
+    pub fn flatten_denied_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        distinct_permission(self.denied_permissions.clone())
+    }
+
+    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        exclude_denied_permissions(
+            self.flatten_granted_permissions(),
+            self.flatten_denied_permissions(),
+        )
+    }
 }
 
 #[derive(Serialize, Clone)]
@@ -77,22 +104,44 @@ pub struct AssignedRoleGroupPermissions {
     pub role_group: RoleGroup,
     pub roles: Vec<Arc<Mutex<AssignedRolePermissions>>>,
     pub children: HashMap<Uuid, Arc<Mutex<AssignedRoleGroupPermissions>>>,
+    pub denied_permissions: Vec<Arc<Mutex<Permission>>>,
 }
 
 impl AssignedRoleGroupPermissions {
-    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+    fn flatten_granted_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
         let mut permissions = vec![];
 
         for role in &self.roles {
-            permissions.extend(role.lock().unwrap().flatten_permissions());
+            permissions.extend(role.lock().unwrap().flatten_granted_permissions());
         }
 
         for child in self.children.values() {
-            permissions.extend(child.lock().unwrap().flatten_permissions());
+            permissions.extend(child.lock().unwrap().flatten_granted_permissions());
         }
 
         distinct_permission(permissions)
     }
+
+    pub fn flatten_denied_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        let mut permissions = self.denied_permissions.clone();
+
+        for role in &self.roles {
+            permissions.extend(role.lock().unwrap().flatten_denied_permissions());
+        }
+
+        for child in self.children.values() {
+            permissions.extend(child.lock().unwrap().flatten_denied_permissions());
+        }
+
+        distinct_permission(permissions)
+    }
+
+    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        exclude_denied_permissions(
+            self.flatten_granted_permissions(),
+            self.flatten_denied_permissions(),
+        )
+    }
 }
 
 #[derive(Serialize, Clone)]
@@ -104,14 +153,20 @@ pub struct AssignedUserGroupPermissions {
     pub permission_groups: Vec<Arc<Mutex<AssignedPermissionGroupPermissions>>>,
     pub roles: Vec<Arc<Mutex<AssignedRolePermissions>>>,
     pub role_groups: Vec<Arc<Mutex<AssignedRoleGroupPermissions>>>,
+    pub denied_permissions: Vec<Arc<Mutex<Permission>>>,
 }
 
 impl AssignedUserGroupPermissions {
-    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+    fn flatten_granted_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
         let mut permissions = self.permissions.clone();
 
         if let Some(inherited_group) = &self.inherited_group {
-            permissions.extend(inherited_group.lock().unwrap().flatten_permissions());
+            permissions.extend(
+                inherited_group
+                    .lock()
+                    .unwrap()
+                    .flatten_granted_permissions(),
+            );
         }
 
         for permission_group in &self.permission_groups {
@@ -119,15 +174,40 @@ impl AssignedUserGroupPermissions {
         }
 
         for role in &self.roles {
-            permissions.extend(role.lock().unwrap().flatten_permissions());
+            permissions.extend(role.lock().unwrap().flatten_granted_permissions());
+        }
+
+        for role_group in &self.role_groups {
+            permissions.extend(role_group.lock().unwrap().flatten_granted_permissions());
+        }
+
+        distinct_permission(permissions)
+    }
+
+    pub fn flatten_denied_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        let mut permissions = self.denied_permissions.clone();
+
+        if let Some(inherited_group) = &self.inherited_group {
+            permissions.extend(inherited_group.lock().unwrap().flatten_denied_permissions());
+        }
+
+        for role in &self.roles {
+            permissions.extend(role.lock().unwrap().flatten_denied_permissions());
         }
 
         for role_group in &self.role_groups {
-            permissions.extend(role_group.lock().unwrap().flatten_permissions());
+            permissions.extend(role_group.lock().unwrap().flatten_denied_permissions());
         }
 
         distinct_permission(permissions)
     }
+
+    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        exclude_denied_permissions(
+            self.flatten_granted_permissions(),
+            self.flatten_denied_permissions(),
+        )
+    }
 }
 
 #[derive(Serialize, Clone)]
@@ -138,10 +218,11 @@ pub struct AssignedDepartmentPermissions {
     pub permission_groups: Vec<Arc<Mutex<AssignedPermissionGroupPermissions>>>,
     pub roles: Vec<Arc<Mutex<AssignedRolePermissions>>>,
     pub role_groups: Vec<Arc<Mutex<AssignedRoleGroupPermissions>>>,
+    pub denied_permissions: Vec<Arc<Mutex<Permission>>>,
 }
 
 impl AssignedDepartmentPermissions {
-    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+    fn flatten_granted_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
         let mut permissions = self.permissions.clone();
 
         for permission_group in &self.permission_groups {
@@ -149,15 +230,36 @@ impl AssignedDepartmentPermissions {
         }
 
         for role in &self.roles {
-            permissions.extend(role.lock().unwrap().flatten_permissions());
+            permissions.extend(role.lock().unwrap().flatten_granted_permissions());
         }
 
         for role_group in &self.role_groups {
-            permissions.extend(role_group.lock().unwrap().flatten_permissions());
+            permissions.extend(role_group.lock().unwrap().flatten_granted_permissions());
         }
 
         distinct_permission(permissions)
     }
+
+    pub fn flatten_denied_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        let mut permissions = self.denied_permissions.clone();
+
+        for role in &self.roles {
+            permissions.extend(role.lock().unwrap().flatten_denied_permissions());
+        }
+
+        for role_group in &self.role_groups {
+            permissions.extend(role_group.lock().unwrap().flatten_denied_permissions());
+        }
+
+        distinct_permission(permissions)
+    }
+
+    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        exclude_denied_permissions(
+            self.flatten_granted_permissions(),
+            self.flatten_denied_permissions(),
+        )
+    }
 }
 
 #[derive(Serialize, Clone)]
@@ -170,10 +272,11 @@ pub struct AssignedUserPermissions {
     pub roles: Vec<Arc<Mutex<AssignedRolePermissions>>>,
     pub permission_groups: Vec<Arc<Mutex<AssignedPermissionGroupPermissions>>>,
     pub permissions: Vec<Arc<Mutex<Permission>>>,
+    pub denied_permissions: Vec<Arc<Mutex<Permission>>>,
 }
 
 impl AssignedUserPermissions {
-    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+    fn flatten_granted_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
         let mut permissions = self.permissions.clone();
 
         for permission_group in &self.permission_groups {
@@ -181,23 +284,55 @@ impl AssignedUserPermissions {
         }
 
         for role in &self.roles {
-            permissions.extend(role.lock().unwrap().flatten_permissions());
+            permissions.extend(role.lock().unwrap().flatten_granted_permissions());
+        }
+
+        for role_group in &self.role_groups {
+            permissions.extend(role_group.lock().unwrap().flatten_granted_permissions());
+        }
+
+        for user_group in &self.user_groups {
+            permissions.extend(user_group.lock().unwrap().flatten_granted_permissions());
+        }
+
+        for department in &self.departments {
+            permissions.extend(department.lock().unwrap().flatten_granted_permissions());
+        }
+
+        distinct_permission(permissions)
+    }
+
+    /// Permissions denied to the user, or to anything they reach.
+    pub fn flatten_denied_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        let mut permissions = self.denied_permissions.clone();
+
+        for role in &self.roles {
+            permissions.extend(role.lock().unwrap().flatten_denied_permissions());
         }
 
         for role_group in &self.role_groups {
-            permissions.extend(role_group.lock().unwrap().flatten_permissions());
+            permissions.extend(role_group.lock().unwrap().flatten_denied_permissions());
         }
 
         for user_group in &self.user_groups {
-            permissions.extend(user_group.lock().unwrap().flatten_permissions());
+            permissions.extend(user_group.lock().unwrap().flatten_denied_permissions());
         }
 
         for department in &self.departments {
-            permissions.extend(department.lock().unwrap().flatten_permissions());
+            permissions.extend(department.lock().unwrap().flatten_denied_permissions());
         }
 
         distinct_permission(permissions)
     }
+
+    /// Permissions granted to the user through any path, unless denied through any path: a
+    /// denial wins over every grant, wherever either of them is made.
+    pub fn flatten_permissions(&self) -> Vec<Arc<Mutex<Permission>>> {
+        exclude_denied_permissions(
+            self.flatten_granted_permissions(),
+            self.flatten_denied_permissions(),
+        )
+    }
 }
 
 pub struct PermissionTree {
@@ -221,6 +356,34 @@ pub enum PermissionTreeEntry {
     DepartmentId(Uuid),
 }
 
+/// Permissions denied to each of the subjects, shared with the nodes of `permissions_map`.
+async fn query_denied_permissions(
+    permission_service: &PermissionService,
+    permissions_map: &mut HashMap<Uuid, Arc<Mutex<Permission>>>,
+    subject_kind: PermissionSubjectKind,
+    subject_id_list: Vec<Uuid>,
+) -> AppResult<HashMap<Uuid, Vec<Arc<Mutex<Permission>>>>> {
+    let denied_permissions = permission_service
+        .query_denied_permissions_by_subject_id_list(subject_kind, subject_id_list)
+        .await?;
+
+    Ok(denied_permissions
+        .into_iter()
+        .map(|(subject_id, permissions)| {
+            let permissions = permissions
+                .into_iter()
+                .map(|permission| {
+                    permissions_map
+                        .entry(permission.id)
+                        .or_insert(Arc::new(Mutex::new(permission)))
+                        .clone()
+                })
+                .collect();
+            (subject_id, permissions)
+        })
+        .collect())
+}
+
 impl AuthService {
     async fn query_permission_tree(&self, entry: PermissionTreeEntry) -> AppResult<PermissionTree> {
         let user_service = UserService::new(self.app.clone());
@@ -251,6 +414,7 @@ impl AuthService {
                     role_groups: vec![],
                     user_groups: vec![],
                     departments: vec![],
+                    denied_permissions: vec![],
                 };
                 users_map.insert(user_id, Arc::new(Mutex::new(user_permissions)));
             }
@@ -274,6 +438,7 @@ impl AuthService {
                     role,
                     permissions: vec![],
                     permission_groups: vec![],
+                    denied_permissions: vec![],
                 };
                 roles_map.insert(role_id, Arc::new(Mutex::new(role_permissions)));
             }
@@ -285,6 +450,7 @@ impl AuthService {
                     role_group,
                     roles: vec![],
                     children: HashMap::new(),
+                    denied_permissions: vec![],
                 };
                 role_groups_map.insert(role_group_id, Arc::new(Mutex::new(role_group_permissions)));
             }
@@ -299,6 +465,7 @@ impl AuthService {
                     permission_groups: vec![],
                     roles: vec![],
                     role_groups: vec![],
+                    denied_permissions: vec![],
                 };
                 user_groups_map.insert(user_group_id, Arc::new(Mutex::new(user_group_permissions)));
             }
@@ -312,6 +479,7 @@ impl AuthService {
                     permission_groups: vec![],
                     roles: vec![],
                     role_groups: vec![],
+                    denied_permissions: vec![],
                 };
                 departments_map.insert(department_id, Arc::new(Mutex::new(department_permissions)));
             }
@@ -334,6 +502,7 @@ impl AuthService {
                         permission_groups: vec![],
                         roles: vec![],
                         role_groups: vec![],
+                        denied_permissions: vec![],
                     })));
             });
             // fill user departments
@@ -366,6 +535,7 @@ impl AuthService {
                         roles: vec![],
                         role_groups: vec![],
                         inherited_group: None,
+                        denied_permissions: vec![],
                     })));
             });
             // fill user groups
@@ -394,6 +564,7 @@ impl AuthService {
                     permission_groups: vec![],
                     roles: vec![],
                     role_groups: vec![],
+                    denied_permissions: vec![],
                 }))
             })
             .collect::<Vec<_>>();
@@ -428,6 +599,7 @@ impl AuthService {
                         role_group,
                         roles: vec![],
                         children: HashMap::new(),
+                        denied_permissions: vec![],
                     })));
             });
             // fill user role_groups
@@ -462,6 +634,7 @@ impl AuthService {
                             role_group,
                             roles: vec![],
                             children: HashMap::new(),
+                            denied_permissions: vec![],
                         })));
                 });
                 // fill department
@@ -496,6 +669,7 @@ impl AuthService {
                             role_group,
                             roles: vec![],
                             children: HashMap::new(),
+                            denied_permissions: vec![],
                         })));
                 });
                 // fill department
@@ -521,6 +695,7 @@ impl AuthService {
                     role_group,
                     roles: vec![],
                     children: HashMap::new(),
+                    denied_permissions: vec![],
                 })));
         });
         // fill role_group children
@@ -533,8 +708,11 @@ impl AuthService {
             }
         }
         for (id, parent_id) in parent_child_pairs {
+            // A group can be assigned without its parent, which is then not part of the tree
+            let Some(parent) = role_groups_map.get(&parent_id) else {
+                continue;
+            };
             let child = role_groups_map.get(&id).unwrap().clone();
-            let parent = role_groups_map.get_mut(&parent_id).unwrap();
             parent.lock().unwrap().children.insert(id, child);
         }
 
@@ -553,6 +731,7 @@ impl AuthService {
                         role,
                         permissions: vec![],
                         permission_groups: vec![],
+                        denied_permissions: vec![],
                     })));
             });
             // fill user roles
@@ -579,6 +758,7 @@ impl AuthService {
                         role,
                         permissions: vec![],
                         permission_groups: vec![],
+                        denied_permissions: vec![],
                     })));
             });
             // fill roles
@@ -610,6 +790,7 @@ impl AuthService {
                         role,
                         permissions: vec![],
                         permission_groups: vec![],
+                        denied_permissions: vec![],
                     })));
             });
             // fill roles
@@ -641,6 +822,7 @@ impl AuthService {
                         role,
                         permissions: vec![],
                         permission_groups: vec![],
+                        denied_permissions: vec![],
                     })));
             });
             // fill role groups
@@ -838,8 +1020,11 @@ impl AuthService {
             }
         }
         for (id, parent_id) in parent_child_pairs {
+            // A group can be assigned without its parent, which is then not part of the tree
+            let Some(parent) = permission_groups_map.get(&parent_id) else {
+                continue;
+            };
             let child = permission_groups_map.get(&id).unwrap().clone();
-            let parent = permission_groups_map.get_mut(&parent_id).unwrap();
             parent.lock().unwrap().children.insert(id, child);
         }
 
@@ -991,6 +1176,96 @@ impl AuthService {
                     .collect();
             });
 
+        // 7.1 query user denied permissions
+        let user_id_list = users_map.keys().cloned().collect::<Vec<_>>();
+        let denied_permissions = query_denied_permissions(
+            &permission_service,
+            &mut permissions_map,
+            PermissionSubjectKind::User,
+            user_id_list,
+        )
+        .await?;
+        for (user_id, permissions) in denied_permissions {
+            users_map
+                .get(&user_id)
+                .unwrap()
+                .lock()
+                .unwrap()
+                .denied_permissions = permissions;
+        }
+
+        // 7.2 query department denied permissions
+        let department_id_list = departments_map.keys().cloned().collect::<Vec<_>>();
+        let denied_permissions = query_denied_permissions(
+            &permission_service,
+            &mut permissions_map,
+            PermissionSubjectKind::Department,
+            department_id_list,
+        )
+        .await?;
+        for (department_id, permissions) in denied_permissions {
+            departments_map
+                .get(&department_id)
+                .unwrap()
+                .lock()
+                .unwrap()
+                .denied_permissions = permissions;
+        }
+
+        // 7.3 query user_group denied permissions
+        let user_group_id_list = user_groups_map.keys().cloned().collect::<Vec<_>>();
+        let denied_permissions = query_denied_permissions(
+            &permission_service,
+            &mut permissions_map,
+            PermissionSubjectKind::UserGroup,
+            user_group_id_list,
+        )
+        .await?;
+        for (user_group_id, permissions) in denied_permissions {
+            user_groups_map
+                .get(&user_group_id)
+                .unwrap()
+                .lock()
+                .unwrap()
+                .denied_permissions = permissions;
+        }
+
+        // 7.4 query role denied permissions
+        let role_id_list = roles_map.keys().cloned().collect::<Vec<_>>();
+        let denied_permissions = query_denied_permissions(
+            &permission_service,
+            &mut permissions_map,
+            PermissionSubjectKind::Role,
+            role_id_list,
+        )
+        .await?;
+        for (role_id, permissions) in denied_permissions {
+            roles_map
+                .get(&role_id)
+                .unwrap()
+                .lock()
+                .unwrap()
+                .denied_permissions = permissions;
+        }
+
+        // 7.5 query role_group denied permissions
+        let role_group_id_list = role_groups_map.keys().cloned().collect::<Vec<_>>();
+        let denied_permissions = query_denied_permissions(
+            &permission_service,
+            &mut permissions_map,
+            PermissionSubjectKind::RoleGroup,
+            role_group_id_list,
+        )
+        .await?;
+        for (role_group_id, permissions) in denied_permissions {
+            role_groups_map
+                .get(&role_group_id)
+                .unwrap()
+                .lock()
+                .unwrap()
+                .denied_permissions = permissions;
+        }
+
         Ok(PermissionTree {
             permissions_map,
             permission_groups_map,
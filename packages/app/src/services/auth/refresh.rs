@@ -0,0 +1,73 @@
+use chrono::Utc;
+
+use crate::{
+    error::AppException,
+    models::auth_token::{AuthToken, AuthTokenKind, RefreshTokenPayload},
+    result::AppResult,
+    services::{auth::AuthService, auth_token::AuthTokenService},
+};
+
+pub struct RefreshParams {
+    pub refresh_token: String,
+}
+
+pub struct RefreshResult {
+    pub access_token: String,
+    pub refresh_token: AuthToken,
+}
+
+impl AuthService {
+    /// Exchange a refresh token for a new access token and a new refresh token. Replaying a
+    /// refresh token that was already rotated revokes every session and refresh token of the
+    /// user, as there is no telling which of them the token leaked along with.
+    pub async fn refresh(&self, params: RefreshParams) -> AppResult<RefreshResult> {
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+
+        let refresh_token = auth_token_service
+            .query_auth_token_by_jwt(&params.refresh_token)
+            .await?
+            .filter(|x| x.kind == AuthTokenKind::Refresh);
+        let Some(refresh_token) = refresh_token else {
+            return Err(AppException::InvalidRefreshToken.into());
+        };
+
+        let payload = serde_json::from_str::<RefreshTokenPayload>(&refresh_token.payload).unwrap();
+        let user_id = refresh_token.ref_id;
+
+        if payload.rotated
+            || !auth_token_service
+                .rotate_refresh_token(&refresh_token)
+                .await?
+        {
+            self.logout_sessions(user_id).await?;
+            return Err(AppException::RefreshTokenReused.into());
+        }
+
+        let session = auth_token_service
+            .query_auth_token_by_id(payload.session_id)
+            .await?
+            .filter(|x| x.kind == AuthTokenKind::Session)
+            .filter(|x| !x.is_session_expired(&self.app.config.session, Utc::now()));
+        let Some(session) = session else {
+            auth_token_service
+                .delete_session_token_family(user_id, payload.session_id)
+                .await?;
+            return Err(AppException::InvalidRefreshToken.into());
+        };
+
+        // Refreshing counts as activity on the session
+        if session.should_renew_session(&self.app.config.session, Utc::now()) {
+            auth_token_service.renew_session_token(&session).await?;
+        }
+
+        let access_token = auth_token_service
+            .reissue_session_access_token(&session)
+            .await?;
+        let refresh_token = auth_token_service.create_refresh_token(&session).await?;
+
+        Ok(RefreshResult {
+            access_token,
+            refresh_token,
+        })
+    }
+}
@@ -1,11 +1,16 @@
-use shared::enums::Gender;
-use uuid::Uuid;
+use shared::enums::{Gender, RegistrationMode, SettingName};
 
 use crate::{
-    models::user::User,
+    error::{AppError, AppException},
     result::AppResult,
     services::{
-        auth::{AuthService, login::LoginParams},
+        auth::{
+            AuthService,
+            email_verification::is_valid_email,
+            login::{LoginOutcome, LoginParams},
+        },
+        invitation::InvitationService,
+        setting::SettingService,
         user::{UserService, create_user::CreateUserParams},
     },
 };
@@ -13,15 +18,85 @@ use crate::{
 pub struct RegisterParams {
     pub account: String,
     pub password: String,
+    /// Mailed a verification link. Required in `DomainRestricted` mode without an invitation, the
+    /// user is then only let in once the email is verified.
+    pub email: Option<String>,
+    pub invitation_code: Option<String>,
     pub ip: Option<String>,
     pub platform: Option<String>,
     pub agent: Option<String>,
 }
 
+/// Who may register, stored in the `settings` table.
+#[derive(Debug, Clone)]
+pub struct RegistrationSettings {
+    pub mode: RegistrationMode,
+    /// Email domains that may register in `DomainRestricted` mode, lowercase.
+    pub domains: Vec<String>,
+}
+
+impl RegistrationSettings {
+    pub fn allows_email(&self, email: &str) -> bool {
+        email
+            .rsplit_once('@')
+            .is_some_and(|(_, domain)| self.domains.contains(&domain.to_lowercase()))
+    }
+
+    /// Fail unless a user may register, `invited` or else with `email`.
+    pub fn check_registration(&self, invited: bool, email: Option<&str>) -> AppResult<()> {
+        match self.mode {
+            RegistrationMode::Disabled => Err(AppException::RegistrationDisabled.into()),
+            RegistrationMode::InviteOnly if !invited => {
+                Err(AppException::InvitationRequired.into())
+            }
+            RegistrationMode::DomainRestricted
+                if !invited && !email.is_some_and(|x| self.allows_email(x)) =>
+            {
+                Err(AppException::EmailDomainNotAllowed.into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
 impl AuthService {
-    pub async fn register(&self, params: RegisterParams) -> AppResult<(Uuid, User)> {
+    /// Create the user and log them in, or else answer with `EmailVerificationRequired` if only
+    /// the email domain let them register.
+    pub async fn register(&self, params: RegisterParams) -> AppResult<LoginOutcome> {
         let user_service = UserService::new(self.app.clone());
-        user_service
+        let invitation_service = InvitationService::new(self.app.clone());
+
+        let email = params
+            .email
+            .as_deref()
+            .map(str::trim)
+            .filter(|x| !x.is_empty());
+        if email.is_some_and(|x| !is_valid_email(x)) {
+            return Err(AppException::InvalidEmail.into());
+        }
+
+        // Who may register is settled first, so a closed registration tells nothing about
+        // which emails are taken
+        let settings = self.query_registration_settings().await?;
+        let invited = params.invitation_code.is_some();
+        settings.check_registration(invited, email)?;
+        // Anyone can type in an address of an allowed domain, owning it has to be proven first
+        let registration_pending = settings.mode == RegistrationMode::DomainRestricted && !invited;
+
+        if let Some(email) = email {
+            match user_service.query_user_by_verified_email(email).await {
+                Ok(_) => return Err(AppException::AlreadyExists.into()),
+                Err(AppError::Exception(AppException::UserNotFound)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let invitation = match &params.invitation_code {
+            Some(code) => Some(invitation_service.claim_invitation(code).await?),
+            None => None,
+        };
+
+        let user_id = user_service
             .create_user(CreateUserParams {
                 account: params.account.clone(),
                 password: params.password.clone(),
@@ -29,8 +104,26 @@ impl AuthService {
                 phone: None,
                 email: None,
                 gender: Some(Gender::Unknown),
+                registration_pending,
             })
-            .await?;
+            .await;
+        let user_id = match (user_id, &invitation) {
+            (Ok(user_id), _) => user_id,
+            (Err(err), Some(invitation)) => {
+                invitation_service.release_invitation(invitation.id).await?;
+                return Err(err);
+            }
+            (Err(err), None) => return Err(err),
+        };
+
+        if let Some(invitation) = &invitation {
+            invitation_service
+                .accept_invitation(invitation, user_id)
+                .await?;
+        }
+        if let Some(email) = email {
+            self.send_email_verification(user_id, email).await?;
+        }
 
         self.login(LoginParams {
             account: params.account,
@@ -41,4 +134,48 @@ impl AuthService {
         })
         .await
     }
+
+    /// Registration stays open if the settings are missing, as it was before they existed.
+    pub async fn query_registration_settings(&self) -> AppResult<RegistrationSettings> {
+        let setting_service = SettingService::new(self.app.clone());
+        let mode = setting_service
+            .query_setting_value(SettingName::RegistrationMode)
+            .await?
+            .and_then(|x| x.parse().ok())
+            .unwrap_or(RegistrationMode::Open);
+        let domains = setting_service
+            .query_setting_value(SettingName::RegistrationDomains)
+            .await?
+            .unwrap_or_default();
+
+        Ok(RegistrationSettings {
+            mode,
+            domains: parse_domains(&domains),
+        })
+    }
+
+    pub async fn update_registration_settings(
+        &self,
+        settings: RegistrationSettings,
+    ) -> AppResult<()> {
+        let setting_service = SettingService::new(self.app.clone());
+        setting_service
+            .update_setting_value(SettingName::RegistrationMode, settings.mode.to_string())
+            .await?;
+        let domains = parse_domains(&settings.domains.join(","));
+        setting_service
+            .update_setting_value(SettingName::RegistrationDomains, domains.join(","))
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Comma separated domains, lowercase and without a leading `@`.
+fn parse_domains(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|x| x.trim().trim_start_matches('@').to_lowercase())
+        .filter(|x| !x.is_empty())
+        .collect()
 }
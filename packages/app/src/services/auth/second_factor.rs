@@ -0,0 +1,72 @@
+use chrono::Utc;
+
+use crate::{
+    error::AppException,
+    models::auth_token::AuthTokenKind,
+    result::AppResult,
+    services::{
+        auth::{AuthService, login::LoginResult},
+        auth_token::AuthTokenService,
+        login_history::create_login_history::CreateLoginHistoryParams,
+        user::UserService,
+    },
+};
+
+pub struct SecondFactorLoginParams {
+    pub challenge_token: String,
+    /// A TOTP code or one of the recovery codes.
+    pub code: String,
+}
+
+impl AuthService {
+    /// Finish a login that `login` answered with `SecondFactorRequired`.
+    pub async fn login_with_second_factor(
+        &self,
+        params: SecondFactorLoginParams,
+    ) -> AppResult<LoginResult> {
+        let auth_token_service = AuthTokenService::new(self.app.clone());
+
+        let Some(challenge_token) = auth_token_service
+            .query_auth_token_by_jwt(&params.challenge_token)
+            .await?
+        else {
+            return Err(AppException::AuthenticationFailed.into());
+        };
+        if challenge_token.kind != AuthTokenKind::SecondFactorChallenge
+            || challenge_token.is_expired(&self.app.config.session, Utc::now())
+        {
+            return Err(AppException::AuthenticationFailed.into());
+        }
+
+        let user = UserService::new(self.app.clone())
+            .query_user_by_id(challenge_token.ref_id)
+            .await?;
+
+        let attempt = CreateLoginHistoryParams {
+            user_id: Some(user.id),
+            account: user.account.clone(),
+            ip: challenge_token.ip,
+            platform: challenge_token.platform,
+            agent: challenge_token.agent,
+            ..Default::default()
+        };
+
+        // The account may have been locked meanwhile by failures of another challenge
+        if user.locked_until.is_some_and(|x| x > Utc::now()) {
+            return self.fail_login(attempt, AppException::AccountLocked).await;
+        }
+
+        if !self.verify_second_factor(&user, &params.code).await? {
+            self.count_failed_login(user.id).await?;
+            return self
+                .fail_login(attempt, AppException::AuthenticationFailed)
+                .await;
+        }
+
+        auth_token_service
+            .delete_auth_token_by_id(challenge_token.id)
+            .await?;
+
+        self.complete_login(user, attempt).await
+    }
+}
@@ -0,0 +1,93 @@
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        auth_token::{AuthToken, SessionTokenPayload},
+        data_scope::DataScopeRule,
+    },
+    result::AppResult,
+    services::{
+        auth::AuthService, auth_token::AuthTokenService, department::DepartmentService,
+        role::RoleService, user_group::UserGroupService,
+    },
+};
+
+impl AuthService {
+    /// Resolve the current grants of the user into a session payload.
+    pub async fn build_session_payload(&self, user_id: Uuid) -> AppResult<SessionTokenPayload> {
+        let role_service = RoleService::new(self.app.clone());
+        let group_service = UserGroupService::new(self.app.clone());
+        let department_service = DepartmentService::new(self.app.clone());
+
+        // Read the version first, so a change racing with the resolution below leaves the
+        // payload stale rather than silently outdated.
+        let permission_version = self.query_permission_version().await?;
+
+        let permissions = self
+            .query_effective_permissions(user_id, permission_version)
+            .await?;
+        let roles = role_service.query_roles_by_user_id(user_id).await?;
+        let groups = group_service.query_user_groups_by_user_id(user_id).await?;
+        let departments = department_service
+            .query_departments_by_user_id(user_id)
+            .await?;
+
+        Ok(SessionTokenPayload {
+            user_id,
+            data_scopes: permissions
+                .iter()
+                .filter_map(DataScopeRule::from_permission)
+                .collect(),
+            permissions: permissions.iter().map(|x| x.code.clone()).collect(),
+            roles: roles.into_iter().map(|x| x.id).collect(),
+            groups: groups.into_iter().map(|x| x.id).collect(),
+            departments: departments.into_iter().map(|x| x.id).collect(),
+            permission_version,
+            name: None,
+            scope: None,
+            application_id: None,
+            impersonator_id: None,
+        })
+    }
+
+    /// Payload of a session token, resolved again if the permission graph changed since it was
+    /// stored. An impersonation the impersonator may no longer carry on with is ended, and
+    /// comes back as `None`.
+    pub async fn query_session_payload(
+        &self,
+        auth_token: &AuthToken,
+    ) -> AppResult<Option<SessionTokenPayload>> {
+        let payload = serde_json::from_str::<SessionTokenPayload>(&auth_token.payload).unwrap();
+
+        let permission_version = self.query_permission_version().await?;
+        if payload.permission_version == permission_version {
+            return Ok(Some(payload));
+        }
+
+        let mut payload = SessionTokenPayload {
+            name: payload.name,
+            scope: payload.scope,
+            application_id: payload.application_id,
+            impersonator_id: payload.impersonator_id,
+            ..self.build_session_payload(payload.user_id).await?
+        };
+        payload.restrict_to_scope();
+
+        if let Some(impersonator_id) = payload.impersonator_id {
+            let impersonator_payload = self.build_session_payload(impersonator_id).await?;
+            if !self
+                .may_impersonate(&impersonator_payload, &payload)
+                .await?
+            {
+                self.logout(auth_token.id).await?;
+                return Ok(None);
+            }
+        }
+
+        AuthTokenService::new(self.app.clone())
+            .update_session_token_payload(auth_token.id, &payload)
+            .await?;
+
+        Ok(Some(payload))
+    }
+}
@@ -0,0 +1,128 @@
+use chrono::Utc;
+use shared::utils::{generate_totp_secret, totp_uri, verify_totp};
+use uuid::Uuid;
+
+use crate::{
+    error::AppException,
+    models::user::User,
+    result::AppResult,
+    services::{auth::AuthService, recovery_code::RecoveryCodeService, user::UserService},
+};
+
+pub struct TotpEnrollment {
+    pub secret: String,
+    /// `otpauth://` URI for authenticator apps, usually shown as a QR code.
+    pub uri: String,
+}
+
+impl AuthService {
+    /// Generate a new TOTP secret for the user. It only takes effect once a code of it is
+    /// confirmed with `enable_totp`.
+    pub async fn enroll_totp(&self, user_id: Uuid) -> AppResult<TotpEnrollment> {
+        let user_service = UserService::new(self.app.clone());
+        let user = user_service.query_user_by_id(user_id).await?;
+
+        if user.totp_enabled {
+            return Err(AppException::TotpAlreadyEnabled.into());
+        }
+
+        let secret = generate_totp_secret();
+        user_service
+            .update_totp_secret(user_id, Some(secret.clone()))
+            .await?;
+
+        let uri = totp_uri(&self.app.config.login.totp_issuer, &user.account, &secret);
+
+        Ok(TotpEnrollment { secret, uri })
+    }
+
+    /// Turn TOTP on after checking the first code of the enrolled secret. Returns the recovery
+    /// codes, which are only ever shown here.
+    pub async fn enable_totp(&self, user_id: Uuid, code: &str) -> AppResult<Vec<String>> {
+        let user_service = UserService::new(self.app.clone());
+        let user = user_service.query_user_by_id(user_id).await?;
+
+        if user.totp_enabled {
+            return Err(AppException::TotpAlreadyEnabled.into());
+        }
+        if user.totp_secret.is_none() {
+            return Err(AppException::TotpNotEnrolled.into());
+        }
+        if !self.verify_totp_code(&user, code).await? {
+            return Err(AppException::InvalidTotpCode.into());
+        }
+
+        user_service.enable_totp(user_id).await?;
+
+        RecoveryCodeService::new(self.app.clone())
+            .regenerate_recovery_codes(user_id)
+            .await
+    }
+
+    /// Turn TOTP off, given a current code or a recovery code.
+    pub async fn disable_totp(&self, user_id: Uuid, code: &str) -> AppResult<()> {
+        let user_service = UserService::new(self.app.clone());
+        let user = user_service.query_user_by_id(user_id).await?;
+
+        if !user.totp_enabled {
+            return Err(AppException::TotpNotEnrolled.into());
+        }
+        if !self.verify_second_factor(&user, code).await? {
+            return Err(AppException::InvalidTotpCode.into());
+        }
+
+        user_service.update_totp_secret(user_id, None).await?;
+        RecoveryCodeService::new(self.app.clone())
+            .delete_recovery_codes(user_id)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Replace the recovery codes of the user, given a current code.
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> AppResult<Vec<String>> {
+        let user = UserService::new(self.app.clone())
+            .query_user_by_id(user_id)
+            .await?;
+
+        if !user.totp_enabled {
+            return Err(AppException::TotpNotEnrolled.into());
+        }
+        if !self.verify_totp_code(&user, code).await? {
+            return Err(AppException::InvalidTotpCode.into());
+        }
+
+        RecoveryCodeService::new(self.app.clone())
+            .regenerate_recovery_codes(user_id)
+            .await
+    }
+
+    /// Check a TOTP code, or failing that spend a recovery code.
+    pub(super) async fn verify_second_factor(&self, user: &User, code: &str) -> AppResult<bool> {
+        if self.verify_totp_code(user, code).await? {
+            return Ok(true);
+        }
+
+        RecoveryCodeService::new(self.app.clone())
+            .use_recovery_code(user.id, code)
+            .await
+    }
+
+    /// Check a TOTP code of the user's secret. A code is accepted at most once.
+    async fn verify_totp_code(&self, user: &User, code: &str) -> AppResult<bool> {
+        let Some(secret) = &user.totp_secret else {
+            return Ok(false);
+        };
+        let Some(step) = verify_totp(secret, code.trim(), Utc::now().timestamp()) else {
+            return Ok(false);
+        };
+
+        UserService::new(self.app.clone())
+            .update_totp_last_used_step(user.id, step)
+            .await
+    }
+}
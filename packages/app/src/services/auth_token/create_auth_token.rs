@@ -1,78 +1,260 @@
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Utc};
 use entity::auth_tokens;
 use jsonwebtoken::{EncodingKey, Header, encode};
 use sea_orm::{
+    ActiveModelTrait,
     ActiveValue::{NotSet, Set},
-    EntityTrait,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
-    models::auth_token::{AuthTokenKind, SessionTokenClaims, SessionTokenPayload},
+    models::auth_token::{
+        AuthToken, AuthTokenClaims, AuthTokenKind, RefreshTokenPayload, SessionTokenPayload,
+    },
     result::AppResult,
     services::auth_token::AuthTokenService,
 };
 
-const SECRET: &[u8; 6] = b"secret";
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateSessionTokenParams {
     pub ip: Option<String>,
     pub platform: Option<String>,
     pub agent: Option<String>,
     pub expired_at: Option<DateTime<Utc>>,
+    pub payload: SessionTokenPayload,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreatePersonalAccessTokenParams {
+    pub expired_at: DateTime<Utc>,
+    pub payload: SessionTokenPayload,
+}
+
+pub struct CreateOAuthAccessTokenParams {
+    pub expired_at: DateTime<Utc>,
+    pub payload: SessionTokenPayload,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateLoginChallengeTokenParams {
+    /// `SecondFactorChallenge` or `PasswordChange`.
+    pub kind: AuthTokenKind,
     pub user_id: Uuid,
-    pub permissions: Vec<String>,
-    pub roles: Vec<Uuid>,
-    pub groups: Vec<Uuid>,
-    pub departments: Vec<Uuid>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
 }
 
-impl AuthTokenService {
-    pub async fn create_session_token(&self, params: CreateSessionTokenParams) -> AppResult<Uuid> {
-        let claims = SessionTokenClaims {
-            sub: params.user_id,
-            exp: params
-                .expired_at
-                .unwrap_or_else(|| Utc::now() + Duration::days(365))
-                .timestamp(),
-        };
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateOneTimeTokenParams {
+    pub kind: AuthTokenKind,
+    pub user_id: Uuid,
+    pub payload: String,
+    pub expired_at: DateTime<Utc>,
+}
 
-        let token = encode(
+/// Who a token is handed out to: the client of a login, or the application of an OAuth access
+/// token.
+#[derive(Default)]
+struct IssuedTo {
+    ip: Option<String>,
+    platform: Option<String>,
+    agent: Option<String>,
+    application_id: Option<Uuid>,
+}
+
+impl AuthTokenService {
+    pub(crate) fn encode_jwt(&self, claims: &AuthTokenClaims) -> String {
+        encode(
             &Header::default(),
-            &claims,
-            &EncodingKey::from_secret(SECRET),
+            claims,
+            &EncodingKey::from_secret(self.app.config.jwt.secret.as_bytes()),
         )
-        .unwrap();
-
-        let payload = SessionTokenPayload {
-            user_id: params.user_id,
-            permissions: params.permissions,
-            roles: params.roles,
-            groups: params.groups,
-            departments: params.departments,
+        .unwrap()
+    }
+
+    /// Access token of a session: a JWT valid for the access token lifetime, never outliving the
+    /// session itself.
+    pub(crate) fn encode_access_token(
+        &self,
+        session_id: Uuid,
+        user_id: Uuid,
+        absolute_expired_at: DateTime<Utc>,
+    ) -> String {
+        let exp =
+            (Utc::now() + self.app.config.session.access_token_lifetime()).min(absolute_expired_at);
+
+        self.encode_jwt(&AuthTokenClaims {
+            jti: session_id,
+            sub: user_id,
+            exp: exp.timestamp(),
+        })
+    }
+
+    /// Insert a token of `kind` for `ref_id`, signed into a JWT that expires with it. A session
+    /// is handed its access token instead, see `encode_access_token`.
+    async fn insert_auth_token(
+        &self,
+        kind: AuthTokenKind,
+        ref_id: Uuid,
+        payload: String,
+        expired_at: DateTime<Utc>,
+        issued_to: IssuedTo,
+    ) -> AppResult<AuthToken> {
+        let id = Uuid::new_v4();
+        let token = match kind {
+            AuthTokenKind::Session => {
+                let absolute_expired_at = Utc::now() + self.app.config.session.absolute_lifetime();
+                self.encode_access_token(id, ref_id, absolute_expired_at)
+            }
+            _ => self.encode_jwt(&AuthTokenClaims {
+                jti: id,
+                sub: ref_id,
+                exp: expired_at.timestamp(),
+            }),
         };
 
         let active_model = auth_tokens::ActiveModel {
-            id: Set(Uuid::new_v4()),
-            kind: Set(AuthTokenKind::Session.to_string()),
-            ref_id: Set(params.user_id),
+            id: Set(id),
+            kind: Set(kind.to_string()),
+            ref_id: Set(ref_id),
             token: Set(token),
-            ip: Set(params.ip),
-            platform: Set(params.platform),
-            agent: Set(params.agent),
-            payload: Set(serde_json::to_string(&payload).unwrap()),
-            expired_at: Set(params.expired_at.map(Into::into)),
+            ip: Set(issued_to.ip),
+            platform: Set(issued_to.platform),
+            agent: Set(issued_to.agent),
+            payload: Set(payload),
+            expired_at: Set(Some(expired_at.into())),
+            last_seen_at: NotSet,
+            last_seen_ip: NotSet,
+            device_name: NotSet,
+            application_id: Set(issued_to.application_id),
             is_deleted: NotSet,
             created_at: NotSet,
             updated_at: NotSet,
         };
 
-        let result = auth_tokens::Entity::insert(active_model)
-            .exec(&self.conn)
-            .await?;
+        let model = active_model.insert(&self.conn).await?;
+
+        Ok(model.into())
+    }
+
+    pub async fn create_session_token(
+        &self,
+        params: CreateSessionTokenParams,
+    ) -> AppResult<AuthToken> {
+        let session_config = &self.app.config.session;
+        let now = Utc::now();
+        let expired_at = params
+            .expired_at
+            .unwrap_or_else(|| now + session_config.idle_timeout())
+            .min(now + session_config.absolute_lifetime());
+
+        self.insert_auth_token(
+            AuthTokenKind::Session,
+            params.payload.user_id,
+            serde_json::to_string(&params.payload).unwrap(),
+            expired_at,
+            IssuedTo {
+                ip: params.ip,
+                platform: params.platform,
+                agent: params.agent,
+                application_id: None,
+            },
+        )
+        .await
+    }
+
+    /// Mint a refresh token for the session, valid until the end of the session's absolute
+    /// lifetime.
+    pub async fn create_refresh_token(&self, session: &AuthToken) -> AppResult<AuthToken> {
+        let payload = RefreshTokenPayload {
+            session_id: session.id,
+            rotated: false,
+        };
 
-        Ok(result.last_insert_id)
+        self.insert_auth_token(
+            AuthTokenKind::Refresh,
+            session.ref_id,
+            serde_json::to_string(&payload).unwrap(),
+            session.absolute_expired_at(&self.app.config.session),
+            IssuedTo {
+                ip: session.ip.clone(),
+                platform: session.platform.clone(),
+                agent: session.agent.clone(),
+                application_id: None,
+            },
+        )
+        .await
+    }
+
+    pub async fn create_personal_access_token(
+        &self,
+        params: CreatePersonalAccessTokenParams,
+    ) -> AppResult<AuthToken> {
+        self.insert_auth_token(
+            AuthTokenKind::PersonalAccess,
+            params.payload.user_id,
+            serde_json::to_string(&params.payload).unwrap(),
+            params.expired_at,
+            IssuedTo::default(),
+        )
+        .await
+    }
+
+    /// Mint an access token for an application, acting for `payload.user_id`.
+    pub async fn create_oauth_access_token(
+        &self,
+        params: CreateOAuthAccessTokenParams,
+    ) -> AppResult<AuthToken> {
+        self.insert_auth_token(
+            AuthTokenKind::OAuthAccess,
+            params.payload.user_id,
+            serde_json::to_string(&params.payload).unwrap(),
+            params.expired_at,
+            IssuedTo {
+                application_id: params.payload.application_id,
+                ..Default::default()
+            },
+        )
+        .await
+    }
+
+    /// Mint the short-lived token a login is resumed with once the second factor is entered or
+    /// the expired password replaced. It keeps the client metadata of the login for the session
+    /// created afterwards.
+    pub async fn create_login_challenge_token(
+        &self,
+        params: CreateLoginChallengeTokenParams,
+    ) -> AppResult<AuthToken> {
+        self.insert_auth_token(
+            params.kind,
+            params.user_id,
+            "{}".to_string(),
+            Utc::now() + self.app.config.login.second_factor_timeout(),
+            IssuedTo {
+                ip: params.ip,
+                platform: params.platform,
+                agent: params.agent,
+                application_id: None,
+            },
+        )
+        .await
+    }
+
+    /// Mint a token that is spent by `take_one_time_token`, such as an email verification or a
+    /// password reset token.
+    pub async fn create_one_time_token(
+        &self,
+        params: CreateOneTimeTokenParams,
+    ) -> AppResult<AuthToken> {
+        self.insert_auth_token(
+            params.kind,
+            params.user_id,
+            params.payload,
+            params.expired_at,
+            IssuedTo::default(),
+        )
+        .await
     }
 }
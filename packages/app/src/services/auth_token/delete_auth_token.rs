@@ -1,7 +1,12 @@
+use chrono::Utc;
 use entity::auth_tokens;
 use sea_orm::prelude::*;
 
-use crate::{result::AppResult, services::auth_token::AuthTokenService};
+use crate::{
+    models::auth_token::{AuthToken, AuthTokenKind, RefreshTokenPayload},
+    result::AppResult,
+    services::auth_token::AuthTokenService,
+};
 
 impl AuthTokenService {
     pub async fn delete_auth_token_by_token(&self, token: &str) -> AppResult<()> {
@@ -14,7 +19,9 @@ impl AuthTokenService {
     }
 
     pub async fn delete_auth_token_by_id(&self, id: Uuid) -> AppResult<()> {
-        auth_tokens::Entity::delete_by_id(id).exec(&self.conn).await?;
+        auth_tokens::Entity::delete_by_id(id)
+            .exec(&self.conn)
+            .await?;
 
         Ok(())
     }
@@ -27,4 +34,122 @@ impl AuthTokenService {
 
         Ok(())
     }
+
+    pub async fn delete_auth_tokens_by_ref_id_and_kind(
+        &self,
+        ref_id: Uuid,
+        kind: AuthTokenKind,
+    ) -> AppResult<()> {
+        auth_tokens::Entity::delete_many()
+            .filter(auth_tokens::Column::RefId.eq(ref_id))
+            .filter(auth_tokens::Column::Kind.eq(kind.to_string()))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Spend a one-time token: it is deleted and returned if it is of `kind`, unexpired and not
+    /// spent already.
+    pub async fn take_one_time_token(
+        &self,
+        jwt: &str,
+        kind: AuthTokenKind,
+    ) -> AppResult<Option<AuthToken>> {
+        let Some(auth_token) = self.query_auth_token_by_jwt(jwt).await? else {
+            return Ok(None);
+        };
+        if auth_token.kind != kind || auth_token.is_expired(&self.app.config.session, Utc::now()) {
+            return Ok(None);
+        }
+
+        if !self.spend_one_time_token(auth_token.id).await? {
+            return Ok(None);
+        }
+
+        Ok(Some(auth_token))
+    }
+
+    /// Delete a one-time token that is about to be used. Returns `false` if it was spent
+    /// concurrently: only the request that actually deletes the token gets to use it.
+    pub async fn spend_one_time_token(&self, id: Uuid) -> AppResult<bool> {
+        let result = auth_tokens::Entity::delete_by_id(id)
+            .exec(&self.conn)
+            .await?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    /// Revoke a session together with every refresh token minted for it.
+    pub async fn delete_session_token_family(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> AppResult<()> {
+        let refresh_tokens = self
+            .query_auth_tokens_by_ref_id_and_kind(user_id, AuthTokenKind::Refresh)
+            .await?;
+
+        let mut id_list = refresh_tokens
+            .into_iter()
+            .filter(|x| {
+                serde_json::from_str::<RefreshTokenPayload>(&x.payload)
+                    .is_ok_and(|payload| payload.session_id == session_id)
+            })
+            .map(|x| x.id)
+            .collect::<Vec<_>>();
+        id_list.push(session_id);
+
+        auth_tokens::Entity::delete_many()
+            .filter(auth_tokens::Column::Id.is_in(id_list))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Revoke every session of the user but `session_id`, together with their refresh tokens.
+    pub async fn delete_other_session_tokens(
+        &self,
+        user_id: Uuid,
+        session_id: Uuid,
+    ) -> AppResult<()> {
+        let refresh_tokens = self
+            .query_auth_tokens_by_ref_id_and_kind(user_id, AuthTokenKind::Refresh)
+            .await?;
+        let sessions = self
+            .query_auth_tokens_by_ref_id_and_kind(user_id, AuthTokenKind::Session)
+            .await?;
+
+        let id_list = refresh_tokens
+            .into_iter()
+            .filter(|x| {
+                serde_json::from_str::<RefreshTokenPayload>(&x.payload)
+                    .is_ok_and(|payload| payload.session_id != session_id)
+            })
+            .chain(sessions.into_iter().filter(|x| x.id != session_id))
+            .map(|x| x.id)
+            .collect::<Vec<_>>();
+
+        auth_tokens::Entity::delete_many()
+            .filter(auth_tokens::Column::Id.is_in(id_list))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Revoke every access token issued to the applications.
+    pub async fn delete_auth_tokens_by_application_id(
+        &self,
+        application_id_list: &[Uuid],
+    ) -> AppResult<()> {
+        auth_tokens::Entity::delete_many()
+            .filter(auth_tokens::Column::Kind.eq(AuthTokenKind::OAuthAccess.to_string()))
+            .filter(auth_tokens::Column::ApplicationId.is_in(application_id_list.to_vec()))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
 }
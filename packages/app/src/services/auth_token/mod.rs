@@ -4,5 +4,6 @@ use crate::impl_service;
 pub mod create_auth_token;
 pub mod delete_auth_token;
 pub mod query_auth_tokens;
+pub mod update_auth_token;
 
 impl_service!(AuthTokenService, auth_tokens::Entity);
@@ -1,7 +1,10 @@
 use crate::{
-    models::auth_token::AuthToken, result::AppResult, services::auth_token::AuthTokenService,
+    models::auth_token::{AuthToken, AuthTokenClaims, AuthTokenKind},
+    result::AppResult,
+    services::auth_token::AuthTokenService,
 };
 use entity::auth_tokens;
+use jsonwebtoken::{DecodingKey, Validation, decode};
 use sea_orm::prelude::*;
 
 impl AuthTokenService {
@@ -11,6 +14,23 @@ impl AuthTokenService {
         Ok(auth_token.map(Into::into))
     }
 
+    /// Look up the auth token a JWT was minted for. Returns `None` if the signature is invalid,
+    /// the JWT has expired, or it no longer matches a stored token.
+    pub async fn query_auth_token_by_jwt(&self, jwt: &str) -> AppResult<Option<AuthToken>> {
+        let Ok(token_data) = decode::<AuthTokenClaims>(
+            jwt,
+            &DecodingKey::from_secret(self.app.config.jwt.secret.as_bytes()),
+            &Validation::default(),
+        ) else {
+            return Ok(None);
+        };
+        let claims = token_data.claims;
+
+        let auth_token = self.query_auth_token_by_id(claims.jti).await?;
+
+        Ok(auth_token.filter(|x| x.token == jwt && x.ref_id == claims.sub))
+    }
+
     pub async fn query_auth_tokens_by_ref_id(&self, ref_id: Uuid) -> AppResult<Vec<AuthToken>> {
         let auth_token = auth_tokens::Entity::find()
             .filter(auth_tokens::Column::RefId.eq(ref_id))
@@ -19,4 +39,18 @@ impl AuthTokenService {
 
         Ok(auth_token.into_iter().map(Into::into).collect())
     }
+
+    pub async fn query_auth_tokens_by_ref_id_and_kind(
+        &self,
+        ref_id: Uuid,
+        kind: AuthTokenKind,
+    ) -> AppResult<Vec<AuthToken>> {
+        let auth_token = auth_tokens::Entity::find()
+            .filter(auth_tokens::Column::RefId.eq(ref_id))
+            .filter(auth_tokens::Column::Kind.eq(kind.to_string()))
+            .all(&self.conn)
+            .await?;
+
+        Ok(auth_token.into_iter().map(Into::into).collect())
+    }
 }
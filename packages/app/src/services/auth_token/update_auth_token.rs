@@ -0,0 +1,159 @@
+use chrono::{DateTime, Duration, Utc};
+use entity::auth_tokens;
+use sea_orm::{Condition, prelude::*};
+
+use crate::{
+    models::auth_token::{AuthToken, EmailLoginPayload, RefreshTokenPayload, SessionTokenPayload},
+    result::AppResult,
+    services::auth_token::AuthTokenService,
+};
+
+/// How often the last activity of a token is recorded at most.
+const LAST_SEEN_INTERVAL_SECONDS: i64 = 60;
+
+impl AuthTokenService {
+    /// Slide the expiry of a session token forward by the idle timeout, capped at the end of
+    /// its absolute lifetime. Returns the new expiry.
+    pub async fn renew_session_token(&self, auth_token: &AuthToken) -> AppResult<DateTime<Utc>> {
+        let expired_at =
+            auth_token.renewed_session_expired_at(&self.app.config.session, Utc::now());
+
+        auth_tokens::Entity::update_many()
+            .col_expr(
+                auth_tokens::Column::ExpiredAt,
+                Expr::value(Some(DateTimeWithTimeZone::from(expired_at))),
+            )
+            .filter(auth_tokens::Column::Id.eq(auth_token.id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(expired_at)
+    }
+
+    /// Record that the token was just used from `ip`. Skipped if it was recorded less than a
+    /// minute ago, so busy clients don't cause a write per request.
+    pub async fn touch_auth_token(
+        &self,
+        auth_token: &AuthToken,
+        ip: Option<String>,
+    ) -> AppResult<()> {
+        let now = Utc::now();
+        let threshold = now - Duration::seconds(LAST_SEEN_INTERVAL_SECONDS);
+        if auth_token.last_seen_at.is_some_and(|x| x > threshold) {
+            return Ok(());
+        }
+
+        // Concurrent requests all read the old timestamp, only the first one writes
+        auth_tokens::Entity::update_many()
+            .col_expr(
+                auth_tokens::Column::LastSeenAt,
+                Expr::value(Some(DateTimeWithTimeZone::from(now))),
+            )
+            .col_expr(auth_tokens::Column::LastSeenIp, Expr::value(ip))
+            .filter(auth_tokens::Column::Id.eq(auth_token.id))
+            .filter(
+                Condition::any()
+                    .add(auth_tokens::Column::LastSeenAt.is_null())
+                    .add(auth_tokens::Column::LastSeenAt.lte(threshold)),
+            )
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_device_name(&self, id: Uuid, device_name: Option<String>) -> AppResult<()> {
+        auth_tokens::Entity::update_many()
+            .col_expr(auth_tokens::Column::DeviceName, Expr::value(device_name))
+            .filter(auth_tokens::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_session_token_payload(
+        &self,
+        id: Uuid,
+        payload: &SessionTokenPayload,
+    ) -> AppResult<()> {
+        auth_tokens::Entity::update_many()
+            .col_expr(
+                auth_tokens::Column::Payload,
+                Expr::value(serde_json::to_string(payload).unwrap()),
+            )
+            .filter(auth_tokens::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Replace the access token of a session, invalidating the previous one. Returns the new
+    /// access token.
+    pub async fn reissue_session_access_token(&self, session: &AuthToken) -> AppResult<String> {
+        let absolute_expired_at = session.absolute_expired_at(&self.app.config.session);
+        let token = self.encode_access_token(session.id, session.ref_id, absolute_expired_at);
+
+        auth_tokens::Entity::update_many()
+            .col_expr(auth_tokens::Column::Token, Expr::value(token.clone()))
+            .filter(auth_tokens::Column::Id.eq(session.id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(token)
+    }
+
+    /// Mark a refresh token as rotated. Returns `false` if it was rotated concurrently, in which
+    /// case the caller lost the race and must treat the token as replayed.
+    pub async fn rotate_refresh_token(&self, refresh_token: &AuthToken) -> AppResult<bool> {
+        let mut payload =
+            serde_json::from_str::<RefreshTokenPayload>(&refresh_token.payload).unwrap();
+        payload.rotated = true;
+
+        let result = auth_tokens::Entity::update_many()
+            .col_expr(
+                auth_tokens::Column::Payload,
+                Expr::value(serde_json::to_string(&payload).unwrap()),
+            )
+            .filter(auth_tokens::Column::Id.eq(refresh_token.id))
+            .filter(auth_tokens::Column::Payload.eq(&refresh_token.payload))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    /// Count a wrong code against an email login token, dropping the token once `max_attempts`
+    /// wrong codes were tried. A token updated concurrently is dropped right away, rather than
+    /// letting parallel guesses slip past the count.
+    pub async fn count_failed_email_login_code(
+        &self,
+        auth_token: &AuthToken,
+        max_attempts: u32,
+    ) -> AppResult<()> {
+        let mut payload = serde_json::from_str::<EmailLoginPayload>(&auth_token.payload).unwrap();
+        payload.failed_attempts += 1;
+
+        if payload.failed_attempts < max_attempts {
+            let result = auth_tokens::Entity::update_many()
+                .col_expr(
+                    auth_tokens::Column::Payload,
+                    Expr::value(serde_json::to_string(&payload).unwrap()),
+                )
+                .filter(auth_tokens::Column::Id.eq(auth_token.id))
+                .filter(auth_tokens::Column::Payload.eq(&auth_token.payload))
+                .exec(&self.conn)
+                .await?;
+            if result.rows_affected == 1 {
+                return Ok(());
+            }
+        }
+
+        auth_tokens::Entity::delete_by_id(auth_token.id)
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+}
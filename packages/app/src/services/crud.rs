@@ -1,14 +1,21 @@
 use migration::{Alias, IntoCondition, SimpleExpr};
-use sea_orm::{IntoActiveModel, QueryOrder, QuerySelect, QueryTrait, prelude::*, sea_query};
+use sea_orm::{
+    Condition, IntoActiveModel, QueryOrder, QuerySelect, QueryTrait, prelude::*, sea_query,
+};
 use std::marker::PhantomData;
 
-use crate::utils::query::{Cursor, QueryCondition};
+use crate::utils::{
+    data_scope::{DataScopeFilter, DataScoped},
+    query::{Cursor, QueryCondition},
+};
 
 pub struct Crud<T>
 where
     T: EntityTrait + Send + Sync,
 {
     db: DatabaseConnection,
+    /// Added to every `find_by_condition*` and `count_by_condition` query.
+    data_scope: Option<Condition>,
     _marker: PhantomData<T>,
 }
 
@@ -19,9 +26,18 @@ where
     pub fn new(db: DatabaseConnection) -> Self {
         Self {
             db,
+            data_scope: None,
             _marker: PhantomData,
         }
     }
+
+    pub fn with_data_scope(mut self, data_scope: &DataScopeFilter) -> Self
+    where
+        T: DataScoped,
+    {
+        self.data_scope = data_scope.condition::<T>();
+        self
+    }
 }
 
 impl<T> Crud<T>
@@ -64,8 +80,11 @@ where
         Ok(res)
     }
 
-    fn find_condition_to_select(condition: QueryCondition) -> Select<T> {
+    fn find_condition_to_select(&self, condition: QueryCondition) -> Select<T> {
         let mut select_query = T::find();
+        if let Some(data_scope) = &self.data_scope {
+            select_query = select_query.filter(data_scope.clone());
+        }
         let QueryCondition {
             condition,
             orders,
@@ -95,7 +114,7 @@ where
         let query_condition: QueryCondition = condition.into();
         let count_condition: QueryCondition = query_condition.clone();
         let count = self.count_by_condition(count_condition).await?;
-        let select_query = Self::find_condition_to_select(query_condition);
+        let select_query = self.find_condition_to_select(query_condition);
         let res = select_query.all(&self.db).await?;
         Ok((res, count))
     }
@@ -105,7 +124,7 @@ where
         condition: impl Into<QueryCondition>,
     ) -> Result<Vec<<T as EntityTrait>::Model>, DbErr> {
         let query_condition = condition.into();
-        let select_query = Self::find_condition_to_select(query_condition);
+        let select_query = self.find_condition_to_select(query_condition);
         let res = select_query.all(&self.db).await?;
         Ok(res)
     }
@@ -115,7 +134,7 @@ where
         condition: impl Into<QueryCondition>,
     ) -> Result<Option<<T as EntityTrait>::Model>, DbErr> {
         let query_condition = condition.into();
-        let select_query = Self::find_condition_to_select(query_condition);
+        let select_query = self.find_condition_to_select(query_condition);
         let res = select_query.one(&self.db).await?;
         Ok(res)
     }
@@ -126,7 +145,7 @@ where
     ) -> Result<i64, DbErr> {
         let mut condition: QueryCondition = condition.into();
         condition = condition.clear_cursor().clear_orders();
-        let count_query = Self::find_condition_to_select(condition);
+        let count_query = self.find_condition_to_select(condition);
         let mut count_query = count_query.select_only().into_query();
         let builder = self.db.get_database_backend();
         count_query.expr_as(
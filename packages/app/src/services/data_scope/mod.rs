@@ -0,0 +1,68 @@
+use std::future::Future;
+
+use entity::departments;
+use shared::enums::DataScope;
+use uuid::Uuid;
+
+use crate::{
+    impl_service,
+    models::auth_token::SessionTokenPayload,
+    result::AppResult,
+    utils::{data_scope::DataScopeFilter, query::TreeQuery},
+};
+
+impl_service!(DataScopeService, departments::Entity);
+
+/// Implemented by every service through `impl_service!`. Only the queries of services declared
+/// with `impl_service!(.., data_scoped)` are restricted to the data scope of a session, the
+/// others ignore it.
+pub trait DataScopedService: Sized + Send {
+    /// Whether the service is handed to requests restricted to the data scope of the caller.
+    const DATA_SCOPED: bool = false;
+
+    /// Restrict every `find_by_condition*` query of the service to the rows the session may see.
+    fn scope_to(
+        self,
+        _payload: &SessionTokenPayload,
+    ) -> impl Future<Output = AppResult<Self>> + Send {
+        async { Ok(self) }
+    }
+}
+
+impl DataScopeService {
+    /// Union of the scopes granted by the data permissions of the session.
+    pub async fn resolve_data_scope(
+        &self,
+        payload: &SessionTokenPayload,
+    ) -> AppResult<DataScopeFilter> {
+        let mut department_ids = vec![];
+        let mut tree_department_ids = vec![];
+        for rule in &payload.data_scopes {
+            match rule.scope {
+                DataScope::All => return Ok(DataScopeFilter::All),
+                DataScope::SelfOnly => {}
+                DataScope::Department => department_ids.extend(&payload.departments),
+                DataScope::DepartmentTree => tree_department_ids.extend(&payload.departments),
+                DataScope::CustomDepartments => department_ids.extend(&rule.department_ids),
+            }
+        }
+
+        if !tree_department_ids.is_empty() {
+            let descendants = TreeQuery::new(departments::Entity)
+                .query_descendants_with_many(&self.conn, distinct(tree_department_ids))
+                .await?;
+            department_ids.extend(descendants.into_iter().map(|x| x.id));
+        }
+
+        Ok(DataScopeFilter::Restricted {
+            user_id: payload.user_id,
+            department_ids: distinct(department_ids),
+        })
+    }
+}
+
+fn distinct(mut id_list: Vec<Uuid>) -> Vec<Uuid> {
+    id_list.sort();
+    id_list.dedup();
+    id_list
+}
@@ -2,7 +2,7 @@ use entity::departments;
 use sea_orm::prelude::*;
 use sea_orm::{EntityTrait, prelude::Uuid};
 
-use crate::result::AppResult;
+use crate::{result::AppResult, services::auth::AuthService};
 
 use super::DepartmentService;
 
@@ -16,6 +16,10 @@ impl DepartmentService {
             .exec(&self.conn)
             .await?;
 
+        AuthService::new(self.app.clone())
+            .bump_permission_version()
+            .await?;
+
         Ok(())
     }
 }
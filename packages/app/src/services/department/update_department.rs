@@ -1,7 +1,7 @@
 use entity::departments;
 use sea_orm::{ActiveValue::Set, IntoActiveModel, prelude::*};
 
-use crate::{error::AppException, result::AppResult};
+use crate::{error::AppException, result::AppResult, services::auth::AuthService};
 
 use super::DepartmentService;
 
@@ -52,6 +52,10 @@ impl DepartmentService {
 
         active_model.update(&self.conn).await?;
 
+        AuthService::new(self.app.clone())
+            .bump_permission_version()
+            .await?;
+
         Ok(())
     }
 }
@@ -0,0 +1,41 @@
+use entity::impersonations;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+use uuid::Uuid;
+
+use crate::result::AppResult;
+
+use super::ImpersonationService;
+
+#[derive(Debug, Default)]
+pub struct CreateImpersonationParams {
+    pub user_id: Uuid,
+    pub impersonator_id: Uuid,
+    pub impersonator_account: String,
+    pub session_id: Uuid,
+    pub reason: Option<String>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+}
+
+impl ImpersonationService {
+    pub async fn create_impersonation(&self, params: CreateImpersonationParams) -> AppResult<Uuid> {
+        let active_model = impersonations::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(params.user_id),
+            impersonator_id: Set(params.impersonator_id),
+            impersonator_account: Set(params.impersonator_account),
+            session_id: Set(params.session_id),
+            reason: Set(params.reason),
+            ip: Set(params.ip),
+            platform: Set(params.platform),
+            agent: Set(params.agent),
+            ..Default::default()
+        };
+        let result = impersonations::Entity::insert(active_model)
+            .exec(&self.conn)
+            .await?;
+
+        Ok(result.last_insert_id)
+    }
+}
@@ -0,0 +1,56 @@
+use chrono::Utc;
+use entity::impersonations;
+use sea_orm::prelude::*;
+
+use crate::result::AppResult;
+
+use super::ImpersonationService;
+
+impl ImpersonationService {
+    /// Record that the impersonation running in the session ended, if there is one.
+    pub async fn end_impersonation_by_session_id(&self, session_id: Uuid) -> AppResult<()> {
+        impersonations::Entity::update_many()
+            .col_expr(
+                impersonations::Column::EndedAt,
+                Expr::value(Some(DateTimeWithTimeZone::from(Utc::now()))),
+            )
+            .filter(impersonations::Column::SessionId.eq(session_id))
+            .filter(impersonations::Column::EndedAt.is_null())
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record that every impersonation of the user still running ended, but the one in
+    /// `session_id`.
+    pub async fn end_other_impersonations(&self, user_id: Uuid, session_id: Uuid) -> AppResult<()> {
+        impersonations::Entity::update_many()
+            .col_expr(
+                impersonations::Column::EndedAt,
+                Expr::value(Some(DateTimeWithTimeZone::from(Utc::now()))),
+            )
+            .filter(impersonations::Column::UserId.eq(user_id))
+            .filter(impersonations::Column::SessionId.ne(session_id))
+            .filter(impersonations::Column::EndedAt.is_null())
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record that every impersonation of the user still running ended.
+    pub async fn end_impersonations_by_user_id(&self, user_id: Uuid) -> AppResult<()> {
+        impersonations::Entity::update_many()
+            .col_expr(
+                impersonations::Column::EndedAt,
+                Expr::value(Some(DateTimeWithTimeZone::from(Utc::now()))),
+            )
+            .filter(impersonations::Column::UserId.eq(user_id))
+            .filter(impersonations::Column::EndedAt.is_null())
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+}
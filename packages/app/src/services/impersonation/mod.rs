@@ -0,0 +1,9 @@
+use entity::impersonations;
+
+use crate::impl_service;
+
+pub mod create_impersonation;
+pub mod end_impersonation;
+pub mod query_impersonations;
+
+impl_service!(ImpersonationService, impersonations::Entity);
@@ -0,0 +1,22 @@
+use entity::impersonations;
+use sea_orm::{QueryOrder, prelude::*};
+
+use crate::{models::impersonation::Impersonation, result::AppResult};
+
+use super::ImpersonationService;
+
+impl ImpersonationService {
+    /// Every time the user was impersonated, newest first.
+    pub async fn query_impersonations_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> AppResult<Vec<Impersonation>> {
+        let records = impersonations::Entity::find()
+            .filter(impersonations::Column::UserId.eq(user_id))
+            .order_by_desc(impersonations::Column::CreatedAt)
+            .all(&self.conn)
+            .await?;
+
+        Ok(records.into_iter().map(Impersonation::from).collect())
+    }
+}
@@ -0,0 +1,55 @@
+use chrono::{DateTime, Utc};
+use entity::invitations;
+use sea_orm::ActiveValue::Set;
+use shared::utils::generate_recovery_code;
+use uuid::Uuid;
+
+use crate::result::AppResult;
+
+use super::{InvitationService, digest_invitation_code, distinct};
+
+#[derive(Debug, Default)]
+pub struct CreateInvitationParams {
+    pub note: Option<String>,
+    pub role_ids: Vec<Uuid>,
+    pub department_ids: Vec<Uuid>,
+    pub user_group_ids: Vec<Uuid>,
+    pub expired_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+}
+
+/// A newly issued invitation. The code is only ever shown here.
+pub struct InvitationCode {
+    pub id: Uuid,
+    pub code: String,
+}
+
+impl InvitationService {
+    pub async fn create_invitation(
+        &self,
+        params: CreateInvitationParams,
+    ) -> AppResult<InvitationCode> {
+        let role_ids = distinct(params.role_ids);
+        let department_ids = distinct(params.department_ids);
+        let user_group_ids = distinct(params.user_group_ids);
+        self.validate_invitation(&role_ids, &department_ids, &user_group_ids)
+            .await?;
+
+        let code = generate_recovery_code();
+
+        let active_model = invitations::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            code_digest: Set(digest_invitation_code(&code)),
+            note: Set(params.note),
+            role_ids: Set(serde_json::to_string(&role_ids).unwrap()),
+            department_ids: Set(serde_json::to_string(&department_ids).unwrap()),
+            user_group_ids: Set(serde_json::to_string(&user_group_ids).unwrap()),
+            created_by: Set(params.created_by),
+            expired_at: Set(params.expired_at.map(Into::into)),
+            ..Default::default()
+        };
+        let model = self.crud.create(active_model).await?;
+
+        Ok(InvitationCode { id: model.id, code })
+    }
+}
@@ -0,0 +1,20 @@
+use entity::invitations;
+use sea_orm::prelude::*;
+
+use crate::result::AppResult;
+
+use super::InvitationService;
+
+#[derive(Debug)]
+pub struct DeleteInvitationsParams(pub Vec<Uuid>);
+
+impl InvitationService {
+    /// Delete invitations. Users who already registered with them keep what they were given.
+    pub async fn delete_invitations(&self, params: DeleteInvitationsParams) -> AppResult<()> {
+        self.crud
+            .delete_many(invitations::Column::Id.is_in(params.0))
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,66 @@
+use entity::{departments, invitations, roles, user_groups};
+use sea_orm::{ColumnTrait, EntityTrait, PaginatorTrait, QueryFilter};
+use uuid::Uuid;
+
+use crate::{error::AppException, impl_service, result::AppResult};
+
+pub mod create_invitation;
+pub mod delete_invitations;
+pub mod query_invitations;
+pub mod redeem_invitation;
+
+impl_service!(InvitationService, invitations::Entity);
+
+/// Invitation codes are random, a plain digest keeps them from being read back. Codes are
+/// compared without separators and case, as they are typed in by hand.
+pub(crate) fn digest_invitation_code(code: &str) -> String {
+    let code = code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    shared::utils::hash_sha256(code.as_bytes())
+}
+
+impl InvitationService {
+    /// Every role, department and user group an invitation names must exist. The lists are
+    /// expected without duplicates, see `distinct`.
+    async fn validate_invitation(
+        &self,
+        role_ids: &[Uuid],
+        department_ids: &[Uuid],
+        user_group_ids: &[Uuid],
+    ) -> AppResult<()> {
+        let count = roles::Entity::find()
+            .filter(roles::Column::Id.is_in(role_ids.to_vec()))
+            .count(&self.conn)
+            .await?;
+        if count != role_ids.len() as u64 {
+            return Err(AppException::RoleNotFound.into());
+        }
+
+        let count = departments::Entity::find()
+            .filter(departments::Column::Id.is_in(department_ids.to_vec()))
+            .count(&self.conn)
+            .await?;
+        if count != department_ids.len() as u64 {
+            return Err(AppException::DepartmentNotFound.into());
+        }
+
+        let count = user_groups::Entity::find()
+            .filter(user_groups::Column::Id.is_in(user_group_ids.to_vec()))
+            .count(&self.conn)
+            .await?;
+        if count != user_group_ids.len() as u64 {
+            return Err(AppException::UserGroupNotFound.into());
+        }
+
+        Ok(())
+    }
+}
+
+pub(crate) fn distinct(mut id_list: Vec<Uuid>) -> Vec<Uuid> {
+    id_list.sort();
+    id_list.dedup();
+    id_list
+}
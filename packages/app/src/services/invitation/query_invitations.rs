@@ -0,0 +1,31 @@
+use entity::invitations;
+use sea_orm::{Condition, prelude::*};
+
+use crate::{models::invitation::Invitation, result::AppResult, utils::query::PageableQuery};
+
+use super::InvitationService;
+
+pub struct FilterInvitationsParams {
+    pub used: Option<bool>,
+}
+
+impl From<FilterInvitationsParams> for Condition {
+    fn from(value: FilterInvitationsParams) -> Self {
+        Condition::all().add_option(value.used.map(|x| match x {
+            true => invitations::Column::UsedAt.is_not_null(),
+            false => invitations::Column::UsedAt.is_null(),
+        }))
+    }
+}
+
+impl InvitationService {
+    pub async fn query_invitations_by_page(
+        &self,
+        params: PageableQuery<FilterInvitationsParams>,
+    ) -> AppResult<(Vec<Invitation>, i64)> {
+        let (records, total) = self.crud.find_by_condition_with_count(params).await?;
+        let records = records.into_iter().map(Invitation::from).collect();
+
+        Ok((records, total))
+    }
+}
@@ -0,0 +1,118 @@
+use chrono::Utc;
+use entity::{
+    invitations, relation_roles_users, relation_users_departments, relation_users_user_groups,
+};
+use sea_orm::{ActiveValue::Set, Condition, TransactionTrait, prelude::*};
+
+use crate::{
+    error::AppException, models::invitation::Invitation, result::AppResult,
+    services::auth::AuthService,
+};
+
+use super::{InvitationService, digest_invitation_code};
+
+impl InvitationService {
+    /// Mark an unused and unexpired invitation as used, failing with `InvalidInvitation`
+    /// otherwise. Only one registration can claim an invitation, even when they race.
+    pub async fn claim_invitation(&self, code: &str) -> AppResult<Invitation> {
+        let code_digest = digest_invitation_code(code);
+        let now = Utc::now();
+
+        let result = invitations::Entity::update_many()
+            .col_expr(
+                invitations::Column::UsedAt,
+                Expr::value(Some(DateTimeWithTimeZone::from(now))),
+            )
+            .filter(invitations::Column::CodeDigest.eq(&code_digest))
+            .filter(invitations::Column::UsedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(invitations::Column::ExpiredAt.is_null())
+                    .add(invitations::Column::ExpiredAt.gt(now)),
+            )
+            .exec(&self.conn)
+            .await?;
+        if result.rows_affected != 1 {
+            return Err(AppException::InvalidInvitation.into());
+        }
+
+        let model = self
+            .crud
+            .find_one_by_condition(invitations::Column::CodeDigest.eq(code_digest))
+            .await?;
+        let Some(model) = model else {
+            return Err(AppException::InvalidInvitation.into());
+        };
+
+        Ok(model.into())
+    }
+
+    /// Make a claimed invitation usable again, when the registration that claimed it failed.
+    pub async fn release_invitation(&self, id: Uuid) -> AppResult<()> {
+        invitations::Entity::update_many()
+            .col_expr(
+                invitations::Column::UsedAt,
+                Expr::value(Option::<DateTimeWithTimeZone>::None),
+            )
+            .filter(invitations::Column::Id.eq(id))
+            .filter(invitations::Column::UsedBy.is_null())
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Record who registered with a claimed invitation, and put them into everything it names.
+    /// Cached permissions are invalidated, as the user gains those of the roles and groups.
+    pub async fn accept_invitation(&self, invitation: &Invitation, user_id: Uuid) -> AppResult<()> {
+        let txn = self.conn.begin().await?;
+
+        invitations::Entity::update_many()
+            .col_expr(invitations::Column::UsedBy, Expr::value(Some(user_id)))
+            .filter(invitations::Column::Id.eq(invitation.id))
+            .exec(&txn)
+            .await?;
+
+        if !invitation.role_ids.is_empty() {
+            relation_roles_users::Entity::insert_many(invitation.role_ids.iter().map(|role_id| {
+                relation_roles_users::ActiveModel {
+                    role_id: Set(*role_id),
+                    user_id: Set(user_id),
+                    ..Default::default()
+                }
+            }))
+            .exec(&txn)
+            .await?;
+        }
+        if !invitation.department_ids.is_empty() {
+            relation_users_departments::Entity::insert_many(invitation.department_ids.iter().map(
+                |department_id| relation_users_departments::ActiveModel {
+                    user_id: Set(user_id),
+                    department_id: Set(*department_id),
+                    ..Default::default()
+                },
+            ))
+            .exec(&txn)
+            .await?;
+        }
+        if !invitation.user_group_ids.is_empty() {
+            relation_users_user_groups::Entity::insert_many(invitation.user_group_ids.iter().map(
+                |user_group_id| relation_users_user_groups::ActiveModel {
+                    user_id: Set(user_id),
+                    user_group_id: Set(*user_group_id),
+                    ..Default::default()
+                },
+            ))
+            .exec(&txn)
+            .await?;
+        }
+
+        txn.commit().await?;
+
+        AuthService::new(self.app.clone())
+            .bump_permission_version()
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use entity::login_histories;
+use sea_orm::{ActiveValue::Set, EntityTrait};
+use uuid::Uuid;
+
+use crate::result::AppResult;
+
+use super::LoginHistoryService;
+
+#[derive(Debug, Default)]
+pub struct CreateLoginHistoryParams {
+    pub user_id: Option<Uuid>,
+    pub account: String,
+    pub success: bool,
+    pub failure_reason: Option<String>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+}
+
+impl LoginHistoryService {
+    pub async fn create_login_history(&self, params: CreateLoginHistoryParams) -> AppResult<Uuid> {
+        let active_model = login_histories::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(params.user_id),
+            account: Set(params.account),
+            success: Set(params.success),
+            failure_reason: Set(params.failure_reason),
+            ip: Set(params.ip),
+            platform: Set(params.platform),
+            agent: Set(params.agent),
+            ..Default::default()
+        };
+        let result = login_histories::Entity::insert(active_model)
+            .exec(&self.conn)
+            .await?;
+
+        Ok(result.last_insert_id)
+    }
+}
@@ -0,0 +1,8 @@
+use entity::login_histories;
+
+use crate::impl_service;
+
+pub mod create_login_history;
+pub mod query_login_histories;
+
+impl_service!(LoginHistoryService, login_histories::Entity, data_scoped);
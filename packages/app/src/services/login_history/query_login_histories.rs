@@ -0,0 +1,75 @@
+use entity::login_histories;
+use migration::{ColumnRef, IntoColumnRef};
+use sea_orm::{Condition, prelude::*};
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::{
+    models::login_history::LoginHistory,
+    result::AppResult,
+    utils::query::{Order, PageableQuery},
+};
+
+use super::LoginHistoryService;
+
+#[derive(Default)]
+pub struct FilterLoginHistoriesParams {
+    pub user_id: Option<Uuid>,
+    pub account: Option<String>,
+    pub success: Option<bool>,
+}
+
+impl From<FilterLoginHistoriesParams> for Condition {
+    fn from(value: FilterLoginHistoriesParams) -> Self {
+        Condition::all()
+            .add_option(
+                value
+                    .user_id
+                    .map(|user_id| login_histories::Column::UserId.eq(user_id)),
+            )
+            .add_option(
+                value
+                    .account
+                    .map(|account| login_histories::Column::Account.like(account)),
+            )
+            .add_option(
+                value
+                    .success
+                    .map(|success| login_histories::Column::Success.eq(success)),
+            )
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum LoginHistoryOrderField {
+    CreatedAt,
+}
+
+impl From<LoginHistoryOrderField> for ColumnRef {
+    fn from(value: LoginHistoryOrderField) -> Self {
+        match value {
+            LoginHistoryOrderField::CreatedAt => {
+                login_histories::Column::CreatedAt.into_column_ref()
+            }
+        }
+    }
+}
+
+impl LoginHistoryService {
+    /// Page through login histories, newest first unless ordered otherwise.
+    pub async fn query_login_histories_by_page(
+        &self,
+        mut params: PageableQuery<FilterLoginHistoriesParams, LoginHistoryOrderField>,
+    ) -> AppResult<(Vec<LoginHistory>, i64)> {
+        if params.orders.is_none() {
+            params.orders = Some(vec![(LoginHistoryOrderField::CreatedAt, Order::Desc)]);
+        }
+
+        let (records, total) = self.crud.find_by_condition_with_count(params).await?;
+
+        let login_histories = records.into_iter().map(LoginHistory::from).collect();
+
+        Ok((login_histories, total))
+    }
+}
@@ -18,9 +18,17 @@ macro_rules! impl_service {
                 Self::new(app)
             }
         }
+
+        impl crate::services::data_scope::DataScopedService for $struct_name {}
     };
 
     ($struct_name:ident, $entity:ty) => {
+        $crate::impl_service!(@entity $struct_name, $entity);
+
+        impl crate::services::data_scope::DataScopedService for $struct_name {}
+    };
+
+    (@entity $struct_name:ident, $entity:ty) => {
         pub struct $struct_name {
             #[allow(dead_code)]
             app: crate::App,
@@ -47,4 +55,25 @@ macro_rules! impl_service {
             }
         }
     };
+
+    ($struct_name:ident, $entity:ty, data_scoped) => {
+        $crate::impl_service!(@entity $struct_name, $entity);
+
+        impl crate::services::data_scope::DataScopedService for $struct_name {
+            const DATA_SCOPED: bool = true;
+
+            async fn scope_to(
+                mut self,
+                payload: &crate::models::auth_token::SessionTokenPayload,
+            ) -> crate::result::AppResult<Self> {
+                let data_scope =
+                    crate::services::data_scope::DataScopeService::new(self.app.clone())
+                        .resolve_data_scope(payload)
+                        .await?;
+                self.crud = self.crud.with_data_scope(&data_scope);
+
+                Ok(self)
+            }
+        }
+    };
 }
@@ -6,13 +6,19 @@ pub mod budget;
 pub mod category;
 pub mod code;
 pub mod crud;
+pub mod data_scope;
 pub mod department;
+pub mod impersonation;
+pub mod invitation;
+pub mod login_history;
 pub mod macros;
 pub mod menu;
 pub mod permission;
 pub mod permission_group;
+pub mod recovery_code;
 pub mod role;
 pub mod role_group;
+pub mod setting;
 pub mod tag;
 pub mod third_user;
 pub mod transaction;
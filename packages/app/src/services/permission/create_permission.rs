@@ -2,9 +2,10 @@ use entity::prelude::Permissions;
 use entity::relation_permissions_permission_groups;
 use entity::{permissions, prelude::RelationPermissionsPermissionGroups};
 use sea_orm::{ActiveValue::Set, EntityTrait, TransactionTrait};
+use shared::enums::DataScope;
 use uuid::Uuid;
 
-use crate::result::AppResult;
+use crate::{result::AppResult, services::auth::AuthService};
 
 use super::PermissionService;
 
@@ -14,10 +15,17 @@ pub struct CreatePermissionParams {
     pub kind: String,
     pub description: Option<String>,
     pub parent_id: Option<Uuid>,
+    /// Required for data permissions.
+    pub data_scope: Option<DataScope>,
+    /// Required for data permissions with a custom scope.
+    pub data_department_ids: Vec<Uuid>,
 }
 
 impl PermissionService {
     pub async fn create_permission(&self, params: CreatePermissionParams) -> AppResult<Uuid> {
+        self.validate_data_scope(&params.kind, params.data_scope, &params.data_department_ids)
+            .await?;
+
         let tx = self.conn.begin().await?;
 
         let permission_active_model = permissions::ActiveModel {
@@ -25,6 +33,11 @@ impl PermissionService {
             code: Set(params.code),
             kind: Set(params.kind),
             description: Set(params.description),
+            data_scope: Set(params.data_scope.map(|x| x.to_string())),
+            data_department_ids: Set(params
+                .data_scope
+                .is_some()
+                .then(|| serde_json::to_string(&params.data_department_ids).unwrap())),
             ..Default::default()
         };
 
@@ -47,6 +60,10 @@ impl PermissionService {
 
         tx.commit().await?;
 
+        AuthService::new(self.app.clone())
+            .bump_permission_version()
+            .await?;
+
         Ok(permission_id)
     }
 }
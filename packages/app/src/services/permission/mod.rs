@@ -1,9 +1,41 @@
-use entity::permissions;
+use entity::{departments, permissions};
+use sea_orm::prelude::*;
+use shared::enums::{DataScope, PermissionKind};
 
-use crate::impl_service;
+use crate::{error::AppException, impl_service, result::AppResult};
 pub mod create_permission;
 pub mod delete_permissions;
 pub mod query_permissions;
 pub mod update_permission;
 
 impl_service!(PermissionService, permissions::Entity);
+
+impl PermissionService {
+    /// Data permissions need a scope, and only they may have one. Only a custom scope lists
+    /// departments, which have to exist.
+    async fn validate_data_scope(
+        &self,
+        kind: &str,
+        data_scope: Option<DataScope>,
+        data_department_ids: &[Uuid],
+    ) -> AppResult<()> {
+        let is_data = kind == PermissionKind::Data.to_string();
+        if is_data != data_scope.is_some() {
+            return Err(AppException::InvalidDataScope.into());
+        }
+        let is_custom = data_scope == Some(DataScope::CustomDepartments);
+        if is_custom == data_department_ids.is_empty() {
+            return Err(AppException::InvalidDataScope.into());
+        }
+
+        let count = departments::Entity::find()
+            .filter(departments::Column::Id.is_in(data_department_ids.to_vec()))
+            .count(&self.conn)
+            .await?;
+        if count != data_department_ids.len() as u64 {
+            return Err(AppException::DepartmentNotFound.into());
+        }
+
+        Ok(())
+    }
+}
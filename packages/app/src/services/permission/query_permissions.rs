@@ -1,10 +1,12 @@
 use std::collections::HashMap;
 
 use entity::{
-    permissions, relation_permissions_departments, relation_permissions_permission_groups,
-    relation_permissions_roles, relation_permissions_user_groups, relation_permissions_users,
+    permission_denials, permissions, relation_permissions_departments,
+    relation_permissions_permission_groups, relation_permissions_roles,
+    relation_permissions_user_groups, relation_permissions_users,
 };
 use sea_orm::{Condition, prelude::*};
+use shared::enums::PermissionSubjectKind;
 use uuid::Uuid;
 
 use crate::{models::permission::Permission, result::AppResult, utils::query::PageableQuery};
@@ -194,4 +196,46 @@ impl PermissionService {
 
         Ok(map)
     }
+
+    /// Permissions denied to the subject itself, not to what it reaches.
+    pub async fn query_denied_permissions(
+        &self,
+        subject_kind: PermissionSubjectKind,
+        subject_id: Uuid,
+    ) -> AppResult<Vec<Permission>> {
+        let permissions = permissions::Entity::find()
+            .inner_join(permission_denials::Entity)
+            .filter(permission_denials::Column::SubjectKind.eq(subject_kind.to_string()))
+            .filter(permission_denials::Column::SubjectId.eq(subject_id))
+            .all(&self.conn)
+            .await?;
+
+        Ok(permissions.into_iter().map(Permission::from).collect())
+    }
+
+    pub async fn query_denied_permissions_by_subject_id_list(
+        &self,
+        subject_kind: PermissionSubjectKind,
+        subject_id_list: Vec<Uuid>,
+    ) -> AppResult<HashMap<Uuid, Vec<Permission>>> {
+        if subject_id_list.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let results = permissions::Entity::find()
+            .find_also_related(permission_denials::Entity)
+            .filter(permission_denials::Column::SubjectKind.eq(subject_kind.to_string()))
+            .filter(permission_denials::Column::SubjectId.is_in(subject_id_list))
+            .all(&self.conn)
+            .await?;
+
+        let mut map = HashMap::new();
+        for (permission, denial) in results {
+            let subject_id = denial.unwrap().subject_id;
+            map.entry(subject_id)
+                .or_insert_with(Vec::new)
+                .push(Permission::from(permission));
+        }
+
+        Ok(map)
+    }
 }
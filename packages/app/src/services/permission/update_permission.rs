@@ -1,7 +1,11 @@
 use entity::permissions;
 use sea_orm::{ActiveValue::Set, IntoActiveModel, prelude::*};
+use shared::enums::DataScope;
 
-use crate::{error::AppException, result::AppResult};
+use crate::{
+    error::AppException, models::permission::Permission, result::AppResult,
+    services::auth::AuthService,
+};
 
 use super::PermissionService;
 
@@ -10,6 +14,8 @@ pub struct UpdatePermissionParams {
     pub id: Uuid,
     pub code: Option<String>,
     pub description: Option<String>,
+    pub data_scope: Option<DataScope>,
+    pub data_department_ids: Option<Vec<Uuid>>,
 }
 
 impl PermissionService {
@@ -18,12 +24,15 @@ impl PermissionService {
             id,
             code,
             description,
+            data_scope,
+            data_department_ids,
         } = params;
 
         let model = permissions::Entity::find_by_id(id).one(&self.conn).await?;
         let Some(model) = model else {
             return Err(AppException::PermissionNotFound.into());
         };
+        let permission = Permission::from(model.clone());
         let mut active_model = model.into_active_model();
 
         if let Some(code) = code {
@@ -34,8 +43,29 @@ impl PermissionService {
             active_model.description = Set(Some(description));
         }
 
+        if data_scope.is_some() || data_department_ids.is_some() {
+            let data_scope = data_scope.or(permission.data_scope);
+            // Departments only stay with a custom scope
+            let data_department_ids = match data_scope {
+                Some(DataScope::CustomDepartments) => {
+                    data_department_ids.unwrap_or(permission.data_department_ids)
+                }
+                _ => vec![],
+            };
+            self.validate_data_scope(&permission.kind, data_scope, &data_department_ids)
+                .await?;
+
+            active_model.data_scope = Set(data_scope.map(|x| x.to_string()));
+            active_model.data_department_ids =
+                Set(Some(serde_json::to_string(&data_department_ids).unwrap()));
+        }
+
         active_model.update(&self.conn).await?;
 
+        AuthService::new(self.app.clone())
+            .bump_permission_version()
+            .await?;
+
         Ok(())
     }
 }
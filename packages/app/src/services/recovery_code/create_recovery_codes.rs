@@ -0,0 +1,40 @@
+use entity::recovery_codes;
+use sea_orm::{ActiveValue::Set, ColumnTrait, EntityTrait, QueryFilter, TransactionTrait};
+use shared::utils::generate_recovery_code;
+use uuid::Uuid;
+
+use crate::result::AppResult;
+
+use super::{RecoveryCodeService, digest_recovery_code};
+
+const RECOVERY_CODE_COUNT: usize = 10;
+
+impl RecoveryCodeService {
+    /// Replace every recovery code of the user with a new set. Only the digests are stored, so
+    /// the returned codes can't be shown again.
+    pub async fn regenerate_recovery_codes(&self, user_id: Uuid) -> AppResult<Vec<String>> {
+        let codes = (0..RECOVERY_CODE_COUNT)
+            .map(|_| generate_recovery_code())
+            .collect::<Vec<_>>();
+
+        let tx = self.conn.begin().await?;
+
+        recovery_codes::Entity::delete_many()
+            .filter(recovery_codes::Column::UserId.eq(user_id))
+            .exec(&tx)
+            .await?;
+
+        recovery_codes::Entity::insert_many(codes.iter().map(|code| recovery_codes::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            code_digest: Set(digest_recovery_code(code)),
+            ..Default::default()
+        }))
+        .exec(&tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(codes)
+    }
+}
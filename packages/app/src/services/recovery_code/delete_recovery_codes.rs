@@ -0,0 +1,18 @@
+use entity::recovery_codes;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use crate::result::AppResult;
+
+use super::RecoveryCodeService;
+
+impl RecoveryCodeService {
+    pub async fn delete_recovery_codes(&self, user_id: Uuid) -> AppResult<()> {
+        recovery_codes::Entity::delete_many()
+            .filter(recovery_codes::Column::UserId.eq(user_id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,19 @@
+use entity::recovery_codes;
+
+use crate::impl_service;
+
+pub mod create_recovery_codes;
+pub mod delete_recovery_codes;
+pub mod use_recovery_code;
+
+impl_service!(RecoveryCodeService, recovery_codes::Entity);
+
+/// Recovery codes are compared case-insensitively and with or without the dash.
+pub(crate) fn digest_recovery_code(code: &str) -> String {
+    let code = code
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .collect::<String>()
+        .to_ascii_uppercase();
+    shared::utils::hash_blake3(code.as_bytes())
+}
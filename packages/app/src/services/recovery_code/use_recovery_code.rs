@@ -0,0 +1,29 @@
+use chrono::Utc;
+use entity::recovery_codes;
+use sea_orm::{
+    ColumnTrait, EntityTrait, QueryFilter, prelude::DateTimeWithTimeZone, sea_query::Expr,
+};
+use uuid::Uuid;
+
+use crate::result::AppResult;
+
+use super::{RecoveryCodeService, digest_recovery_code};
+
+impl RecoveryCodeService {
+    /// Spend one of the user's recovery codes. Returns `false` if the code is unknown or was
+    /// already used.
+    pub async fn use_recovery_code(&self, user_id: Uuid, code: &str) -> AppResult<bool> {
+        let result = recovery_codes::Entity::update_many()
+            .col_expr(
+                recovery_codes::Column::UsedAt,
+                Expr::value(Some(DateTimeWithTimeZone::from(Utc::now()))),
+            )
+            .filter(recovery_codes::Column::UserId.eq(user_id))
+            .filter(recovery_codes::Column::CodeDigest.eq(digest_recovery_code(code)))
+            .filter(recovery_codes::Column::UsedAt.is_null())
+            .exec(&self.conn)
+            .await?;
+
+        Ok(result.rows_affected == 1)
+    }
+}
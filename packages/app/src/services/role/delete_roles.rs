@@ -2,7 +2,7 @@ use entity::roles;
 use sea_orm::prelude::*;
 use sea_orm::{EntityTrait, prelude::Uuid};
 
-use crate::result::AppResult;
+use crate::{result::AppResult, services::auth::AuthService};
 
 use super::RoleService;
 
@@ -16,6 +16,10 @@ impl RoleService {
             .exec(&self.conn)
             .await?;
 
+        AuthService::new(self.app.clone())
+            .bump_permission_version()
+            .await?;
+
         Ok(())
     }
 }
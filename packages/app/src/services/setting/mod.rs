@@ -0,0 +1,7 @@
+use entity::settings;
+
+use crate::impl_service;
+pub mod query_setting;
+pub mod update_setting;
+
+impl_service!(SettingService, settings::Entity);
@@ -0,0 +1,16 @@
+use entity::settings;
+use sea_orm::prelude::*;
+use shared::enums::SettingName;
+
+use crate::{result::AppResult, services::setting::SettingService};
+
+impl SettingService {
+    pub async fn query_setting_value(&self, name: SettingName) -> AppResult<Option<String>> {
+        let model = settings::Entity::find()
+            .filter(settings::Column::Name.eq(name.to_string()))
+            .one(&self.conn)
+            .await?;
+
+        Ok(model.map(|x| x.value))
+    }
+}
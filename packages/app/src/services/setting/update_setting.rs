@@ -0,0 +1,41 @@
+use entity::settings;
+use sea_orm::{ActiveValue::Set, prelude::*, sea_query::OnConflict};
+use shared::enums::SettingName;
+
+use crate::{result::AppResult, services::setting::SettingService};
+
+impl SettingService {
+    /// Set a setting, creating it if it doesn't exist yet.
+    pub async fn update_setting_value(&self, name: SettingName, value: String) -> AppResult<()> {
+        let active_model = settings::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(name.to_string()),
+            value: Set(value),
+            ..Default::default()
+        };
+        settings::Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::column(settings::Column::Name)
+                    .update_column(settings::Column::Value)
+                    .to_owned(),
+            )
+            .exec_without_returning(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Atomically increase an integer setting by one.
+    pub async fn increase_setting_value(&self, name: SettingName) -> AppResult<()> {
+        settings::Entity::update_many()
+            .col_expr(
+                settings::Column::Value,
+                Expr::cust("(CAST(value AS BIGINT) + 1)::TEXT"),
+            )
+            .filter(settings::Column::Name.eq(name.to_string()))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+}
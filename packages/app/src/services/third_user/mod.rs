@@ -2,4 +2,7 @@ use entity::third_users;
 
 use crate::impl_service;
 
+pub mod query_third_users;
+pub mod save_third_user;
+
 impl_service!(ThirdUserService, third_users::Entity);
@@ -0,0 +1,23 @@
+use entity::third_users;
+use sea_orm::{ColumnTrait, EntityTrait, QueryFilter};
+
+use crate::{models::third_user::ThirdUser, result::AppResult};
+
+use super::ThirdUserService;
+
+impl ThirdUserService {
+    pub async fn query_third_user_by_open_id(
+        &self,
+        platform: &str,
+        open_id: &str,
+    ) -> AppResult<Option<ThirdUser>> {
+        let third_user = third_users::Entity::find()
+            .filter(third_users::Column::Platform.eq(platform))
+            .filter(third_users::Column::OpenId.eq(open_id))
+            .filter(third_users::Column::IsDeleted.eq(false))
+            .one(&self.conn)
+            .await?;
+
+        Ok(third_user.map(Into::into))
+    }
+}
@@ -0,0 +1,56 @@
+use entity::third_users;
+use sea_orm::{
+    ActiveValue::{NotSet, Set},
+    EntityTrait,
+    sea_query::OnConflict,
+};
+use uuid::Uuid;
+
+use crate::result::AppResult;
+
+use super::ThirdUserService;
+
+#[derive(Debug)]
+pub struct SaveThirdUserParams {
+    pub user_id: Uuid,
+    pub platform: String,
+    pub open_id: String,
+    pub union_id: Option<String>,
+    pub access_token: Option<String>,
+    pub payload: Option<String>,
+}
+
+impl ThirdUserService {
+    /// Link an external identity to a user, replacing the identity the user had on the same
+    /// platform before.
+    pub async fn save_third_user(&self, params: SaveThirdUserParams) -> AppResult<()> {
+        let active_model = third_users::ActiveModel {
+            user_id: Set(params.user_id),
+            platform: Set(params.platform),
+            open_id: Set(params.open_id),
+            union_id: Set(params.union_id),
+            access_token: Set(params.access_token),
+            payload: Set(params.payload),
+            is_deleted: Set(false),
+            created_at: NotSet,
+            updated_at: NotSet,
+        };
+
+        third_users::Entity::insert(active_model)
+            .on_conflict(
+                OnConflict::columns([third_users::Column::UserId, third_users::Column::Platform])
+                    .update_columns([
+                        third_users::Column::OpenId,
+                        third_users::Column::UnionId,
+                        third_users::Column::AccessToken,
+                        third_users::Column::Payload,
+                        third_users::Column::IsDeleted,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+}
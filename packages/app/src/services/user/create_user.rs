@@ -1,3 +1,4 @@
+use chrono::Utc;
 use entity::users;
 use sea_orm::ActiveValue;
 use shared::{enums::Gender, utils::hash_password};
@@ -15,10 +16,27 @@ pub struct CreateUserParams {
     pub phone: Option<String>,
     pub email: Option<String>,
     pub gender: Option<Gender>,
+    /// Keep the user from logging in until the email is verified.
+    pub registration_pending: bool,
+}
+
+/// A user signing up through an identity provider, without a password.
+#[derive(Debug, Default)]
+pub struct CreateExternalUserParams {
+    pub account: String,
+    pub nickname: Option<String>,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub avatar_url: Option<String>,
 }
 
 impl UserService {
     pub async fn create_user(&self, params: CreateUserParams) -> AppResult<Uuid> {
+        self.validate_password(&params.account, &params.password, None)
+            .await?;
+
+        let password_digest =
+            hash_password(&params.password, &self.app.config.password_hash.params());
         let user_active_model = users::ActiveModel {
             id: ActiveValue::Set(Uuid::new_v4()),
             account: ActiveValue::Set(params.account),
@@ -31,9 +49,48 @@ impl UserService {
             gender: ActiveValue::Set(Gender::Unknown.to_string()),
             birthday: ActiveValue::NotSet,
             bio: ActiveValue::NotSet,
-            password_digest: ActiveValue::Set(Some(hash_password(&params.password))),
+            password_digest: ActiveValue::Set(Some(password_digest.clone())),
+            last_login: ActiveValue::NotSet,
+            failed_login_attempts: ActiveValue::NotSet,
+            locked_until: ActiveValue::NotSet,
+            totp_secret: ActiveValue::NotSet,
+            totp_enabled: ActiveValue::NotSet,
+            totp_last_used_step: ActiveValue::NotSet,
+            password_changed_at: ActiveValue::Set(Some(Utc::now().into())),
+            registration_pending: ActiveValue::Set(params.registration_pending),
+            is_deleted: ActiveValue::NotSet,
+            created_at: ActiveValue::NotSet,
+            updated_at: ActiveValue::NotSet,
+        };
+        let result = self.crud.create(user_active_model).await?;
+        self.record_password_history(&self.conn, result.id, password_digest)
+            .await?;
+
+        Ok(result.id)
+    }
+
+    pub async fn create_external_user(&self, params: CreateExternalUserParams) -> AppResult<Uuid> {
+        let user_active_model = users::ActiveModel {
+            id: ActiveValue::Set(Uuid::new_v4()),
+            account: ActiveValue::Set(params.account),
+            nickname: ActiveValue::Set(params.nickname),
+            real_name: ActiveValue::NotSet,
+            phone: ActiveValue::NotSet,
+            email: ActiveValue::Set(params.email),
+            email_verified: ActiveValue::Set(params.email_verified),
+            avatar_url: ActiveValue::Set(params.avatar_url),
+            gender: ActiveValue::Set(Gender::Unknown.to_string()),
+            birthday: ActiveValue::NotSet,
+            bio: ActiveValue::NotSet,
+            password_digest: ActiveValue::Set(None),
             last_login: ActiveValue::NotSet,
             failed_login_attempts: ActiveValue::NotSet,
+            locked_until: ActiveValue::NotSet,
+            totp_secret: ActiveValue::NotSet,
+            totp_enabled: ActiveValue::NotSet,
+            totp_last_used_step: ActiveValue::NotSet,
+            password_changed_at: ActiveValue::NotSet,
+            registration_pending: ActiveValue::NotSet,
             is_deleted: ActiveValue::NotSet,
             created_at: ActiveValue::NotSet,
             updated_at: ActiveValue::NotSet,
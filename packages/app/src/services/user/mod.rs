@@ -3,6 +3,8 @@ use entity::users;
 use crate::impl_service;
 pub mod create_user;
 pub mod delete_user;
+pub mod password_policy;
 pub mod query_user;
+pub mod update_user;
 
-impl_service!(UserService, users::Entity);
+impl_service!(UserService, users::Entity, data_scoped);
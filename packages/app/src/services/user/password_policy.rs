@@ -0,0 +1,259 @@
+use chrono::Utc;
+use entity::{password_histories, users};
+use sea_orm::{
+    ActiveValue::{NotSet, Set},
+    ConnectionTrait, QueryOrder, QuerySelect,
+    prelude::*,
+};
+use shared::utils::verify_password;
+
+use crate::{
+    config::PasswordPolicyConfig,
+    error::AppException,
+    models::{password_policy::PasswordPolicyViolation, user::User},
+    result::AppResult,
+};
+
+use super::UserService;
+
+/// Passwords found at the top of breach corpora, one per line in lowercase.
+const COMMON_PASSWORDS: &str = include_str!("common_passwords.txt");
+
+impl UserService {
+    /// Check a new password of `account` against the policy, and against the previous passwords
+    /// of the user when there is one already. Every broken rule is reported at once.
+    pub async fn validate_password(
+        &self,
+        account: &str,
+        password: &str,
+        user_id: Option<Uuid>,
+    ) -> AppResult<()> {
+        let policy = &self.app.config.password_policy;
+        let mut violations = check_password_policy(policy, account, password);
+
+        if let Some(user_id) = user_id
+            && self.is_password_reused(user_id, password).await?
+        {
+            violations.push(PasswordPolicyViolation::RecentlyUsed {
+                history_size: policy.history_size,
+            });
+        }
+
+        if !violations.is_empty() {
+            return Err(AppException::PasswordPolicyViolated(violations).into());
+        }
+
+        Ok(())
+    }
+
+    /// Whether the password of the user is older than the policy allows. Users without a
+    /// password have nothing to expire.
+    pub fn is_password_expired(&self, user: &User) -> bool {
+        let Some(max_age) = self.app.config.password_policy.max_age() else {
+            return false;
+        };
+
+        user.password_digest.is_some()
+            && user
+                .password_changed_at
+                .is_none_or(|x| x + max_age < Utc::now())
+    }
+
+    /// Whether `password` is the current password of the user or one of the previous ones the
+    /// policy remembers.
+    async fn is_password_reused(&self, user_id: Uuid, password: &str) -> AppResult<bool> {
+        let history_size = self.app.config.password_policy.history_size;
+        if history_size == 0 {
+            return Ok(false);
+        }
+
+        let current = users::Entity::find_by_id(user_id)
+            .one(&self.conn)
+            .await?
+            .and_then(|x| x.password_digest);
+        let previous = password_histories::Entity::find()
+            .filter(password_histories::Column::UserId.eq(user_id))
+            .order_by_desc(password_histories::Column::CreatedAt)
+            .limit(history_size as u64)
+            .all(&self.conn)
+            .await?
+            .into_iter()
+            .map(|x| x.password_digest);
+
+        let pepper = self.app.config.password_hash.pepper();
+        Ok(current
+            .into_iter()
+            .chain(previous)
+            .any(|x| verify_password(password, &x, pepper)))
+    }
+
+    /// Remember a password the user just set, forgetting those beyond the history size.
+    pub(super) async fn record_password_history<C: ConnectionTrait>(
+        &self,
+        conn: &C,
+        user_id: Uuid,
+        password_digest: String,
+    ) -> AppResult<()> {
+        let active_model = password_histories::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            password_digest: Set(password_digest),
+            is_deleted: NotSet,
+            created_at: NotSet,
+            updated_at: NotSet,
+        };
+        password_histories::Entity::insert(active_model)
+            .exec(conn)
+            .await?;
+
+        let forgotten = password_histories::Entity::find()
+            .select_only()
+            .column(password_histories::Column::Id)
+            .filter(password_histories::Column::UserId.eq(user_id))
+            .order_by_desc(password_histories::Column::CreatedAt)
+            .offset(self.app.config.password_policy.history_size as u64)
+            .into_tuple::<Uuid>()
+            .all(conn)
+            .await?;
+        if !forgotten.is_empty() {
+            password_histories::Entity::delete_many()
+                .filter(password_histories::Column::Id.is_in(forgotten))
+                .exec(conn)
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The rules that can be checked on the password alone.
+fn check_password_policy(
+    policy: &PasswordPolicyConfig,
+    account: &str,
+    password: &str,
+) -> Vec<PasswordPolicyViolation> {
+    let mut violations = Vec::new();
+
+    if password.chars().count() < policy.min_length {
+        violations.push(PasswordPolicyViolation::TooShort {
+            min_length: policy.min_length,
+        });
+    }
+
+    let character_classes = [
+        password.chars().any(|x| x.is_lowercase()),
+        password.chars().any(|x| x.is_uppercase()),
+        password.chars().any(|x| x.is_numeric()),
+        password.chars().any(|x| !x.is_alphanumeric()),
+    ];
+    if character_classes.iter().filter(|x| **x).count() < policy.min_character_classes {
+        violations.push(PasswordPolicyViolation::TooFewCharacterClasses {
+            min_character_classes: policy.min_character_classes,
+        });
+    }
+
+    let lowercase = password.to_lowercase();
+    let common = COMMON_PASSWORDS.lines().any(|x| x == lowercase)
+        || policy
+            .blocklist
+            .iter()
+            .any(|x| x.to_lowercase() == lowercase);
+    if common {
+        violations.push(PasswordPolicyViolation::Common);
+    }
+
+    // Very short account names would match by accident
+    let account = account.to_lowercase();
+    if policy.reject_account_name && account.chars().count() >= 3 && lowercase.contains(&account) {
+        violations.push(PasswordPolicyViolation::ContainsAccount);
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(password: &str) -> Vec<PasswordPolicyViolation> {
+        check_password_policy(&PasswordPolicyConfig::default(), "alice", password)
+    }
+
+    #[test]
+    fn accepts_password_following_every_rule() {
+        assert_eq!(check("Tr0ub4dor&3"), Vec::new());
+    }
+
+    #[test]
+    fn counts_length_in_characters() {
+        assert_eq!(
+            check("Ab1!xyz"),
+            vec![PasswordPolicyViolation::TooShort { min_length: 8 }]
+        );
+        assert_eq!(check("Ab1!xyzw"), Vec::new());
+        // Eight characters, though more bytes
+        assert_eq!(check("Äb1!xyzé"), Vec::new());
+    }
+
+    #[test]
+    fn requires_character_classes() {
+        assert_eq!(
+            check("abcdefgh12"),
+            vec![PasswordPolicyViolation::TooFewCharacterClasses {
+                min_character_classes: 3
+            }]
+        );
+        assert_eq!(check("abcdefgh12!"), Vec::new());
+        assert_eq!(check("ABCDEFGH12!"), Vec::new());
+    }
+
+    #[test]
+    fn refuses_common_and_blocklisted_passwords() {
+        assert_eq!(check("P@ssw0rd"), vec![PasswordPolicyViolation::Common]);
+
+        let policy = PasswordPolicyConfig {
+            blocklist: vec!["Company2026!".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(
+            check_password_policy(&policy, "alice", "company2026!"),
+            vec![PasswordPolicyViolation::Common]
+        );
+    }
+
+    #[test]
+    fn refuses_account_name() {
+        assert_eq!(
+            check("xALICEx1!"),
+            vec![PasswordPolicyViolation::ContainsAccount]
+        );
+
+        let policy = PasswordPolicyConfig {
+            reject_account_name: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            check_password_policy(&policy, "alice", "xALICEx1!"),
+            Vec::new()
+        );
+        // Too short to be matched
+        assert_eq!(
+            check_password_policy(&PasswordPolicyConfig::default(), "al", "xALx1!yz"),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn reports_every_violation_at_once() {
+        assert_eq!(
+            check("alice"),
+            vec![
+                PasswordPolicyViolation::TooShort { min_length: 8 },
+                PasswordPolicyViolation::TooFewCharacterClasses {
+                    min_character_classes: 3
+                },
+                PasswordPolicyViolation::ContainsAccount,
+            ]
+        );
+    }
+}
@@ -62,6 +62,42 @@ impl UserService {
         Ok(user.into())
     }
 
+    /// The user who verified the given email address.
+    pub async fn query_user_by_verified_email(&self, email: &str) -> AppResult<User> {
+        let user = self
+            .crud
+            .find_one_by_condition(
+                Condition::all()
+                    .add(users::Column::Email.eq(email))
+                    .add(users::Column::EmailVerified.eq(true)),
+            )
+            .await?;
+
+        let Some(user) = user else {
+            return Err(AppException::UserNotFound.into());
+        };
+
+        Ok(user.into())
+    }
+
+    /// Fail with `UserNotFound` unless every one of the users is within the data scope of the
+    /// service, so users out of scope can't be told apart from missing ones.
+    pub async fn assert_users_in_scope(&self, ids: &[Uuid]) -> AppResult<()> {
+        let found = self
+            .crud
+            .find_by_condition(users::Column::Id.is_in(ids.to_vec()))
+            .await?;
+
+        if ids
+            .iter()
+            .any(|id| !found.iter().any(|user| user.id == *id))
+        {
+            return Err(AppException::UserNotFound.into());
+        }
+
+        Ok(())
+    }
+
     pub async fn query_users_by_page(
         &self,
         params: PageableQuery<FilterUsersParams, UserOrderField>,
@@ -0,0 +1,244 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use entity::users;
+use sea_orm::{ActiveValue::Set, IntoActiveModel, TransactionTrait, prelude::*};
+use shared::{enums::Gender, utils::hash_password};
+
+use crate::{error::AppException, models::user::LastLogin, result::AppResult};
+
+use super::UserService;
+
+/// Profile fields of a user, `None` leaves a field as it is.
+#[derive(Debug, Default)]
+pub struct UpdateUserParams {
+    pub id: Uuid,
+    pub nickname: Option<String>,
+    pub real_name: Option<String>,
+    pub phone: Option<String>,
+    pub email: Option<String>,
+    pub gender: Option<Gender>,
+    pub birthday: Option<NaiveDate>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+impl UserService {
+    /// Update the profile of a user. A changed email has to be verified again.
+    pub async fn update_user(&self, params: UpdateUserParams) -> AppResult<()> {
+        let model = users::Entity::find_by_id(params.id).one(&self.conn).await?;
+        let Some(model) = model else {
+            return Err(AppException::UserNotFound.into());
+        };
+        let previous_email = model.email.clone();
+        let mut active_model = model.into_active_model();
+
+        if let Some(nickname) = params.nickname {
+            active_model.nickname = Set(Some(nickname));
+        }
+
+        if let Some(real_name) = params.real_name {
+            active_model.real_name = Set(Some(real_name));
+        }
+
+        if let Some(phone) = params.phone {
+            active_model.phone = Set(Some(phone));
+        }
+
+        if let Some(email) = params.email
+            && previous_email.as_ref() != Some(&email)
+        {
+            active_model.email = Set(Some(email));
+            active_model.email_verified = Set(false);
+        }
+
+        if let Some(gender) = params.gender {
+            active_model.gender = Set(gender.to_string());
+        }
+
+        if let Some(birthday) = params.birthday {
+            active_model.birthday = Set(Some(birthday));
+        }
+
+        if let Some(bio) = params.bio {
+            active_model.bio = Set(Some(bio));
+        }
+
+        if let Some(avatar_url) = params.avatar_url {
+            active_model.avatar_url = Set(Some(avatar_url));
+        }
+
+        self.crud.update(active_model).await?;
+
+        Ok(())
+    }
+
+    /// Count a failed login. Returns the number of consecutive failures so far.
+    pub async fn increase_failed_login_attempts(&self, id: Uuid) -> AppResult<i16> {
+        let models = users::Entity::update_many()
+            .col_expr(
+                users::Column::FailedLoginAttempts,
+                Expr::cust("LEAST(failed_login_attempts + 1, 32767)"),
+            )
+            .filter(users::Column::Id.eq(id))
+            .exec_with_returning(&self.conn)
+            .await?;
+
+        let Some(model) = models.into_iter().next() else {
+            return Err(AppException::UserNotFound.into());
+        };
+
+        Ok(model.failed_login_attempts)
+    }
+
+    pub async fn lock_user(&self, id: Uuid, locked_until: DateTime<Utc>) -> AppResult<()> {
+        users::Entity::update_many()
+            .col_expr(
+                users::Column::LockedUntil,
+                Expr::value(Some(DateTimeWithTimeZone::from(locked_until))),
+            )
+            .filter(users::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lift a lockout and forget previous failed logins.
+    pub async fn unlock_user(&self, id: Uuid) -> AppResult<()> {
+        users::Entity::update_many()
+            .col_expr(users::Column::FailedLoginAttempts, Expr::value(0i16))
+            .col_expr(
+                users::Column::LockedUntil,
+                Expr::value(Option::<DateTimeWithTimeZone>::None),
+            )
+            .filter(users::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_last_login(&self, id: Uuid, last_login: &LastLogin) -> AppResult<()> {
+        users::Entity::update_many()
+            .col_expr(
+                users::Column::LastLogin,
+                Expr::value(Some(serde_json::to_string(last_login).unwrap())),
+            )
+            .filter(users::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Store a freshly enrolled TOTP secret, or clear it with `None`. Either way TOTP stays
+    /// disabled until a code of the new secret is verified.
+    pub async fn update_totp_secret(&self, id: Uuid, secret: Option<String>) -> AppResult<()> {
+        users::Entity::update_many()
+            .col_expr(users::Column::TotpSecret, Expr::value(secret))
+            .col_expr(users::Column::TotpEnabled, Expr::value(false))
+            .col_expr(
+                users::Column::TotpLastUsedStep,
+                Expr::value(Option::<i64>::None),
+            )
+            .filter(users::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn enable_totp(&self, id: Uuid) -> AppResult<()> {
+        users::Entity::update_many()
+            .col_expr(users::Column::TotpEnabled, Expr::value(true))
+            .filter(users::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark the TOTP code of `step` as used. Returns `false` if that step or a later one was
+    /// already used.
+    pub async fn update_totp_last_used_step(&self, id: Uuid, step: i64) -> AppResult<bool> {
+        let result = users::Entity::update_many()
+            .col_expr(users::Column::TotpLastUsedStep, Expr::value(Some(step)))
+            .filter(users::Column::Id.eq(id))
+            .filter(
+                users::Column::TotpLastUsedStep
+                    .is_null()
+                    .or(users::Column::TotpLastUsedStep.lt(step)),
+            )
+            .exec(&self.conn)
+            .await?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    /// Change the email of the user, which is unverified until `verify_email` is called.
+    pub async fn update_email(&self, id: Uuid, email: &str) -> AppResult<()> {
+        users::Entity::update_many()
+            .col_expr(users::Column::Email, Expr::value(Some(email.to_string())))
+            .col_expr(users::Column::EmailVerified, Expr::value(false))
+            .filter(users::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Mark the email of the user as verified, which finishes a pending registration. Returns
+    /// `false` if the user meanwhile changed the email to a different one.
+    pub async fn verify_email(&self, id: Uuid, email: &str) -> AppResult<bool> {
+        let result = users::Entity::update_many()
+            .col_expr(users::Column::EmailVerified, Expr::value(true))
+            .col_expr(users::Column::RegistrationPending, Expr::value(false))
+            .filter(users::Column::Id.eq(id))
+            .filter(users::Column::Email.eq(email))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(result.rows_affected == 1)
+    }
+
+    /// Set a new password, which has to pass the password policy.
+    pub async fn update_password(&self, id: Uuid, password: &str) -> AppResult<()> {
+        let user = self.query_user_by_id(id).await?;
+        self.validate_password(&user.account, password, Some(id))
+            .await?;
+
+        let password_digest = hash_password(password, &self.app.config.password_hash.params());
+        let txn = self.conn.begin().await?;
+        users::Entity::update_many()
+            .col_expr(
+                users::Column::PasswordDigest,
+                Expr::value(Some(password_digest.clone())),
+            )
+            .col_expr(
+                users::Column::PasswordChangedAt,
+                Expr::value(Some(DateTimeWithTimeZone::from(Utc::now()))),
+            )
+            .filter(users::Column::Id.eq(id))
+            .exec(&txn)
+            .await?;
+        self.record_password_history(&txn, id, password_digest)
+            .await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+
+    /// Replace the stored hash of the current password, e.g. with one of higher cost. The
+    /// password itself stays the same, so neither its age nor the history change.
+    pub async fn update_password_digest(&self, id: Uuid, password_digest: String) -> AppResult<()> {
+        users::Entity::update_many()
+            .col_expr(
+                users::Column::PasswordDigest,
+                Expr::value(Some(password_digest)),
+            )
+            .filter(users::Column::Id.eq(id))
+            .exec(&self.conn)
+            .await?;
+
+        Ok(())
+    }
+}
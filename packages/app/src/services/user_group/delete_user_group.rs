@@ -2,7 +2,7 @@ use entity::user_groups;
 use sea_orm::prelude::Uuid;
 use sea_orm::prelude::*;
 
-use crate::result::AppResult;
+use crate::{result::AppResult, services::auth::AuthService};
 
 use super::UserGroupService;
 
@@ -13,6 +13,10 @@ impl UserGroupService {
     pub async fn delete_user_group_by_id(&self, id: Uuid) -> AppResult<()> {
         self.crud.delete_by_id(id).await?;
 
+        AuthService::new(self.app.clone())
+            .bump_permission_version()
+            .await?;
+
         Ok(())
     }
 
@@ -21,6 +25,10 @@ impl UserGroupService {
             .delete_many(user_groups::Column::Id.is_in(params.0))
             .await?;
 
+        AuthService::new(self.app.clone())
+            .bump_permission_version()
+            .await?;
+
         Ok(())
     }
 }
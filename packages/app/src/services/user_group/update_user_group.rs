@@ -1,7 +1,7 @@
 use entity::user_groups;
 use sea_orm::{ActiveValue::Set, IntoActiveModel, prelude::*};
 
-use crate::{error::AppException, result::AppResult};
+use crate::{error::AppException, result::AppResult, services::auth::AuthService};
 
 use super::UserGroupService;
 
@@ -52,6 +52,10 @@ impl UserGroupService {
 
         self.crud.update(active_model).await?;
 
+        AuthService::new(self.app.clone())
+            .bump_permission_version()
+            .await?;
+
         Ok(())
     }
 }
@@ -0,0 +1,65 @@
+use entity::{login_histories, relation_users_departments, users};
+use migration::Query;
+use sea_orm::{Condition, prelude::*};
+
+/// Entities whose rows belong to a user, and are only visible within the data scope of the
+/// session querying them.
+pub trait DataScoped: EntityTrait {
+    /// Column holding the id of the user a row belongs to.
+    fn owner_column() -> Self::Column;
+}
+
+impl DataScoped for users::Entity {
+    fn owner_column() -> Self::Column {
+        users::Column::Id
+    }
+}
+
+impl DataScoped for login_histories::Entity {
+    fn owner_column() -> Self::Column {
+        login_histories::Column::UserId
+    }
+}
+
+/// Rows a session may see, resolved from its data permissions.
+#[derive(Debug, Clone)]
+pub enum DataScopeFilter {
+    All,
+    /// Rows owned by the user, or by a member of one of the departments.
+    Restricted {
+        user_id: Uuid,
+        department_ids: Vec<Uuid>,
+    },
+}
+
+impl DataScopeFilter {
+    /// Condition limiting `T` to the rows in scope, `None` if every row is.
+    pub fn condition<T: DataScoped>(&self) -> Option<Condition> {
+        let Self::Restricted {
+            user_id,
+            department_ids,
+        } = self
+        else {
+            return None;
+        };
+
+        let owner_column = T::owner_column();
+        let mut condition = Condition::any().add(owner_column.eq(*user_id));
+        if !department_ids.is_empty() {
+            condition = condition.add(
+                owner_column.in_subquery(
+                    Query::select()
+                        .column(relation_users_departments::Column::UserId)
+                        .from(relation_users_departments::Entity)
+                        .and_where(
+                            relation_users_departments::Column::DepartmentId
+                                .is_in(department_ids.clone()),
+                        )
+                        .to_owned(),
+                ),
+            );
+        }
+
+        Some(condition)
+    }
+}
@@ -1 +1,2 @@
+pub mod data_scope;
 pub mod query;
@@ -0,0 +1,214 @@
+//! Runs `OidcProvider` against `MockOidcServer`: discovery, the key set, the token endpoint and
+//! the checks of the ID token, over HTTP and without a database.
+
+use app::{
+    error::{AppError, AppException},
+    identity_provider::{
+        AuthorizationRequest, CodeExchange, ExternalIdentity, IdentityProvider, OidcProvider,
+        mock::{MockIdTokenOptions, MockOidcServer},
+    },
+    result::AppResult,
+};
+use serde_json::{Map, Value, json};
+use shared::utils::{generate_random_token, pkce_challenge};
+
+const REDIRECT_URI: &str = "http://localhost/callback";
+
+/// A login started by the client, with what it keeps to redeem the code.
+struct Login {
+    url: String,
+    code_verifier: String,
+    nonce: String,
+}
+
+async fn start_login(provider: &OidcProvider) -> AppResult<Login> {
+    let code_verifier = generate_random_token(32);
+    let nonce = generate_random_token(16);
+    let url = provider
+        .authorization_url(AuthorizationRequest {
+            redirect_uri: REDIRECT_URI.to_string(),
+            state: generate_random_token(16),
+            nonce: nonce.clone(),
+            code_challenge: pkce_challenge(&code_verifier),
+        })
+        .await?;
+
+    Ok(Login {
+        url,
+        code_verifier,
+        nonce,
+    })
+}
+
+fn exchange(login: &Login, code: &str) -> CodeExchange {
+    CodeExchange {
+        code: code.to_string(),
+        redirect_uri: REDIRECT_URI.to_string(),
+        code_verifier: login.code_verifier.clone(),
+        nonce: login.nonce.clone(),
+    }
+}
+
+fn identity() -> ExternalIdentity {
+    ExternalIdentity {
+        subject: "mock-subject".to_string(),
+        union_id: None,
+        email: Some("user@example.com".to_string()),
+        email_verified: true,
+        name: Some("Mock User".to_string()),
+        avatar_url: None,
+        access_token: Some("mock-access-token".to_string()),
+        payload: Value::Null,
+    }
+}
+
+fn claims(value: Value) -> MockIdTokenOptions {
+    let Value::Object(claims) = value else {
+        unreachable!()
+    };
+
+    MockIdTokenOptions {
+        claims,
+        unknown_key: false,
+    }
+}
+
+fn assert_authentication_failed(result: AppResult<ExternalIdentity>) {
+    assert!(
+        matches!(
+            result,
+            Err(AppError::Exception(
+                AppException::ExternalAuthenticationFailed
+            ))
+        ),
+        "expected ExternalAuthenticationFailed",
+    );
+}
+
+async fn setup() -> AppResult<(MockOidcServer, OidcProvider)> {
+    let server = MockOidcServer::start().await?;
+    let provider = OidcProvider::new(server.provider_config());
+
+    Ok((server, provider))
+}
+
+#[tokio::test]
+async fn exchanges_code_for_identity() -> anyhow::Result<()> {
+    let (server, provider) = setup().await?;
+
+    let login = start_login(&provider).await?;
+    let redirect = server.approve(&login.url, identity())?;
+    let identity = provider
+        .exchange_code(exchange(&login, &redirect.code))
+        .await?;
+
+    assert_eq!(identity.subject, "mock-subject");
+    assert_eq!(identity.email.as_deref(), Some("user@example.com"));
+    assert!(identity.email_verified);
+    assert_eq!(identity.access_token.as_deref(), Some("mock-access-token"));
+    assert_eq!(identity.payload["nonce"], json!(login.nonce));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_replayed_code() -> anyhow::Result<()> {
+    let (server, provider) = setup().await?;
+
+    let login = start_login(&provider).await?;
+    let redirect = server.approve(&login.url, identity())?;
+    provider
+        .exchange_code(exchange(&login, &redirect.code))
+        .await?;
+
+    assert_authentication_failed(
+        provider
+            .exchange_code(exchange(&login, &redirect.code))
+            .await,
+    );
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_other_code_verifier() -> anyhow::Result<()> {
+    let (server, provider) = setup().await?;
+
+    let login = start_login(&provider).await?;
+    let redirect = server.approve(&login.url, identity())?;
+    let exchange = CodeExchange {
+        code_verifier: generate_random_token(32),
+        ..exchange(&login, &redirect.code)
+    };
+
+    assert_authentication_failed(provider.exchange_code(exchange).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_other_redirect_uri() -> anyhow::Result<()> {
+    let (server, provider) = setup().await?;
+
+    let login = start_login(&provider).await?;
+    let redirect = server.approve(&login.url, identity())?;
+    let exchange = CodeExchange {
+        redirect_uri: "http://localhost/elsewhere".to_string(),
+        ..exchange(&login, &redirect.code)
+    };
+
+    assert_authentication_failed(provider.exchange_code(exchange).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_other_nonce() -> anyhow::Result<()> {
+    let (server, provider) = setup().await?;
+
+    let login = start_login(&provider).await?;
+    let redirect = server.approve(&login.url, identity())?;
+    let exchange = CodeExchange {
+        nonce: generate_random_token(16),
+        ..exchange(&login, &redirect.code)
+    };
+
+    assert_authentication_failed(provider.exchange_code(exchange).await);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_invalid_id_tokens() -> anyhow::Result<()> {
+    let (server, provider) = setup().await?;
+
+    let cases = [
+        claims(json!({ "aud": "another-client" })),
+        claims(json!({ "iss": "http://another-issuer" })),
+        claims(json!({ "exp": 1 })),
+        claims(json!({ "nonce": null })),
+        MockIdTokenOptions {
+            claims: Map::new(),
+            unknown_key: true,
+        },
+    ];
+    for options in cases {
+        let login = start_login(&provider).await?;
+        let redirect = server.approve_with(&login.url, identity(), options.clone())?;
+
+        let result = provider
+            .exchange_code(exchange(&login, &redirect.code))
+            .await;
+        assert!(
+            matches!(
+                result,
+                Err(AppError::Exception(
+                    AppException::ExternalAuthenticationFailed
+                ))
+            ),
+            "expected {options:?} to be rejected",
+        );
+    }
+
+    Ok(())
+}
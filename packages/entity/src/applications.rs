@@ -7,19 +7,48 @@ use sea_orm::entity::prelude::*;
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
+    #[sea_orm(unique)]
     pub app_id: Uuid,
-    pub app_secret: String,
+    pub secret_digest: String,
     pub name: String,
-    pub description: Uuid,
+    pub description: Option<String>,
     pub is_deleted: bool,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
+    pub redirect_uris: String,
+    pub scopes: String,
+    pub confidential: bool,
+    pub owner_id: Option<Uuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::application_consents::Entity")]
+    ApplicationConsents,
+    #[sea_orm(has_many = "super::application_nonces::Entity")]
+    ApplicationNonces,
     #[sea_orm(has_many = "super::menus::Entity")]
     Menus,
+    #[sea_orm(
+        belongs_to = "super::users::Entity",
+        from = "Column::OwnerId",
+        to = "super::users::Column::Id",
+        on_update = "NoAction",
+        on_delete = "NoAction"
+    )]
+    Users,
+}
+
+impl Related<super::application_consents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApplicationConsents.def()
+    }
+}
+
+impl Related<super::application_nonces::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApplicationNonces.def()
+    }
 }
 
 impl Related<super::menus::Entity> for Entity {
@@ -28,4 +57,10 @@ impl Related<super::menus::Entity> for Entity {
     }
 }
 
+impl Related<super::users::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Users.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
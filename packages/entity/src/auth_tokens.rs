@@ -15,6 +15,10 @@ pub struct Model {
     pub platform: Option<String>,
     pub payload: String,
     pub expired_at: Option<DateTimeWithTimeZone>,
+    pub last_seen_at: Option<DateTimeWithTimeZone>,
+    pub last_seen_ip: Option<String>,
+    pub device_name: Option<String>,
+    pub application_id: Option<Uuid>,
     pub is_deleted: bool,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
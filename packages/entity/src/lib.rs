@@ -4,6 +4,8 @@ pub mod prelude;
 
 pub mod account_books;
 pub mod accounts;
+pub mod application_consents;
+pub mod application_nonces;
 pub mod applications;
 pub mod auth_tokens;
 pub mod budgets;
@@ -11,9 +13,15 @@ pub mod categories;
 pub mod codes;
 pub mod collaborations;
 pub mod departments;
+pub mod impersonations;
+pub mod invitations;
+pub mod login_histories;
 pub mod menus;
+pub mod password_histories;
+pub mod permission_denials;
 pub mod permission_groups;
 pub mod permissions;
+pub mod recovery_codes;
 pub mod relation_account_books_accounts;
 pub mod relation_permission_groups_departments;
 pub mod relation_permission_groups_roles;
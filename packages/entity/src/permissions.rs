@@ -12,6 +12,8 @@ pub struct Model {
     #[sea_orm(unique)]
     pub code: String,
     pub description: Option<String>,
+    pub data_scope: Option<String>,
+    pub data_department_ids: Option<String>,
     pub is_deleted: bool,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
@@ -19,6 +21,8 @@ pub struct Model {
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::permission_denials::Entity")]
+    PermissionDenials,
     #[sea_orm(has_many = "super::relation_permissions_departments::Entity")]
     RelationPermissionsDepartments,
     #[sea_orm(has_many = "super::relation_permissions_permission_groups::Entity")]
@@ -31,6 +35,12 @@ pub enum Relation {
     RelationPermissionsUsers,
 }
 
+impl Related<super::permission_denials::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PermissionDenials.def()
+    }
+}
+
 impl Related<super::relation_permissions_departments::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::RelationPermissionsDepartments.def()
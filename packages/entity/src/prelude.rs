@@ -2,6 +2,8 @@
 
 pub use super::account_books::Entity as AccountBooks;
 pub use super::accounts::Entity as Accounts;
+pub use super::application_consents::Entity as ApplicationConsents;
+pub use super::application_nonces::Entity as ApplicationNonces;
 pub use super::applications::Entity as Applications;
 pub use super::auth_tokens::Entity as AuthTokens;
 pub use super::budgets::Entity as Budgets;
@@ -9,9 +11,15 @@ pub use super::categories::Entity as Categories;
 pub use super::codes::Entity as Codes;
 pub use super::collaborations::Entity as Collaborations;
 pub use super::departments::Entity as Departments;
+pub use super::impersonations::Entity as Impersonations;
+pub use super::invitations::Entity as Invitations;
+pub use super::login_histories::Entity as LoginHistories;
 pub use super::menus::Entity as Menus;
+pub use super::password_histories::Entity as PasswordHistories;
+pub use super::permission_denials::Entity as PermissionDenials;
 pub use super::permission_groups::Entity as PermissionGroups;
 pub use super::permissions::Entity as Permissions;
+pub use super::recovery_codes::Entity as RecoveryCodes;
 pub use super::relation_account_books_accounts::Entity as RelationAccountBooksAccounts;
 pub use super::relation_permission_groups_departments::Entity as RelationPermissionGroupsDepartments;
 pub use super::relation_permission_groups_roles::Entity as RelationPermissionGroupsRoles;
@@ -20,6 +20,12 @@ pub struct Model {
     pub password_digest: Option<String>,
     pub last_login: Option<String>,
     pub failed_login_attempts: i16,
+    pub locked_until: Option<DateTimeWithTimeZone>,
+    pub totp_secret: Option<String>,
+    pub totp_enabled: bool,
+    pub totp_last_used_step: Option<i64>,
+    pub password_changed_at: Option<DateTimeWithTimeZone>,
+    pub registration_pending: bool,
     pub is_deleted: bool,
     pub created_at: DateTimeWithTimeZone,
     pub updated_at: DateTimeWithTimeZone,
@@ -29,6 +35,14 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::account_books::Entity")]
     AccountBooks,
+    #[sea_orm(has_many = "super::application_consents::Entity")]
+    ApplicationConsents,
+    #[sea_orm(has_many = "super::applications::Entity")]
+    Applications,
+    #[sea_orm(has_many = "super::password_histories::Entity")]
+    PasswordHistories,
+    #[sea_orm(has_many = "super::recovery_codes::Entity")]
+    RecoveryCodes,
     #[sea_orm(has_many = "super::relation_permission_groups_users::Entity")]
     RelationPermissionGroupsUsers,
     #[sea_orm(has_many = "super::relation_permissions_users::Entity")]
@@ -49,6 +63,30 @@ impl Related<super::account_books::Entity> for Entity {
     }
 }
 
+impl Related<super::application_consents::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ApplicationConsents.def()
+    }
+}
+
+impl Related<super::applications::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Applications.def()
+    }
+}
+
+impl Related<super::password_histories::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordHistories.def()
+    }
+}
+
+impl Related<super::recovery_codes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RecoveryCodes.def()
+    }
+}
+
 impl Related<super::relation_permission_groups_users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::RelationPermissionGroupsUsers.def()
@@ -34,6 +34,26 @@ mod m030_create_table_menus;
 mod m031_create_table_codes;
 mod m032_create_table_uploads;
 mod m033_create_table_upload_chunks;
+mod m034_seeding_settings;
+mod m035_alter_table_users_add_locked_until;
+mod m036_create_table_login_histories;
+mod m037_alter_table_users_add_totp;
+mod m038_create_table_recovery_codes;
+mod m039_alter_table_third_users_add_open_id_index;
+mod m040_alter_table_applications_add_oauth;
+mod m041_create_table_application_consents;
+mod m042_create_table_application_nonces;
+mod m043_alter_table_users_add_password_changed_at;
+mod m044_create_table_password_histories;
+mod m045_create_table_impersonations;
+mod m046_alter_table_auth_tokens_add_last_seen;
+mod m047_create_table_invitations;
+mod m048_seeding_settings_registration;
+mod m049_alter_table_permissions_add_data_scope;
+mod m050_create_index_relation_lookups;
+mod m051_create_table_permission_denials;
+mod m052_alter_table_users_add_registration_pending;
+mod m053_alter_table_auth_tokens_add_application_id;
 mod m099_seeding_data_init;
 mod m100_create_table_account_books;
 mod m101_create_table_categories;
@@ -44,6 +64,16 @@ mod m105_create_table_budgets;
 mod m106_create_table_transactions;
 mod m107_create_table_relation_transactions_tags;
 mod m108_create_table_collaborations;
+mod m109_seeding_permission_unlock_user;
+mod m110_seeding_permission_query_login_histories;
+mod m111_seeding_permission_applications;
+mod m112_seeding_permission_impersonate_user;
+mod m113_seeding_permission_user_sessions;
+mod m114_seeding_permission_invitations;
+mod m115_seeding_permission_data_scopes;
+mod m116_seeding_permission_account_books;
+mod m117_seeding_permission_denials;
+mod permission_seeder;
 mod table_manager;
 
 pub struct Migrator;
@@ -86,6 +116,26 @@ impl MigratorTrait for Migrator {
             Box::new(m031_create_table_codes::Migration),
             Box::new(m032_create_table_uploads::Migration),
             Box::new(m033_create_table_upload_chunks::Migration),
+            Box::new(m034_seeding_settings::Migration),
+            Box::new(m035_alter_table_users_add_locked_until::Migration),
+            Box::new(m036_create_table_login_histories::Migration),
+            Box::new(m037_alter_table_users_add_totp::Migration),
+            Box::new(m038_create_table_recovery_codes::Migration),
+            Box::new(m039_alter_table_third_users_add_open_id_index::Migration),
+            Box::new(m040_alter_table_applications_add_oauth::Migration),
+            Box::new(m041_create_table_application_consents::Migration),
+            Box::new(m042_create_table_application_nonces::Migration),
+            Box::new(m043_alter_table_users_add_password_changed_at::Migration),
+            Box::new(m044_create_table_password_histories::Migration),
+            Box::new(m045_create_table_impersonations::Migration),
+            Box::new(m046_alter_table_auth_tokens_add_last_seen::Migration),
+            Box::new(m047_create_table_invitations::Migration),
+            Box::new(m048_seeding_settings_registration::Migration),
+            Box::new(m049_alter_table_permissions_add_data_scope::Migration),
+            Box::new(m050_create_index_relation_lookups::Migration),
+            Box::new(m051_create_table_permission_denials::Migration),
+            Box::new(m052_alter_table_users_add_registration_pending::Migration),
+            Box::new(m053_alter_table_auth_tokens_add_application_id::Migration),
             Box::new(m099_seeding_data_init::Migration),
             Box::new(m100_create_table_account_books::Migration),
             Box::new(m101_create_table_categories::Migration),
@@ -96,6 +146,15 @@ impl MigratorTrait for Migrator {
             Box::new(m106_create_table_transactions::Migration),
             Box::new(m107_create_table_relation_transactions_tags::Migration),
             Box::new(m108_create_table_collaborations::Migration),
+            Box::new(m109_seeding_permission_unlock_user::Migration),
+            Box::new(m110_seeding_permission_query_login_histories::Migration),
+            Box::new(m111_seeding_permission_applications::Migration),
+            Box::new(m112_seeding_permission_impersonate_user::Migration),
+            Box::new(m113_seeding_permission_user_sessions::Migration),
+            Box::new(m114_seeding_permission_invitations::Migration),
+            Box::new(m115_seeding_permission_data_scopes::Migration),
+            Box::new(m116_seeding_permission_account_books::Migration),
+            Box::new(m117_seeding_permission_denials::Migration),
         ]
     }
 }
@@ -0,0 +1,66 @@
+use entity::settings;
+use sea_orm::prelude::*;
+use sea_orm_migration::{prelude::*, sea_orm::ActiveValue::Set};
+use shared::enums::SettingName;
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Settings are looked up and upserted by name
+        manager
+            .create_index(
+                Index::create()
+                    .name("uk_settings_name")
+                    .table(Settings::Table)
+                    .col(Settings::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Bumped whenever the permission graph changes, see `AuthService::bump_permission_version`
+        settings::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(SettingName::PermissionVersion.to_string()),
+            value: Set("0".to_string()),
+            description: Set(Some("Version of the permission graph".to_string())),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        settings::Entity::delete_many()
+            .filter(settings::Column::Name.eq(SettingName::PermissionVersion.to_string()))
+            .exec(db)
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("uk_settings_name")
+                    .table(Settings::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Settings {
+    Table,
+    Name,
+}
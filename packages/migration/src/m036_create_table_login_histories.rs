@@ -0,0 +1,53 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::table_manager::TableManager;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, LoginHistories::Table)
+            .create_table(
+                Table::create()
+                    .col(uuid(LoginHistories::Id))
+                    .col(uuid_null(LoginHistories::UserId))
+                    .col(string(LoginHistories::Account))
+                    .col(boolean(LoginHistories::Success))
+                    .col(string_null(LoginHistories::FailureReason))
+                    .col(string_null(LoginHistories::Ip))
+                    .col(string_null(LoginHistories::Agent))
+                    .col(string_null(LoginHistories::Platform))
+                    .to_owned(),
+            )
+            .await?
+            .create_index(vec![LoginHistories::UserId])
+            .await?
+            .create_index(vec![LoginHistories::Account])
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, LoginHistories::Table)
+            .drop_table()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum LoginHistories {
+    Table,
+    Id,
+    UserId,
+    Account,
+    Success,
+    FailureReason,
+    Ip,
+    Agent,
+    Platform,
+}
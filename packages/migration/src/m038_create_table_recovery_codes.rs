@@ -0,0 +1,45 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{m008_create_table_users::Users, table_manager::TableManager};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, RecoveryCodes::Table)
+            .create_table(
+                Table::create()
+                    .col(uuid(RecoveryCodes::Id))
+                    .col(uuid(RecoveryCodes::UserId))
+                    .col(string(RecoveryCodes::CodeDigest))
+                    .col(timestamp_with_time_zone_null(RecoveryCodes::UsedAt))
+                    .to_owned(),
+            )
+            .await?
+            .create_foreign_key(RecoveryCodes::UserId, Users::Table, Users::Id)
+            .await?
+            .create_index(vec![RecoveryCodes::UserId])
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, RecoveryCodes::Table)
+            .drop_table()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum RecoveryCodes {
+    Table,
+    Id,
+    UserId,
+    CodeDigest,
+    UsedAt,
+}
@@ -0,0 +1,101 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{
+    m008_create_table_users::Users, m029_create_table_applications::Applications,
+    table_manager::TableManager,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Applications::Table)
+                    .rename_column(Applications::AppSecret, OAuthApplications::SecretDigest)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Applications::Table)
+                    .modify_column(string_null(Applications::Description))
+                    .add_column_if_not_exists(string(OAuthApplications::RedirectUris).default("[]"))
+                    .add_column_if_not_exists(string(OAuthApplications::Scopes).default("[]"))
+                    .add_column_if_not_exists(
+                        boolean(OAuthApplications::Confidential).default(true),
+                    )
+                    .add_column_if_not_exists(uuid_null(OAuthApplications::OwnerId))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Clients are looked up by the public `app_id` on every request
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_applications_app_id")
+                    .table(Applications::Table)
+                    .col(Applications::AppId)
+                    .unique()
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        TableManager::new(manager, Applications::Table)
+            .create_foreign_key(OAuthApplications::OwnerId, Users::Table, Users::Id)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_foreign_key(
+                ForeignKey::drop()
+                    .name("fk_applications_owner_id")
+                    .table(Applications::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx_applications_app_id")
+                    .table(Applications::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Applications::Table)
+                    .drop_column(OAuthApplications::RedirectUris)
+                    .drop_column(OAuthApplications::Scopes)
+                    .drop_column(OAuthApplications::Confidential)
+                    .drop_column(OAuthApplications::OwnerId)
+                    .rename_column(OAuthApplications::SecretDigest, Applications::AppSecret)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum OAuthApplications {
+    SecretDigest,
+    RedirectUris,
+    Scopes,
+    Confidential,
+    OwnerId,
+}
@@ -0,0 +1,54 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{
+    m008_create_table_users::Users, m029_create_table_applications::Applications,
+    table_manager::TableManager,
+};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, ApplicationConsents::Table)
+            .primary_key(vec![
+                ApplicationConsents::UserId,
+                ApplicationConsents::ApplicationId,
+            ])
+            .create_table(
+                Table::create()
+                    .col(uuid(ApplicationConsents::UserId))
+                    .col(uuid(ApplicationConsents::ApplicationId))
+                    .col(string(ApplicationConsents::Scopes))
+                    .to_owned(),
+            )
+            .await?
+            .create_foreign_key(ApplicationConsents::UserId, Users::Table, Users::Id)
+            .await?
+            .create_foreign_key(
+                ApplicationConsents::ApplicationId,
+                Applications::Table,
+                Applications::Id,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, ApplicationConsents::Table)
+            .drop_table()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApplicationConsents {
+    Table,
+    UserId,
+    ApplicationId,
+    Scopes,
+}
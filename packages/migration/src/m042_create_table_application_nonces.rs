@@ -0,0 +1,47 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{m029_create_table_applications::Applications, table_manager::TableManager};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, ApplicationNonces::Table)
+            .primary_key(vec![
+                ApplicationNonces::ApplicationId,
+                ApplicationNonces::Nonce,
+            ])
+            .create_table(
+                Table::create()
+                    .col(uuid(ApplicationNonces::ApplicationId))
+                    .col(string(ApplicationNonces::Nonce))
+                    .to_owned(),
+            )
+            .await?
+            .create_foreign_key(
+                ApplicationNonces::ApplicationId,
+                Applications::Table,
+                Applications::Id,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, ApplicationNonces::Table)
+            .drop_table()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum ApplicationNonces {
+    Table,
+    ApplicationId,
+    Nonce,
+}
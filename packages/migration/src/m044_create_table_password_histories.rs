@@ -0,0 +1,43 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{m008_create_table_users::Users, table_manager::TableManager};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, PasswordHistories::Table)
+            .create_table(
+                Table::create()
+                    .col(uuid(PasswordHistories::Id))
+                    .col(uuid(PasswordHistories::UserId))
+                    .col(string(PasswordHistories::PasswordDigest))
+                    .to_owned(),
+            )
+            .await?
+            .create_foreign_key(PasswordHistories::UserId, Users::Table, Users::Id)
+            .await?
+            .create_index(vec![PasswordHistories::UserId])
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, PasswordHistories::Table)
+            .drop_table()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum PasswordHistories {
+    Table,
+    Id,
+    UserId,
+    PasswordDigest,
+}
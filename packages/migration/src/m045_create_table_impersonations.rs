@@ -0,0 +1,59 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::table_manager::TableManager;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, Impersonations::Table)
+            .create_table(
+                Table::create()
+                    .col(uuid(Impersonations::Id))
+                    .col(uuid(Impersonations::UserId))
+                    .col(uuid(Impersonations::ImpersonatorId))
+                    .col(string(Impersonations::ImpersonatorAccount))
+                    .col(uuid(Impersonations::SessionId))
+                    .col(string_null(Impersonations::Reason))
+                    .col(string_null(Impersonations::Ip))
+                    .col(string_null(Impersonations::Agent))
+                    .col(string_null(Impersonations::Platform))
+                    .col(timestamp_with_time_zone_null(Impersonations::EndedAt))
+                    .to_owned(),
+            )
+            .await?
+            .create_index(vec![Impersonations::UserId])
+            .await?
+            .create_index(vec![Impersonations::ImpersonatorId])
+            .await?
+            .create_index(vec![Impersonations::SessionId])
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, Impersonations::Table)
+            .drop_table()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Impersonations {
+    Table,
+    Id,
+    UserId,
+    ImpersonatorId,
+    ImpersonatorAccount,
+    SessionId,
+    Reason,
+    Ip,
+    Agent,
+    Platform,
+    EndedAt,
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthTokens::Table)
+                    .add_column_if_not_exists(timestamp_with_time_zone_null(AuthTokens::LastSeenAt))
+                    .add_column_if_not_exists(string_null(AuthTokens::LastSeenIp))
+                    .add_column_if_not_exists(string_null(AuthTokens::DeviceName))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthTokens::Table)
+                    .drop_column(AuthTokens::LastSeenAt)
+                    .drop_column(AuthTokens::LastSeenIp)
+                    .drop_column(AuthTokens::DeviceName)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum AuthTokens {
+    Table,
+    LastSeenAt,
+    LastSeenIp,
+    DeviceName,
+}
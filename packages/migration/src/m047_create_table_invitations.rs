@@ -0,0 +1,53 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::table_manager::TableManager;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, Invitations::Table)
+            .create_table(
+                Table::create()
+                    .col(uuid(Invitations::Id))
+                    .col(string(Invitations::CodeDigest).unique_key())
+                    .col(string_null(Invitations::Note))
+                    .col(string(Invitations::RoleIds))
+                    .col(string(Invitations::DepartmentIds))
+                    .col(string(Invitations::UserGroupIds))
+                    .col(uuid_null(Invitations::CreatedBy))
+                    .col(timestamp_with_time_zone_null(Invitations::ExpiredAt))
+                    .col(uuid_null(Invitations::UsedBy))
+                    .col(timestamp_with_time_zone_null(Invitations::UsedAt))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, Invitations::Table)
+            .drop_table()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Invitations {
+    Table,
+    Id,
+    CodeDigest,
+    Note,
+    RoleIds,
+    DepartmentIds,
+    UserGroupIds,
+    CreatedBy,
+    ExpiredAt,
+    UsedBy,
+    UsedAt,
+}
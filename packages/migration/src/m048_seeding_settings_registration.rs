@@ -0,0 +1,54 @@
+use entity::settings;
+use sea_orm::prelude::*;
+use sea_orm_migration::{prelude::*, sea_orm::ActiveValue::Set};
+use shared::enums::{RegistrationMode, SettingName};
+use uuid::Uuid;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Registration stays open, as it was before it could be restricted
+        settings::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(SettingName::RegistrationMode.to_string()),
+            value: Set(RegistrationMode::Open.to_string()),
+            description: Set(Some("Who may register".to_string())),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+        settings::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            name: Set(SettingName::RegistrationDomains.to_string()),
+            value: Set(String::new()),
+            description: Set(Some(
+                "Comma separated email domains that may register when registration is domain restricted"
+                    .to_string(),
+            )),
+            ..Default::default()
+        }
+        .insert(db)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        settings::Entity::delete_many()
+            .filter(settings::Column::Name.is_in([
+                SettingName::RegistrationMode.to_string(),
+                SettingName::RegistrationDomains.to_string(),
+            ]))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+}
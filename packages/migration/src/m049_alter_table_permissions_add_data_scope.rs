@@ -0,0 +1,42 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Permissions::Table)
+                    .add_column_if_not_exists(string_null(Permissions::DataScope))
+                    .add_column_if_not_exists(string_null(Permissions::DataDepartmentIds))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Permissions::Table)
+                    .drop_column(Permissions::DataScope)
+                    .drop_column(Permissions::DataDepartmentIds)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum Permissions {
+    Table,
+    DataScope,
+    DataDepartmentIds,
+}
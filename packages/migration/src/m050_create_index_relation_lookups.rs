@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+/// Relation columns that permissions are resolved through but that don't lead the primary key
+/// of their table, so looking them up would otherwise scan the whole table.
+const LOOKUP_COLUMNS: &[(&str, &str)] = &[
+    ("relation_permission_groups_departments", "department_id"),
+    ("relation_permission_groups_roles", "role_id"),
+    ("relation_permission_groups_user_groups", "user_group_id"),
+    ("relation_permission_groups_users", "user_id"),
+    ("relation_permissions_departments", "department_id"),
+    (
+        "relation_permissions_permission_groups",
+        "permission_group_id",
+    ),
+    ("relation_permissions_roles", "role_id"),
+    ("relation_permissions_user_groups", "user_group_id"),
+    ("relation_permissions_users", "user_id"),
+    ("relation_role_groups_departments", "department_id"),
+    ("relation_role_groups_user_groups", "user_group_id"),
+    ("relation_role_groups_users", "user_id"),
+    ("relation_roles_departments", "department_id"),
+    ("relation_roles_role_groups", "role_group_id"),
+    ("relation_roles_user_groups", "user_group_id"),
+    ("relation_roles_users", "user_id"),
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (table, column) in LOOKUP_COLUMNS {
+            manager
+                .create_index(
+                    Index::create()
+                        .name(index_name(table, column))
+                        .table(Alias::new(*table))
+                        .col(Alias::new(*column))
+                        .if_not_exists()
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for (table, column) in LOOKUP_COLUMNS {
+            manager
+                .drop_index(
+                    Index::drop()
+                        .name(index_name(table, column))
+                        .table(Alias::new(*table))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+fn index_name(table: &str, column: &str) -> String {
+    format!("idx_{table}_{column}")
+}
@@ -0,0 +1,64 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+use crate::{m004_create_table_permissions::Permissions, table_manager::TableManager};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // The subject is a user, user group, department, role or role group, told apart by
+        // `subject_kind`, so it can't have a foreign key
+        TableManager::new(manager, PermissionDenials::Table)
+            .primary_key(vec![
+                PermissionDenials::PermissionId,
+                PermissionDenials::SubjectKind,
+                PermissionDenials::SubjectId,
+            ])
+            .create_table(
+                Table::create()
+                    .col(uuid(PermissionDenials::PermissionId))
+                    .col(string(PermissionDenials::SubjectKind))
+                    .col(uuid(PermissionDenials::SubjectId))
+                    .to_owned(),
+            )
+            .await?
+            .create_foreign_key(
+                PermissionDenials::PermissionId,
+                Permissions::Table,
+                Permissions::Id,
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_permission_denials_subject")
+                    .table(PermissionDenials::Table)
+                    .col(PermissionDenials::SubjectKind)
+                    .col(PermissionDenials::SubjectId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        TableManager::new(manager, PermissionDenials::Table)
+            .drop_table()
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum PermissionDenials {
+    Table,
+    PermissionId,
+    SubjectKind,
+    SubjectId,
+}
@@ -0,0 +1,70 @@
+use sea_orm_migration::{prelude::*, schema::*};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const INDEX_NAME: &str = "idx_auth_tokens_application_id";
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthTokens::Table)
+                    .add_column_if_not_exists(uuid_null(AuthTokens::ApplicationId))
+                    .to_owned(),
+            )
+            .await?;
+
+        // Access tokens issued so far only carry the application in their payload
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "UPDATE auth_tokens SET application_id = (payload::jsonb ->> 'application_id')::uuid \
+                 WHERE kind = 'o_auth_access' AND application_id IS NULL",
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name(INDEX_NAME)
+                    .table(AuthTokens::Table)
+                    .col(AuthTokens::ApplicationId)
+                    .if_not_exists()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name(INDEX_NAME)
+                    .table(AuthTokens::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(AuthTokens::Table)
+                    .drop_column(AuthTokens::ApplicationId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+pub enum AuthTokens {
+    Table,
+    ApplicationId,
+}
@@ -7,7 +7,7 @@ use sea_orm::prelude::*;
 use sea_orm_migration::{prelude::*, sea_orm::ActiveValue::Set};
 use shared::{
     enums::{Gender, OperationPermission as OP, PermissionKind},
-    utils::hash_password,
+    utils::{hash_password, PasswordHashParams},
 };
 use uuid::Uuid;
 
@@ -35,7 +35,10 @@ impl MigrationTrait for Migration {
         let admin_user_id = users::ActiveModel {
             id: Set(Uuid::nil()),
             account: Set("admin".to_string()),
-            password_digest: Set(Some(hash_password("Admin@132"))),
+            password_digest: Set(Some(hash_password(
+                "Admin@132",
+                &PasswordHashParams::default(),
+            ))),
             gender: Set(Gender::Unknown.to_string()),
             ..Default::default()
         }
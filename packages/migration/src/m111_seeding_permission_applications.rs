@@ -0,0 +1,25 @@
+use sea_orm_migration::prelude::*;
+use shared::enums::OperationPermission as OP;
+
+use crate::permission_seeder::{remove_operation_permissions, seed_operation_permissions};
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+const PERMISSIONS: &[OP] = &[
+    OP::QueryApplications,
+    OP::CreateApplication,
+    OP::UpdateApplication,
+    OP::DeleteApplication,
+];
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        seed_operation_permissions(manager.get_connection(), PERMISSIONS).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        remove_operation_permissions(manager.get_connection(), PERMISSIONS).await
+    }
+}
@@ -0,0 +1,46 @@
+use entity::settings;
+use sea_orm::prelude::*;
+use sea_orm_migration::prelude::*;
+use shared::enums::{DataPermission as DP, DataScope, SettingName};
+
+use crate::permission_seeder::{remove_data_permissions, seed_data_permissions};
+
+const PERMISSIONS: &[(DP, DataScope)] = &[
+    (DP::DataScopeAll, DataScope::All),
+    (DP::DataScopeDepartmentTree, DataScope::DepartmentTree),
+    (DP::DataScopeDepartment, DataScope::Department),
+    (DP::DataScopeSelf, DataScope::SelfOnly),
+];
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        seed_data_permissions(db, PERMISSIONS).await?;
+
+        // Sessions resolved before carry no data permissions, and would only see their own rows
+        settings::Entity::update_many()
+            .col_expr(
+                settings::Column::Value,
+                Expr::cust("(CAST(value AS BIGINT) + 1)::TEXT"),
+            )
+            .filter(settings::Column::Name.eq(SettingName::PermissionVersion.to_string()))
+            .exec(db)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let data_permissions = PERMISSIONS
+            .iter()
+            .map(|(x, _)| x.clone())
+            .collect::<Vec<_>>();
+
+        remove_data_permissions(manager.get_connection(), &data_permissions).await
+    }
+}
@@ -0,0 +1,133 @@
+use entity::{permissions, relation_permissions_permission_groups};
+use sea_orm::prelude::*;
+use sea_orm_migration::{prelude::*, sea_orm::ActiveValue::Set};
+use shared::enums::{DataPermission, DataScope, OperationPermission, PermissionKind};
+use uuid::Uuid;
+
+/// Id of the built-in "system" permission group created in `m099_seeding_data_init`.
+const SYSTEM_PERMISSION_GROUP_ID: Uuid = Uuid::nil();
+
+/// Create built-in operation permissions introduced after the initial seeding, and put them
+/// into the "system" permission group.
+pub async fn seed_operation_permissions(
+    db: &SchemaManagerConnection<'_>,
+    operation_permissions: &[OperationPermission],
+) -> Result<(), DbErr> {
+    let permission_id_list =
+        permissions::Entity::insert_many(operation_permissions.iter().map(|x| {
+            permissions::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                code: Set(x.to_string()),
+                kind: Set(PermissionKind::Operation.to_string()),
+                description: Set(None),
+                built_in: Set(true),
+                ..Default::default()
+            }
+        }))
+        .exec_with_returning_keys(db)
+        .await?;
+
+    add_to_system_permission_group(db, permission_id_list).await
+}
+
+/// Create built-in data permissions with the scope each of them grants, and put them into the
+/// "system" permission group.
+pub async fn seed_data_permissions(
+    db: &SchemaManagerConnection<'_>,
+    data_permissions: &[(DataPermission, DataScope)],
+) -> Result<(), DbErr> {
+    let permission_id_list =
+        permissions::Entity::insert_many(data_permissions.iter().map(|(x, data_scope)| {
+            permissions::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                code: Set(x.to_string()),
+                kind: Set(PermissionKind::Data.to_string()),
+                description: Set(None),
+                built_in: Set(true),
+                data_scope: Set(Some(data_scope.to_string())),
+                ..Default::default()
+            }
+        }))
+        .exec_with_returning_keys(db)
+        .await?;
+
+    add_to_system_permission_group(db, permission_id_list).await
+}
+
+async fn add_to_system_permission_group(
+    db: &SchemaManagerConnection<'_>,
+    permission_id_list: Vec<Uuid>,
+) -> Result<(), DbErr> {
+    relation_permissions_permission_groups::Entity::insert_many(
+        permission_id_list.into_iter().map(|x| {
+            relation_permissions_permission_groups::ActiveModel {
+                permission_id: Set(x),
+                permission_group_id: Set(SYSTEM_PERMISSION_GROUP_ID),
+                ..Default::default()
+            }
+        }),
+    )
+    .exec(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Remove permissions created by `seed_operation_permissions`, along with every assignment of
+/// them.
+pub async fn remove_operation_permissions(
+    db: &SchemaManagerConnection<'_>,
+    operation_permissions: &[OperationPermission],
+) -> Result<(), DbErr> {
+    remove_permissions(db, operation_permissions.iter().map(|x| x.to_string())).await
+}
+
+/// Remove permissions created by `seed_data_permissions`, along with every assignment of them.
+pub async fn remove_data_permissions(
+    db: &SchemaManagerConnection<'_>,
+    data_permissions: &[DataPermission],
+) -> Result<(), DbErr> {
+    remove_permissions(db, data_permissions.iter().map(|x| x.to_string())).await
+}
+
+async fn remove_permissions(
+    db: &SchemaManagerConnection<'_>,
+    codes: impl Iterator<Item = String>,
+) -> Result<(), DbErr> {
+    let codes = codes.collect::<Vec<_>>();
+    let permission_id_list = permissions::Entity::find()
+        .filter(permissions::Column::Code.is_in(codes))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|x| format!("'{}'", x.id))
+        .collect::<Vec<_>>();
+    if permission_id_list.is_empty() {
+        return Ok(());
+    }
+    let permission_id_list = permission_id_list.join(",");
+
+    for table_name in [
+        "permission_denials",
+        "relation_permissions_departments",
+        "relation_permissions_permission_groups",
+        "relation_permissions_roles",
+        "relation_permissions_user_groups",
+        "relation_permissions_users",
+        "permissions",
+    ] {
+        let column_name = if table_name == "permissions" {
+            "id"
+        } else {
+            "permission_id"
+        };
+        let sql = format!(
+            r#"
+        DELETE FROM "{table_name}" WHERE {column_name} IN ({permission_id_list});
+        "#,
+        );
+        db.execute_unprepared(&sql).await?;
+    }
+
+    Ok(())
+}
@@ -1,5 +1,30 @@
-use axum::{Router, handler::Handler};
-use utoipa::{Path, openapi::HttpMethod};
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
+
+use axum::{
+    Router,
+    extract::{FromRequestParts, Request},
+    handler::Handler,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+};
+use shared::enums::OperationPermission;
+use utoipa::{
+    Modify, Path,
+    openapi::{
+        HttpMethod, OpenApi,
+        security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    },
+};
+
+use crate::{
+    error::{ServerError, ServerExceptionCode},
+    extractors::principal::Principal,
+};
+
+/// Security scheme of signed in users, by bearer token or session cookie.
+pub const SESSION_SCHEME: &str = "session";
+/// Security scheme of applications signing their requests.
+pub const SIGNATURE_SCHEME: &str = "signature";
 
 pub struct ApiRouter<S = ()> {
     pub router: Router<S>,
@@ -14,6 +39,8 @@ impl<S: Clone + Send + Sync + 'static> ApiRouter<S> {
 }
 
 impl<S: Clone + Send + Sync + 'static> ApiRouter<S> {
+    /// Route the handler as its `#[utoipa::path]` describes, guarded by the permissions the
+    /// path declares in its `security`.
     pub fn route<T, P>(self, _api_path: P, handler: impl Handler<T, S>) -> Self
     where
         P: Path,
@@ -22,8 +49,9 @@ impl<S: Clone + Send + Sync + 'static> ApiRouter<S> {
         let mut router = self;
         let path = P::path();
         let method = P::methods();
+        let requirements = Arc::new(PermissionRequirement::parse::<P>());
         for method in method.into_iter() {
-            let method_router = match method {
+            let mut method_router = match method {
                 HttpMethod::Get => axum::routing::get(handler.clone()),
                 HttpMethod::Post => axum::routing::post(handler.clone()),
                 HttpMethod::Put => axum::routing::put(handler.clone()),
@@ -33,6 +61,14 @@ impl<S: Clone + Send + Sync + 'static> ApiRouter<S> {
                 HttpMethod::Options => axum::routing::options(handler.clone()),
                 HttpMethod::Trace => axum::routing::trace(handler.clone()),
             };
+            if !requirements.is_empty() {
+                let requirements = requirements.clone();
+                method_router = method_router.route_layer(middleware::from_fn(
+                    move |request: Request, next: Next| {
+                        guard_permissions(requirements.clone(), request, next)
+                    },
+                ));
+            }
             router = Self {
                 router: router.router.route(&path, method_router),
             }
@@ -42,6 +78,108 @@ impl<S: Clone + Send + Sync + 'static> ApiRouter<S> {
     }
 }
 
+/// One of the `security` requirements of a path: callers authenticated by `scheme` holding
+/// every one of `permissions`. A caller meeting any requirement of a path may call it.
+#[derive(Debug)]
+struct PermissionRequirement {
+    scheme: String,
+    permissions: Vec<String>,
+}
+
+impl PermissionRequirement {
+    /// Requirements declared by the path, as `security(("session" = ["query_users"]))`.
+    /// Unknown schemes or permissions are bugs in the declaration, and fail at start up.
+    fn parse<P: Path>() -> Vec<Self> {
+        let path = P::path();
+        let security = P::operation().security.unwrap_or_default();
+
+        security
+            .iter()
+            .map(|requirement| {
+                // The requirement keeps its schemes private, but serializes as a map of them
+                let schemes = serde_json::from_value::<BTreeMap<String, Vec<String>>>(
+                    serde_json::to_value(requirement).unwrap(),
+                )
+                .unwrap();
+                // A caller is authenticated by one scheme only, so requiring two can't be met
+                let [(scheme, permissions)] = <[_; 1]>::try_from(Vec::from_iter(schemes))
+                    .unwrap_or_else(|_| panic!("expected a single security scheme on {path}"));
+                assert!(
+                    [SESSION_SCHEME, SIGNATURE_SCHEME].contains(&scheme.as_str()),
+                    "unknown security scheme `{scheme}` on {path}"
+                );
+                for permission in &permissions {
+                    assert!(
+                        OperationPermission::from_str(permission).is_ok(),
+                        "unknown permission `{permission}` on {path}"
+                    );
+                }
+
+                Self {
+                    scheme,
+                    permissions,
+                }
+            })
+            .collect()
+    }
+
+    fn is_met_by(&self, principal: &Principal) -> bool {
+        let scheme = match principal {
+            Principal::User(_) => SESSION_SCHEME,
+            Principal::Application(_) => SIGNATURE_SCHEME,
+        };
+
+        self.scheme == scheme && self.permissions.iter().all(|x| principal.has_permission(x))
+    }
+}
+
+async fn guard_permissions(
+    requirements: Arc<Vec<PermissionRequirement>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = request.into_parts();
+    let principal = match Principal::from_request_parts(&mut parts, &()).await {
+        Ok(principal) => principal,
+        Err(err) => return err.into_response(),
+    };
+    if !requirements.iter().any(|x| x.is_met_by(&principal)) {
+        return ServerError::from(ServerExceptionCode::Forbidden).into_response();
+    }
+
+    next.run(Request::from_parts(parts, body)).await
+}
+
+/// Adds the security schemes the permissions of paths are declared against.
+pub struct SecurityAddon;
+
+impl Modify for SecurityAddon {
+    fn modify(&self, openapi: &mut OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            SESSION_SCHEME,
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some(
+                        "Signed in user, the session cookie works as well. Scopes are the \
+                         permissions the user needs",
+                    ))
+                    .build(),
+            ),
+        );
+        components.add_security_scheme(
+            SIGNATURE_SCHEME,
+            SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::with_description(
+                "x-signature",
+                "Application signing its requests. Scopes are the permissions the application \
+                 needs",
+            ))),
+        );
+    }
+}
+
 #[macro_export]
 macro_rules! init_router {
     ($($handler:ident),*) => {
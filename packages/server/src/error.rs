@@ -44,15 +44,52 @@ impl ServerError {
         match &self {
             &Self::Exception(exception) => match exception.code {
                 ServerExceptionCode::Unauthorized => StatusCode::UNAUTHORIZED,
+                ServerExceptionCode::SessionExpired => StatusCode::UNAUTHORIZED,
                 ServerExceptionCode::Forbidden => StatusCode::FORBIDDEN,
                 ServerExceptionCode::NotFound => StatusCode::NOT_FOUND,
+                ServerExceptionCode::TooManyRequests => StatusCode::TOO_MANY_REQUESTS,
+                ServerExceptionCode::UnknownRsaKey => StatusCode::BAD_REQUEST,
                 ServerExceptionCode::InternalServerError => StatusCode::INTERNAL_SERVER_ERROR,
             },
             &Self::App(err) => match err {
                 &AppError::Exception(ref exception) => match exception {
-                    &AppException::AuthenticationFailed => StatusCode::UNAUTHORIZED,
+                    AppException::AuthenticationFailed
+                    | AppException::InvalidRefreshToken
+                    | AppException::RefreshTokenReused
+                    | AppException::ExternalAuthenticationFailed
+                    | AppException::InvalidClient
+                    | AppException::InvalidSignature
+                    | AppException::RequestReplayed => StatusCode::UNAUTHORIZED,
+                    AppException::AccountLocked => StatusCode::LOCKED,
+                    AppException::InvalidCredentials
+                    | AppException::PasswordPolicyViolated(_)
+                    | AppException::InvalidTotpCode
+                    | AppException::InvalidEmail
+                    | AppException::InvalidVerificationToken
+                    | AppException::UnauthorizedClient
+                    | AppException::InvalidGrant
+                    | AppException::InvalidScope
+                    | AppException::InvalidRedirectUri
+                    | AppException::UnsupportedGrantType
+                    | AppException::UnsupportedResponseType
+                    | AppException::InvalidRequest
+                    | AppException::InvalidInvitation
+                    | AppException::InvalidDataScope => StatusCode::BAD_REQUEST,
+                    AppException::TotpAlreadyEnabled | AppException::TotpNotEnrolled => {
+                        StatusCode::CONFLICT
+                    }
+                    AppException::PermissionNotGranted(_)
+                    | AppException::ImpersonationNotAllowed
+                    | AppException::RegistrationDisabled
+                    | AppException::InvitationRequired
+                    | AppException::EmailDomainNotAllowed => StatusCode::FORBIDDEN,
+                    AppException::NotFound
+                    | AppException::IdentityProviderNotFound
+                    | AppException::ApplicationNotFound => StatusCode::NOT_FOUND,
                     _ => StatusCode::INTERNAL_SERVER_ERROR,
                 },
+                // An identity provider that can't be reached or answers garbage
+                AppError::Http(_) => StatusCode::BAD_GATEWAY,
                 _ => StatusCode::INTERNAL_SERVER_ERROR,
             },
             _ => StatusCode::INTERNAL_SERVER_ERROR,
@@ -65,6 +102,15 @@ impl ServerError {
             _ => self.status().as_str().to_string(),
         }
     }
+
+    pub fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            Self::App(AppError::Exception(AppException::PasswordPolicyViolated(violations))) => {
+                serde_json::to_value(violations).ok()
+            }
+            _ => None,
+        }
+    }
 }
 
 impl From<&str> for ServerError {
@@ -75,7 +121,8 @@ impl From<&str> for ServerError {
 
 impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
-        let error_data = ResponseErrorJson::new(self.code(), self.to_string());
+        let error_data =
+            ResponseErrorJson::new(self.code(), self.to_string()).details(self.details());
         (self.status(), serde_json::to_string(&error_data).unwrap()).into_response()
     }
 }
@@ -84,9 +131,14 @@ impl IntoResponse for ServerError {
 #[strum(serialize_all = "snake_case")]
 pub enum ServerExceptionCode {
     Unauthorized,
+    SessionExpired,
     Forbidden,
     InternalServerError,
     NotFound,
+    TooManyRequests,
+    /// The client encrypted with a key that is unknown or no longer valid, and should fetch the
+    /// current keys again.
+    UnknownRsaKey,
 }
 
 impl From<ServerExceptionCode> for ServerError {
@@ -134,3 +186,56 @@ impl ServerException {
         self
     }
 }
+
+/// An error of the OAuth token, introspection and revocation endpoints, answered in the shape of
+/// RFC 6749 rather than the usual error body, since it is read by OAuth client libraries.
+#[derive(Debug)]
+pub struct OAuthError(ServerError);
+
+impl OAuthError {
+    fn error(&self) -> (StatusCode, &'static str) {
+        let ServerError::App(AppError::Exception(exception)) = &self.0 else {
+            return (self.0.status(), "server_error");
+        };
+        match exception {
+            AppException::InvalidClient => (StatusCode::UNAUTHORIZED, "invalid_client"),
+            AppException::InvalidGrant => (StatusCode::BAD_REQUEST, "invalid_grant"),
+            AppException::InvalidScope => (StatusCode::BAD_REQUEST, "invalid_scope"),
+            AppException::UnauthorizedClient => (StatusCode::BAD_REQUEST, "unauthorized_client"),
+            AppException::UnsupportedGrantType => {
+                (StatusCode::BAD_REQUEST, "unsupported_grant_type")
+            }
+            AppException::InvalidRequest => (StatusCode::BAD_REQUEST, "invalid_request"),
+            _ => (self.0.status(), "server_error"),
+        }
+    }
+}
+
+impl<T: Into<ServerError>> From<T> for OAuthError {
+    fn from(value: T) -> Self {
+        Self(value.into())
+    }
+}
+
+impl IntoResponse for OAuthError {
+    fn into_response(self) -> Response {
+        let (status, error) = self.error();
+        (status, axum::Json(serde_json::json!({ "error": error }))).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_session_is_told_apart_from_missing_one() {
+        let expired = ServerError::from(ServerExceptionCode::SessionExpired);
+        assert_eq!(expired.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(expired.code(), "session_expired");
+
+        let unauthorized = ServerError::from(ServerExceptionCode::Unauthorized);
+        assert_eq!(unauthorized.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(unauthorized.code(), "unauthorized");
+    }
+}
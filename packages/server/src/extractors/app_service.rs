@@ -1,9 +1,13 @@
 use std::ops::Deref;
 
-use app::App;
+use app::{App, services::data_scope::DataScopedService};
 use axum::extract::FromRequestParts;
 use http::request::Parts;
 
+use crate::{error::ServerError, extractors::principal::Principal};
+
+/// A service for the request. Services declared `data_scoped` are restricted to the rows the
+/// caller may see, so extracting them requires a signed in user or a signed application.
 #[derive(Debug)]
 pub struct AppService<T>(T);
 
@@ -18,13 +22,21 @@ impl<T> Deref for AppService<T> {
 impl<S, T> FromRequestParts<S> for AppService<T>
 where
     S: Send + Sync,
-    T: From<App>,
+    T: From<App> + DataScopedService,
 {
-    type Rejection = (http::StatusCode, &'static str);
+    type Rejection = ServerError;
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
         let app = parts.extensions.get::<App>().unwrap();
+        let service = T::from(app.to_owned());
+        if !T::DATA_SCOPED {
+            return Ok(AppService(service));
+        }
 
-        Ok(AppService(app.to_owned().into()))
+        // Applications see every row, their operation permissions are all they are limited by
+        match Principal::from_request_parts(parts, state).await? {
+            Principal::User(session) => Ok(AppService(service.scope_to(&session.payload).await?)),
+            Principal::Application(_) => Ok(AppService(service)),
+        }
     }
 }
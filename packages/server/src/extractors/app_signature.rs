@@ -0,0 +1,107 @@
+use app::{
+    App,
+    models::application::Application,
+    services::application::{ApplicationService, verify_signature::SignedRequest},
+};
+use axum::extract::{FromRequestParts, OriginalUri};
+use http::{HeaderMap, request::Parts};
+use shared::utils::hash_sha256;
+
+use crate::{
+    error::{ServerError, ServerExceptionCode},
+    middlewares::body_digest::BodyDigest,
+    result::ServerResult,
+};
+
+pub const APP_ID_HEADER: &str = "x-app-id";
+pub const TIMESTAMP_HEADER: &str = "x-timestamp";
+pub const NONCE_HEADER: &str = "x-nonce";
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// An application calling on its own behalf, authenticated by a request signature. It holds the
+/// permissions in its scopes, whoever registered it. Kept in the request extensions once
+/// extracted, as the nonce of a request can only be checked once.
+#[derive(Debug, Clone)]
+pub struct AppSignature {
+    pub application: Application,
+}
+
+impl AppSignature {
+    pub fn has_permission(&self, permission_code: impl ToString) -> bool {
+        let permission_code = permission_code.to_string();
+        self.application.scopes.contains(&permission_code)
+    }
+
+    #[allow(unused)]
+    pub fn assert_has_permission(&self, permission_code: impl ToString) -> ServerResult<()> {
+        if !self.has_permission(permission_code) {
+            return Err(ServerExceptionCode::Forbidden.into());
+        }
+
+        Ok(())
+    }
+
+    /// Whether the request carries a signature at all.
+    pub fn is_signed(headers: &HeaderMap) -> bool {
+        headers.contains_key(SIGNATURE_HEADER)
+    }
+}
+
+impl<S> FromRequestParts<S> for AppSignature
+where
+    S: Send + Sync,
+{
+    type Rejection = ServerError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(signature) = parts.extensions.get::<AppSignature>() {
+            return Ok(signature.clone());
+        }
+
+        let header = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|x| x.to_str().ok())
+                .map(str::to_string)
+        };
+        let (Some(app_id), Some(timestamp), Some(nonce), Some(signature)) = (
+            header(APP_ID_HEADER).and_then(|x| x.parse().ok()),
+            header(TIMESTAMP_HEADER).and_then(|x| x.parse().ok()),
+            header(NONCE_HEADER),
+            header(SIGNATURE_HEADER),
+        ) else {
+            return Err(ServerExceptionCode::Unauthorized.into());
+        };
+
+        // Nested routers see the path with their prefix stripped, the client signed all of it
+        let uri = parts
+            .extensions
+            .get::<OriginalUri>()
+            .map(|x| x.0.clone())
+            .unwrap_or_else(|| parts.uri.clone());
+        let body_digest = match parts.extensions.get::<BodyDigest>() {
+            Some(BodyDigest(digest)) => digest.clone(),
+            None => hash_sha256(&[]),
+        };
+
+        let app = parts.extensions.get::<App>().unwrap().clone();
+        let application = ApplicationService::new(app)
+            .verify_signed_request(SignedRequest {
+                app_id,
+                method: parts.method.to_string(),
+                path: uri.path().to_string(),
+                query: uri.query().map(str::to_string),
+                body_digest,
+                timestamp,
+                nonce,
+                signature,
+            })
+            .await?;
+
+        let signature = AppSignature { application };
+        parts.extensions.insert(signature.clone());
+
+        Ok(signature)
+    }
+}
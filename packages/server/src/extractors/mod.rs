@@ -1,3 +1,5 @@
 pub mod app_service;
+pub mod app_signature;
 pub mod helper;
+pub mod principal;
 pub mod session;
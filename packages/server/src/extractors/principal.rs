@@ -0,0 +1,58 @@
+use axum::extract::FromRequestParts;
+use http::request::Parts;
+
+use crate::{error::ServerError, result::ServerResult};
+
+use super::{app_signature::AppSignature, session::Session};
+
+/// Whoever is calling: a signed in user, or an application signing its requests. Signed requests
+/// are always taken as the application, even if they also carry a session.
+#[derive(Debug)]
+pub enum Principal {
+    User(Session),
+    Application(AppSignature),
+}
+
+impl Principal {
+    #[allow(unused)]
+    pub fn has_permission(&self, permission_code: impl ToString) -> bool {
+        match self {
+            Self::User(session) => session.has_permission(permission_code),
+            Self::Application(signature) => signature.has_permission(permission_code),
+        }
+    }
+
+    #[allow(unused)]
+    pub fn assert_has_permission(&self, permission_code: impl ToString) -> ServerResult<()> {
+        match self {
+            Self::User(session) => session.assert_has_permission(permission_code),
+            Self::Application(signature) => signature.assert_has_permission(permission_code),
+        }
+    }
+
+    pub fn assert_not_impersonating(&self) -> ServerResult<()> {
+        match self {
+            Self::User(session) => session.assert_not_impersonating(),
+            Self::Application(_) => Ok(()),
+        }
+    }
+}
+
+impl<S> FromRequestParts<S> for Principal
+where
+    S: Send + Sync,
+{
+    type Rejection = ServerError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if AppSignature::is_signed(&parts.headers) {
+            AppSignature::from_request_parts(parts, state)
+                .await
+                .map(Self::Application)
+        } else {
+            Session::from_request_parts(parts, state)
+                .await
+                .map(Self::User)
+        }
+    }
+}
@@ -1,18 +1,35 @@
-use std::str::FromStr;
+use std::{net::SocketAddr, ops::Deref, str::FromStr};
 
-use app::{App, models::auth_token::SessionTokenPayload, services::auth_token::AuthTokenService};
-use axum::extract::FromRequestParts;
-use axum_extra::extract::CookieJar;
+use app::{
+    App,
+    models::auth_token::{AuthTokenKind, SessionTokenPayload},
+    services::{auth::AuthService, auth_token::AuthTokenService},
+};
+use axum::extract::{ConnectInfo, FromRequestParts, OptionalFromRequestParts};
+use axum_extra::{
+    extract::{CookieJar, cookie::Cookie},
+    headers::{Authorization, HeaderMapExt, authorization::Bearer},
+};
+use chrono::Utc;
 use http::request::Parts;
+use time::Duration;
 use uuid::Uuid;
 
-use crate::{error::ServerExceptionCode, result::ServerResult};
+use crate::{
+    error::{ServerError, ServerExceptionCode},
+    middlewares::session_renewal::SessionRenewal,
+    result::ServerResult,
+};
 
 pub const SESSION_ID_KEY: &str = "id";
 
-#[derive(Debug)]
+/// Kept in the request extensions once extracted, so routes guarded by permissions don't
+/// authenticate twice.
+#[derive(Debug, Clone)]
 pub struct Session {
     pub session_id: Uuid,
+    /// `Session` when the user signed in, or the kind of token acting for them.
+    pub kind: AuthTokenKind,
     pub payload: SessionTokenPayload,
 }
 
@@ -79,37 +96,200 @@ impl Session {
         }
         Ok(())
     }
+
+    /// Whether the user signed in themself, rather than handing a personal access or OAuth
+    /// access token to a client.
+    pub fn is_interactive(&self) -> bool {
+        self.kind == AuthTokenKind::Session
+    }
+
+    pub fn is_impersonating(&self) -> bool {
+        self.payload.impersonator_id.is_some()
+    }
+
+    /// Sensitive operations, like changing the password, are left to the user themself.
+    pub fn assert_not_impersonating(&self) -> ServerResult<()> {
+        if self.is_impersonating() {
+            return Err(ServerExceptionCode::Forbidden.into());
+        }
+
+        Ok(())
+    }
 }
 
 impl<S> FromRequestParts<S> for Session
 where
     S: Send + Sync,
 {
-    type Rejection = (http::StatusCode, &'static str);
+    type Rejection = ServerError;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
-        let jar = CookieJar::from_request_parts(parts, state).await.unwrap();
-        let Some(cookie) = jar.get(SESSION_ID_KEY) else {
-            return Err((http::StatusCode::UNAUTHORIZED, "Unauthorized"));
+        if let Some(session) = parts.extensions.get::<Session>() {
+            return Ok(session.clone());
+        }
+
+        let app = parts.extensions.get::<App>().unwrap().clone();
+        let auth_token_service = AuthTokenService::new(app.clone());
+
+        // Bearer tokens take precedence, the session cookie is the fallback for browsers
+        let bearer = parts.headers.typed_get::<Authorization<Bearer>>();
+        let auth_token = match &bearer {
+            Some(Authorization(bearer)) => {
+                auth_token_service
+                    .query_auth_token_by_jwt(bearer.token())
+                    .await?
+            }
+            None => {
+                let jar = CookieJar::from_request_parts(parts, state).await.unwrap();
+                let Some(cookie) = jar.get(SESSION_ID_KEY) else {
+                    return Err(ServerExceptionCode::Unauthorized.into());
+                };
+
+                let Ok(session_id) = Uuid::from_str(cookie.value()) else {
+                    return Err(ServerExceptionCode::Unauthorized.into());
+                };
+
+                auth_token_service
+                    .query_auth_token_by_id(session_id)
+                    .await?
+            }
         };
 
-        let Ok(session_id) = Uuid::from_str(cookie.value()) else {
-            return Err((http::StatusCode::UNAUTHORIZED, "Unauthorized"));
+        // Personal access and OAuth access tokens are only ever sent as bearer tokens
+        let accepted = match auth_token.as_ref().map(|x| x.kind) {
+            Some(AuthTokenKind::Session) => true,
+            Some(AuthTokenKind::PersonalAccess | AuthTokenKind::OAuthAccess) => bearer.is_some(),
+            _ => false,
         };
+        let Some(auth_token) = auth_token.filter(|_| accepted) else {
+            return Err(ServerExceptionCode::Unauthorized.into());
+        };
+        let session_id = auth_token.id;
 
-        let app = parts.extensions.get::<App>().unwrap();
-        let auth_token_service = AuthTokenService::new(app.clone());
+        let session_config = &app.config.session;
+        let now = Utc::now();
+        if auth_token.is_expired(session_config, now) {
+            auth_token_service
+                .delete_auth_token_by_id(session_id)
+                .await?;
+            return Err(ServerExceptionCode::SessionExpired.into());
+        }
+
+        if auth_token.kind == AuthTokenKind::Session
+            && auth_token.should_renew_session(session_config, now)
+        {
+            let expired_at = auth_token_service.renew_session_token(&auth_token).await?;
+            // Bearer clients keep their token, only the cookie needs to be reissued
+            if bearer.is_none()
+                && let Some(renewal) = parts.extensions.get::<SessionRenewal>()
+            {
+                renewal.set(session_cookie(session_id, expired_at - now));
+            }
+        }
 
-        let Ok(Some(auth_token)) = auth_token_service.query_auth_token_by_id(session_id).await
+        let ip = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip().to_string());
+        auth_token_service.touch_auth_token(&auth_token, ip).await?;
+
+        let Some(payload) = AuthService::new(app.clone())
+            .query_session_payload(&auth_token)
+            .await?
         else {
-            return Err((http::StatusCode::UNAUTHORIZED, "Unauthorized"));
+            return Err(ServerExceptionCode::Unauthorized.into());
         };
 
-        let payload = serde_json::from_str::<SessionTokenPayload>(&auth_token.payload).unwrap();
-
-        Ok(Session {
+        let session = Session {
             session_id,
+            kind: auth_token.kind,
             payload,
-        })
+        };
+        parts.extensions.insert(session.clone());
+
+        Ok(session)
+    }
+}
+
+/// `None` if the request carries no credentials at all; credentials that are sent must be valid.
+impl<S> OptionalFromRequestParts<S> for Session
+where
+    S: Send + Sync,
+{
+    type Rejection = ServerError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        let has_bearer = parts.headers.typed_get::<Authorization<Bearer>>().is_some();
+        let jar = CookieJar::from_request_parts(parts, state).await.unwrap();
+        if !has_bearer && jar.get(SESSION_ID_KEY).is_none() {
+            return Ok(None);
+        }
+
+        <Self as FromRequestParts<S>>::from_request_parts(parts, state)
+            .await
+            .map(Some)
     }
 }
+
+/// A session the user signed in to themself. Managing the account is left to these: a token
+/// handed to a client, whatever its scope, must not be able to take the account over.
+#[derive(Debug, Clone)]
+pub struct InteractiveSession(pub Session);
+
+impl Deref for InteractiveSession {
+    type Target = Session;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<S> FromRequestParts<S> for InteractiveSession
+where
+    S: Send + Sync,
+{
+    type Rejection = ServerError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let session = <Session as FromRequestParts<S>>::from_request_parts(parts, state).await?;
+        if !session.is_interactive() {
+            return Err(ServerExceptionCode::Forbidden.into());
+        }
+
+        Ok(Self(session))
+    }
+}
+
+/// `None` if the request carries no credentials at all, like `Option<Session>`.
+impl<S> OptionalFromRequestParts<S> for InteractiveSession
+where
+    S: Send + Sync,
+{
+    type Rejection = ServerError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        let session =
+            <Session as OptionalFromRequestParts<S>>::from_request_parts(parts, state).await?;
+        match session {
+            Some(session) if !session.is_interactive() => {
+                Err(ServerExceptionCode::Forbidden.into())
+            }
+            session => Ok(session.map(Self)),
+        }
+    }
+}
+
+/// Build the cookie carrying the session id, kept alive for `max_age`.
+pub fn session_cookie(session_id: Uuid, max_age: chrono::Duration) -> Cookie<'static> {
+    let mut cookie = Cookie::new(SESSION_ID_KEY, session_id.to_string());
+    cookie.set_path("/");
+    cookie.set_http_only(true);
+    cookie.set_max_age(Duration::seconds(max_age.num_seconds()));
+    cookie
+}
@@ -0,0 +1,154 @@
+use std::{collections::HashSet, path::Path};
+
+use chrono::{DateTime, Utc};
+use rsa::{
+    RsaPrivateKey, RsaPublicKey,
+    pkcs1::{DecodeRsaPrivateKey, EncodeRsaPrivateKey},
+    pkcs8::{DecodePublicKey, EncodePublicKey, LineEnding},
+};
+use serde::{Deserialize, Serialize};
+use shared::utils;
+
+use crate::{error::ServerExceptionCode, result::ServerResult};
+
+/// Size of the key pairs generated for missing key files.
+const GENERATED_KEY_BITS: usize = 2048;
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RsaKeyConfig {
+    /// Sent back by clients along with the ciphertext, to pick the key to decrypt with.
+    pub kid: String,
+    /// PKCS#1 PEM, generated together with the public key if the file doesn't exist.
+    pub private_key: String,
+    /// SPKI PEM.
+    pub public_key: String,
+    /// From when the key is handed out and accepted, right away if unset.
+    pub not_before: Option<DateTime<Utc>>,
+    /// Until when the key is handed out and accepted, forever if unset.
+    pub not_after: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub struct RsaKey {
+    pub kid: String,
+    pub private_key: RsaPrivateKey,
+    pub public_key: RsaPublicKey,
+    pub not_before: Option<DateTime<Utc>>,
+    pub not_after: Option<DateTime<Utc>>,
+}
+
+impl RsaKey {
+    pub fn is_valid_at(&self, time: DateTime<Utc>) -> bool {
+        self.not_before.is_none_or(|x| x <= time) && self.not_after.is_none_or(|x| time < x)
+    }
+}
+
+/// The keys clients encrypt passwords with. A new key is rolled out by adding it while the
+/// previous one is still valid, so clients that fetched the previous one keep working until it
+/// expires.
+#[derive(Debug)]
+pub struct RsaKeyring {
+    keys: Vec<RsaKey>,
+}
+
+impl RsaKeyring {
+    pub async fn load(configs: &[RsaKeyConfig]) -> ServerResult<Self> {
+        let mut kids = HashSet::new();
+        if let Some(config) = configs.iter().find(|x| !kids.insert(&x.kid)) {
+            return Err(anyhow::anyhow!("Duplicate rsa key id {}", config.kid).into());
+        }
+
+        let mut keys = Vec::with_capacity(configs.len());
+        for config in configs {
+            keys.push(Self::load_key(config).await?);
+        }
+        if !keys.iter().any(|x| x.is_valid_at(Utc::now())) {
+            return Err("No rsa key is currently valid".into());
+        }
+
+        Ok(Self { keys })
+    }
+
+    /// Read the key pair of the config, or generate it on first boot when neither file exists.
+    async fn load_key(config: &RsaKeyConfig) -> ServerResult<RsaKey> {
+        let private_key_path = Path::new(&config.private_key);
+        let public_key_path = Path::new(&config.public_key);
+
+        let (private_key, public_key) = if !private_key_path.exists() && !public_key_path.exists() {
+            println!("Generating rsa key {}...", config.kid);
+            let private_key = utils::generate_rsa_key(GENERATED_KEY_BITS)?;
+            let public_key = RsaPublicKey::from(&private_key);
+
+            let private_key_pem = private_key.to_pkcs1_pem(LineEnding::LF).unwrap();
+            let public_key_pem = public_key.to_public_key_pem(LineEnding::LF).unwrap();
+            for path in [private_key_path, public_key_path] {
+                if let Some(dir) = path.parent() {
+                    tokio::fs::create_dir_all(dir).await?;
+                }
+            }
+            tokio::fs::write(private_key_path, private_key_pem.as_bytes()).await?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                let permissions = std::fs::Permissions::from_mode(0o600);
+                tokio::fs::set_permissions(private_key_path, permissions).await?;
+            }
+            tokio::fs::write(public_key_path, public_key_pem.as_bytes()).await?;
+
+            (private_key, public_key)
+        } else {
+            let private_key_pem = tokio::fs::read_to_string(private_key_path).await?;
+            let private_key = RsaPrivateKey::from_pkcs1_pem(&private_key_pem)
+                .map_err(|_| anyhow::anyhow!("Invalid private key {}", config.private_key))?;
+            let public_key_pem = tokio::fs::read_to_string(public_key_path).await?;
+            let public_key = RsaPublicKey::from_public_key_pem(&public_key_pem)
+                .map_err(|_| anyhow::anyhow!("Invalid public key {}", config.public_key))?;
+
+            (private_key, public_key)
+        };
+
+        Ok(RsaKey {
+            kid: config.kid.clone(),
+            private_key,
+            public_key,
+            not_before: config.not_before,
+            not_after: config.not_after,
+        })
+    }
+
+    /// Keys valid right now, the one clients should encrypt with first. Later keys in the
+    /// settings take precedence.
+    pub fn valid_keys(&self) -> impl Iterator<Item = &RsaKey> {
+        let now = Utc::now();
+        self.keys.iter().rev().filter(move |x| x.is_valid_at(now))
+    }
+
+    pub fn current_key(&self) -> ServerResult<&RsaKey> {
+        self.valid_keys()
+            .next()
+            .ok_or_else(|| ServerExceptionCode::InternalServerError.into())
+    }
+
+    /// Decrypt a base64 ciphertext prefixed with the id of the key, as in `{kid}:{ciphertext}`.
+    /// Ciphertexts without a key id, from clients that don't know about them, are tried with
+    /// every valid key.
+    pub fn decrypt(&self, ciphertext: &str) -> ServerResult<String> {
+        let Some((kid, ciphertext)) = ciphertext.split_once(':') else {
+            let ciphertext = utils::decode_base64(ciphertext)?;
+            let plaintext = self
+                .valid_keys()
+                .find_map(|x| utils::decrypt_rsa_to_utf8(&x.private_key, &ciphertext).ok());
+
+            return plaintext.ok_or_else(|| "Failed to decrypt".into());
+        };
+
+        let key = self.valid_keys().find(|x| x.kid == kid);
+        let Some(key) = key else {
+            return Err(ServerExceptionCode::UnknownRsaKey.into());
+        };
+        let ciphertext = utils::decode_base64(ciphertext)?;
+        let plaintext = utils::decrypt_rsa_to_utf8(&key.private_key, &ciphertext)?;
+
+        Ok(plaintext)
+    }
+}
@@ -1,8 +1,12 @@
-use app::App;
-use axum::{Extension, Router, routing::get};
+use std::{net::SocketAddr, sync::Arc};
+
+use api_router::SecurityAddon;
+use app::{App, config::AppConfig};
+use axum::{Extension, Router, middleware, routing::get};
 use info::Info;
+use keyring::RsaKeyring;
+use middlewares::ip_throttle::IpThrottle;
 use result::ServerResult;
-use rsa::{RsaPrivateKey, RsaPublicKey, pkcs1::DecodeRsaPrivateKey, pkcs8::DecodePublicKey};
 use sea_orm::{Database, DatabaseConnection};
 use settings::Settings;
 use tower_http::trace::TraceLayer;
@@ -15,6 +19,7 @@ mod dto;
 mod error;
 mod extractors;
 mod info;
+mod keyring;
 mod middlewares;
 mod response;
 mod result;
@@ -50,20 +55,17 @@ async fn main() -> ServerResult<()> {
         .with_env_filter(EnvFilter::from_default_env())
         .init();
 
-    // Init secret key
-    println!("Loading private key...");
-    let priv_key_pem = tokio::fs::read_to_string(&setting.private_key)
-        .await
-        .expect("Error: Failed to read private key");
-    let priv_key =
-        RsaPrivateKey::from_pkcs1_pem(&priv_key_pem).expect("Error: Invalid private key");
+    // Anyone knowing the secret can mint tokens, so release builds refuse a guessable one
+    if let Some(weakness) = setting.jwt.secret_weakness() {
+        if !cfg!(debug_assertions) {
+            return Err(anyhow::anyhow!("{weakness}, set a random secret to start").into());
+        }
+        tracing::warn!("{weakness}, release builds refuse to start with it");
+    }
 
-    println!("Loading public key...");
-    let pub_key_pem = tokio::fs::read_to_string(&setting.public_key)
-        .await
-        .expect("Error: Failed to read public key");
-    let pub_key =
-        RsaPublicKey::from_public_key_pem(&pub_key_pem).expect("Error: Invalid public key");
+    // Init rsa keys
+    println!("Loading rsa keys...");
+    let rsa_keyring = Arc::new(RsaKeyring::load(&setting.rsa_key_configs()).await?);
 
     // Init uploader folder
     println!("Init upload directory");
@@ -83,11 +85,23 @@ async fn main() -> ServerResult<()> {
 
     // Init app
     println!("Apply migrations...");
-    let app = App::init(db_conn.clone(), upload_dir.to_path_buf()).await?;
+    let app_config = AppConfig {
+        session: setting.session.clone(),
+        jwt: setting.jwt.clone(),
+        login: setting.login.clone(),
+        mail: setting.mail.clone(),
+        oauth: setting.oauth.clone(),
+        authorization_server: setting.authorization_server.clone(),
+        app_signature: setting.app_signature.clone(),
+        password_policy: setting.password_policy.clone(),
+        password_hash: setting.password_hash.clone(),
+    };
+    let app = App::init(db_conn.clone(), upload_dir.to_path_buf(), app_config).await?;
 
     #[derive(OpenApi)]
     #[openapi(
         info(description = "OpenApi Docs"),
+        modifiers(&SecurityAddon),
         nest(
             (path = "/system", api = routes::system::router::ApiDoc, tags = ["System"]),
             (path = "/auth", api = routes::auth::router::ApiDoc, tags = ["Auth"]),
@@ -98,6 +112,9 @@ async fn main() -> ServerResult<()> {
             (path = "/roles", api = routes::role::router::ApiDoc, tags = ["Role"]),
             (path = "/permissions", api = routes::permission::router::ApiDoc, tags = ["Permission"]),
             (path = "/uploads", api = routes::upload::router::ApiDoc, tags = ["Upload"]),
+            (path = "/applications", api = routes::application::router::ApiDoc, tags = ["Application"]),
+            (path = "/invitations", api = routes::invitation::router::ApiDoc, tags = ["Invitation"]),
+            (path = "/oauth", api = routes::oauth::router::ApiDoc, tags = ["OAuth"]),
         ),
     )]
     struct ApiDoc;
@@ -118,9 +135,18 @@ async fn main() -> ServerResult<()> {
                 )
                 .route("/openapi.json", get(|| async { axum::Json(openapi_json) }))
                 .merge(Scalar::with_url("/docs", openapi.clone()))
-                .nest("/auth", routes::auth::router::init())
+                .nest(
+                    "/auth",
+                    routes::auth::router::init().layer(middleware::from_fn_with_state(
+                        IpThrottle::new(setting.throttle.clone()),
+                        middlewares::ip_throttle::throttle_by_ip,
+                    )),
+                )
+                .nest("/applications", routes::application::router::init())
                 .nest("/department", routes::department::router::init())
                 .nest("/groups", routes::user_group::router::init())
+                .nest("/invitations", routes::invitation::router::init())
+                .nest("/oauth", routes::oauth::router::init())
                 .nest("/permissions", routes::permission::router::init())
                 .nest("/roles", routes::role::router::init())
                 .nest("/session", routes::session::router::init())
@@ -128,10 +154,15 @@ async fn main() -> ServerResult<()> {
                 .nest("/users", routes::user::router::init())
                 .nest("/uploads", routes::upload::router::init()),
         )
+        .layer(middleware::from_fn(
+            middlewares::session_renewal::reissue_session_cookie,
+        ))
+        .layer(middleware::from_fn(
+            middlewares::body_digest::digest_signed_body,
+        ))
         .layer(Extension(app))
         .layer(Extension(db_conn))
-        .layer(Extension(priv_key))
-        .layer(Extension(pub_key))
+        .layer(Extension(rsa_keyring))
         .layer(TraceLayer::new_for_http());
 
     let server_port = setting.server_port;
@@ -143,9 +174,12 @@ async fn main() -> ServerResult<()> {
     println!("{}", Info);
 
     // Start server
-    axum::serve(listener, router)
-        .await
-        .map_err(|err| anyhow::anyhow!(err))?;
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .map_err(|err| anyhow::anyhow!(err))?;
 
     Ok(())
 }
@@ -0,0 +1,34 @@
+use axum::{
+    body::{Body, to_bytes},
+    extract::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::StatusCode;
+use shared::utils::hash_sha256;
+
+use crate::extractors::app_signature::SIGNATURE_HEADER;
+
+/// Largest body a signed request may carry, as it is read into memory to be digested.
+const MAX_SIGNED_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Lowercase hex SHA-256 of the body of a signed request.
+#[derive(Clone, Debug)]
+pub struct BodyDigest(pub String);
+
+/// Digest the body of signed requests for the `AppSignature` extractor, which only sees the
+/// request parts. The body is buffered and handed on unchanged.
+pub async fn digest_signed_body(request: Request, next: Next) -> Response {
+    if !request.headers().contains_key(SIGNATURE_HEADER) {
+        return next.run(request).await;
+    }
+
+    let (mut parts, body) = request.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_SIGNED_BODY_SIZE).await else {
+        return StatusCode::PAYLOAD_TOO_LARGE.into_response();
+    };
+    parts.extensions.insert(BodyDigest(hash_sha256(&bytes)));
+
+    next.run(Request::from_parts(parts, Body::from(bytes)))
+        .await
+}
@@ -0,0 +1,102 @@
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ServerError, ServerExceptionCode};
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct ThrottleConfig {
+    /// Failed authentication attempts accepted from one IP within the window.
+    pub max_failures: u32,
+    /// Length in seconds of the window failures are counted in.
+    pub window: u64,
+}
+
+impl Default for ThrottleConfig {
+    fn default() -> Self {
+        Self {
+            max_failures: 20,
+            window: 5 * 60,
+        }
+    }
+}
+
+/// Failed authentication attempts per client IP, counted in fixed windows.
+#[derive(Clone)]
+pub struct IpThrottle {
+    config: ThrottleConfig,
+    failures: Arc<Mutex<HashMap<IpAddr, (Instant, u32)>>>,
+}
+
+impl IpThrottle {
+    pub fn new(config: ThrottleConfig) -> Self {
+        Self {
+            config,
+            failures: Default::default(),
+        }
+    }
+
+    fn window(&self) -> Duration {
+        Duration::from_secs(self.config.window)
+    }
+
+    fn is_throttled(&self, ip: IpAddr, now: Instant) -> bool {
+        let failures = self.failures.lock().unwrap();
+        failures.get(&ip).is_some_and(|(window_start, count)| {
+            now - *window_start < self.window() && *count >= self.config.max_failures
+        })
+    }
+
+    fn record_failure(&self, ip: IpAddr, now: Instant) {
+        let window = self.window();
+        let mut failures = self.failures.lock().unwrap();
+        failures.retain(|_, (window_start, _)| now - *window_start < window);
+
+        let (_, count) = failures.entry(ip).or_insert((now, 0));
+        *count += 1;
+    }
+}
+
+/// Reject clients whose requests keep failing authentication. Needs the server to be started
+/// with `ConnectInfo<SocketAddr>`, requests without it are let through.
+pub async fn throttle_by_ip(
+    State(throttle): State<IpThrottle>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(ConnectInfo(addr)) = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .cloned()
+    else {
+        return next.run(request).await;
+    };
+    let ip = addr.ip();
+
+    if throttle.is_throttled(ip, Instant::now()) {
+        return ServerError::from(ServerExceptionCode::TooManyRequests).into_response();
+    }
+
+    let response = next.run(request).await;
+
+    if matches!(
+        response.status(),
+        StatusCode::UNAUTHORIZED | StatusCode::LOCKED
+    ) {
+        throttle.record_failure(ip, Instant::now());
+    }
+
+    response
+}
@@ -1 +1,3 @@
-
+pub mod body_digest;
+pub mod ip_throttle;
+pub mod session_renewal;
@@ -0,0 +1,36 @@
+use std::sync::{Arc, Mutex};
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use axum_extra::extract::cookie::Cookie;
+use http::{HeaderValue, header::SET_COOKIE};
+
+/// Slot through which the `Session` extractor hands a renewed session cookie back to the
+/// response, since extractors have no access to the outgoing response themselves.
+#[derive(Clone, Default)]
+pub struct SessionRenewal(Arc<Mutex<Option<Cookie<'static>>>>);
+
+impl SessionRenewal {
+    pub fn set(&self, cookie: Cookie<'static>) {
+        *self.0.lock().unwrap() = Some(cookie);
+    }
+
+    fn take(&self) -> Option<Cookie<'static>> {
+        self.0.lock().unwrap().take()
+    }
+}
+
+pub async fn reissue_session_cookie(mut request: Request, next: Next) -> Response {
+    let renewal = SessionRenewal::default();
+    request.extensions_mut().insert(renewal.clone());
+
+    let mut response = next.run(request).await;
+
+    if let Some(cookie) = renewal.take() {
+        response.headers_mut().append(
+            SET_COOKIE,
+            HeaderValue::from_str(&cookie.to_string()).unwrap(),
+        );
+    }
+
+    response
+}
@@ -8,7 +8,7 @@ use bytes::Bytes;
 use futures_core::TryStream;
 use http::{
     HeaderMap, HeaderValue, StatusCode,
-    header::{IntoHeaderName, SET_COOKIE},
+    header::{IntoHeaderName, LOCATION, SET_COOKIE},
 };
 use serde::Serialize;
 use tokio::io::AsyncRead;
@@ -38,11 +38,23 @@ pub type ResponseJsonNull = ResponseJson<Null>;
 pub struct ResponseErrorJson {
     code: String,
     message: String,
+    /// Machine readable specifics of the error, for those that have any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<serde_json::Value>,
 }
 
 impl ResponseErrorJson {
     pub fn new(code: String, message: String) -> Self {
-        Self { code, message }
+        Self {
+            code,
+            message,
+            details: None,
+        }
+    }
+
+    pub fn details(mut self, details: Option<serde_json::Value>) -> Self {
+        self.details = details;
+        self
     }
 }
 
@@ -78,6 +90,16 @@ impl ApiResponse {
         Self::json(Null)
     }
 
+    /// `302 Found` to `location`.
+    pub fn redirect(location: &str) -> Self {
+        let mut response = Self {
+            status: StatusCode::FOUND,
+            ..Default::default()
+        };
+        response.insert_header(LOCATION, HeaderValue::from_str(location).unwrap());
+        response
+    }
+
     #[allow(dead_code)]
     pub fn insert_header<K: IntoHeaderName, V: Into<HeaderValue>>(
         &mut self,
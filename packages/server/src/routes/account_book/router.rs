@@ -4,12 +4,10 @@ use app::{
 };
 use axum::{Extension, Json, extract::Query};
 use sea_orm::DatabaseConnection;
-use shared::enums::OperationPermission;
 use utoipa::OpenApi;
 
 use crate::{
     dto::PaginatedQueryDto,
-    extractors::session::Session,
     init_router,
     response::{ApiResponse, Null, PaginatedData, ResponseJson},
     result::ServerResult,
@@ -40,17 +38,15 @@ init_router!(
     get,
     path = "/queryAccountBooksByPage",
     params(PaginatedQueryDto, FilterAccountBooksDto),
+    security(("session" = ["query_account_books"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<PaginatedData<AccountBookDto>>)
     )
 )]
 pub async fn query_account_books_by_page(
-    session: Session,
     Extension(conn): Extension<DatabaseConnection>,
     Query(query): Query<PaginatedQuery<FilterAccountBooksDto>>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryGroups)?;
-
     let account_book_service = AccountBookService::new(conn);
 
     let (account_books, total) = account_book_service
@@ -71,17 +67,15 @@ pub async fn query_account_books_by_page(
     post,
     path = "/createAccountBook",
     request_body = CreateAccountBookDto,
+    security(("session" = ["create_account_book"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<CreateAccountBookResponseDto>)
     )
 )]
 pub async fn create_account_book(
-    session: Session,
     Extension(conn): Extension<DatabaseConnection>,
     Json(params): Json<CreateAccountBookDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::CreateGroup)?;
-
     let account_book_service = AccountBookService::new(conn);
 
     let group_id = account_book_service
@@ -98,17 +92,15 @@ pub async fn create_account_book(
     delete,
     path = "/deleteGroups",
     request_body = DeleteGroupsRequestDto,
+    security(("session" = ["delete_account_book"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn delete_account_books(
-    session: Session,
     Extension(conn): Extension<DatabaseConnection>,
     Json(params): Json<DeleteGroupsRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::DeleteGroup)?;
-
     let account_book_service = AccountBookService::new(conn);
     account_book_service
         .delete_account_books(params.into())
@@ -123,17 +115,15 @@ pub async fn delete_account_books(
     patch,
     path = "/updateGroup",
     request_body = UpdateGroupRequestDto,
+    security(("session" = ["update_account_book"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn update_account_book(
-    session: Session,
     Extension(conn): Extension<DatabaseConnection>,
     Json(params): Json<UpdateGroupRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::UpdateGroup)?;
-
     let account_book_service = AccountBookService::new(conn);
     account_book_service
         .update_account_book(params.into())
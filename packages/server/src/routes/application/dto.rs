@@ -0,0 +1,163 @@
+use app::{
+    models::application::Application,
+    services::application::{
+        create_application::{ApplicationCredentials, CreateApplicationParams},
+        delete_applications::DeleteApplicationsParams,
+        query_applications::FilterApplicationsParams,
+        update_application::UpdateApplicationParams,
+    },
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ApplicationDto {
+    pub id: Uuid,
+    pub app_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub redirect_uris: Vec<String>,
+    pub scopes: Vec<String>,
+    pub confidential: bool,
+    pub owner_id: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Application> for ApplicationDto {
+    fn from(value: Application) -> Self {
+        Self {
+            id: value.id,
+            app_id: value.app_id,
+            name: value.name,
+            description: value.description,
+            redirect_uris: value.redirect_uris,
+            scopes: value.scopes,
+            confidential: value.confidential,
+            owner_id: value.owner_id,
+            created_at: value.created_at,
+            updated_at: value.updated_at,
+        }
+    }
+}
+
+/// Application filter params
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ApplicationFilterDto {
+    pub name: Option<String>,
+    pub owner_id: Option<Uuid>,
+}
+
+impl From<ApplicationFilterDto> for FilterApplicationsParams {
+    fn from(value: ApplicationFilterDto) -> Self {
+        Self {
+            name: value.name,
+            owner_id: value.owner_id,
+        }
+    }
+}
+
+/// Application create params
+#[derive(Debug, ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct CreateApplicationRequestDto {
+    pub name: String,
+    pub description: Option<String>,
+    pub redirect_uris: Vec<String>,
+    /// Permission codes the application may ask for
+    pub scopes: Vec<String>,
+    /// Public applications (SPA, mobile) have no secret and rely on PKCE alone
+    #[serde(default = "default_confidential")]
+    #[schema(default = true)]
+    pub confidential: bool,
+    /// User the application acts as in the client credentials grant
+    pub owner_id: Option<Uuid>,
+}
+
+fn default_confidential() -> bool {
+    true
+}
+
+impl From<CreateApplicationRequestDto> for CreateApplicationParams {
+    fn from(value: CreateApplicationRequestDto) -> Self {
+        Self {
+            name: value.name,
+            description: value.description,
+            redirect_uris: value.redirect_uris,
+            scopes: value.scopes,
+            confidential: value.confidential,
+            owner_id: value.owner_id,
+        }
+    }
+}
+
+/// Credentials of an application, the secret and signing key are not shown again
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ApplicationCredentialsDto {
+    pub id: Uuid,
+    pub app_id: Uuid,
+    pub app_secret: Option<String>,
+    /// HMAC-SHA256 key of signed requests, only confidential applications have one
+    pub signing_key: Option<String>,
+}
+
+impl From<ApplicationCredentials> for ApplicationCredentialsDto {
+    fn from(value: ApplicationCredentials) -> Self {
+        Self {
+            id: value.id,
+            app_id: value.app_id,
+            app_secret: value.app_secret,
+            signing_key: value.signing_key,
+        }
+    }
+}
+
+/// Application update params
+#[derive(Debug, ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct UpdateApplicationRequestDto {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub redirect_uris: Option<Vec<String>>,
+    pub scopes: Option<Vec<String>>,
+    pub owner_id: Option<Uuid>,
+}
+
+impl From<UpdateApplicationRequestDto> for UpdateApplicationParams {
+    fn from(value: UpdateApplicationRequestDto) -> Self {
+        Self {
+            id: value.id,
+            name: value.name,
+            description: value.description,
+            redirect_uris: value.redirect_uris,
+            scopes: value.scopes,
+            owner_id: value.owner_id,
+        }
+    }
+}
+
+/// Application id list
+#[derive(Debug, ToSchema, Deserialize)]
+pub struct DeleteApplicationsRequestDto(Vec<Uuid>);
+
+impl From<DeleteApplicationsRequestDto> for DeleteApplicationsParams {
+    fn from(value: DeleteApplicationsRequestDto) -> Self {
+        Self(value.0)
+    }
+}
+
+#[derive(Debug, ToSchema, Deserialize)]
+pub struct ResetApplicationSecretRequestDto {
+    pub id: Uuid,
+}
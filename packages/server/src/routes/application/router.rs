@@ -0,0 +1,156 @@
+use app::services::application::ApplicationService;
+use axum::Json;
+use utoipa::OpenApi;
+
+use crate::{
+    dto::PageableQueryDto,
+    extractors::app_service::AppService,
+    init_router,
+    response::{ApiResponse, PaginatedData, ResponseJson, ResponseJsonNull},
+    result::ServerResult,
+};
+
+use super::dto::{
+    ApplicationCredentialsDto, ApplicationDto, ApplicationFilterDto, CreateApplicationRequestDto,
+    DeleteApplicationsRequestDto, ResetApplicationSecretRequestDto, UpdateApplicationRequestDto,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(
+    query_applications_by_page,
+    create_application,
+    update_application,
+    delete_applications,
+    reset_application_secret
+))]
+pub(crate) struct ApiDoc;
+init_router!(
+    query_applications_by_page,
+    create_application,
+    update_application,
+    delete_applications,
+    reset_application_secret
+);
+
+/// Query applications by page
+#[utoipa::path(
+    operation_id = "queryApplicationsByPage",
+    description = "Query applications by page",
+    post,
+    path = "/queryApplicationsByPage",
+    request_body = PageableQueryDto<ApplicationFilterDto>,
+    security(("session" = ["query_applications"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<PaginatedData<ApplicationDto>>)
+    )
+)]
+pub async fn query_applications_by_page(
+    application_service: AppService<ApplicationService>,
+    Json(params): Json<PageableQueryDto<ApplicationFilterDto>>,
+) -> ServerResult<ApiResponse> {
+    let (records, total) = application_service
+        .query_applications_by_page(params.into())
+        .await?;
+    let records = records
+        .into_iter()
+        .map(ApplicationDto::from)
+        .collect::<Vec<_>>();
+
+    Ok(ApiResponse::json(PaginatedData { records, total }))
+}
+
+/// Create application
+#[utoipa::path(
+    operation_id = "createApplication",
+    description = "Register an application, its secret and signing key are only shown in the response",
+    post,
+    path = "/createApplication",
+    request_body = CreateApplicationRequestDto,
+    security(("session" = ["create_application"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<ApplicationCredentialsDto>)
+    )
+)]
+pub async fn create_application(
+    application_service: AppService<ApplicationService>,
+    Json(params): Json<CreateApplicationRequestDto>,
+) -> ServerResult<ApiResponse> {
+    let credentials = application_service
+        .create_application(params.into())
+        .await?;
+
+    Ok(ApiResponse::json(ApplicationCredentialsDto::from(
+        credentials,
+    )))
+}
+
+/// Update application
+#[utoipa::path(
+    operation_id = "updateApplication",
+    description = "Update application",
+    patch,
+    path = "/updateApplication",
+    request_body = UpdateApplicationRequestDto,
+    security(("session" = ["update_application"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJsonNull)
+    )
+)]
+pub async fn update_application(
+    application_service: AppService<ApplicationService>,
+    Json(params): Json<UpdateApplicationRequestDto>,
+) -> ServerResult<ApiResponse> {
+    application_service
+        .update_application(params.into())
+        .await?;
+
+    Ok(ApiResponse::null())
+}
+
+/// Delete applications
+#[utoipa::path(
+    operation_id = "deleteApplications",
+    description = "Delete applications, revoking every token issued to them",
+    delete,
+    path = "/deleteApplications",
+    request_body = DeleteApplicationsRequestDto,
+    security(("session" = ["delete_application"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJsonNull)
+    )
+)]
+pub async fn delete_applications(
+    application_service: AppService<ApplicationService>,
+    Json(params): Json<DeleteApplicationsRequestDto>,
+) -> ServerResult<ApiResponse> {
+    application_service
+        .delete_applications(params.into())
+        .await?;
+
+    Ok(ApiResponse::null())
+}
+
+/// Reset application secret
+#[utoipa::path(
+    operation_id = "resetApplicationSecret",
+    description = "Replace the secret and signing key of a confidential application, the new ones are only shown in the response",
+    post,
+    path = "/resetApplicationSecret",
+    request_body = ResetApplicationSecretRequestDto,
+    security(("session" = ["update_application"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<ApplicationCredentialsDto>)
+    )
+)]
+pub async fn reset_application_secret(
+    application_service: AppService<ApplicationService>,
+    Json(params): Json<ResetApplicationSecretRequestDto>,
+) -> ServerResult<ApiResponse> {
+    let credentials = application_service
+        .reset_application_secret(params.id)
+        .await?;
+
+    Ok(ApiResponse::json(ApplicationCredentialsDto::from(
+        credentials,
+    )))
+}
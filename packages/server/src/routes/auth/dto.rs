@@ -1,8 +1,19 @@
 use app::services::auth::{
-    assign_permissions::AssignUserPermissionsParams, login::LoginParams,
-    query_permissions::GroupPermissionChainNode, register::RegisterParams,
+    assign_permissions::AssignUserPermissionsParams,
+    change_password::ChangeExpiredPasswordParams,
+    deny_permissions::PermissionDenialsParams,
+    email_login::{EmailCodeLoginParams, EmailLinkLoginParams},
+    impersonate::ImpersonationResult,
+    login::{LoginParams, LoginResult},
+    password_reset::ResetPasswordParams,
+    query_permissions::GroupPermissionChainNode,
+    refresh::RefreshParams,
+    register::{RegisterParams, RegistrationSettings},
+    second_factor::SecondFactorLoginParams,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use shared::enums::{PermissionSubjectKind, RegistrationMode};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
@@ -11,6 +22,11 @@ use uuid::Uuid;
 pub struct RegisterRequestDto {
     pub account: String,
     pub password: String,
+    /// Mailed a verification link, required if registration is domain restricted
+    pub email: Option<String>,
+    /// Required if registration is invite only
+    pub invitation_code: Option<String>,
+    #[schema(ignore)]
     pub ip: Option<String>,
     pub platform: Option<String>,
     #[schema(ignore)]
@@ -22,6 +38,8 @@ impl From<RegisterRequestDto> for RegisterParams {
         Self {
             account: value.account,
             password: value.password,
+            email: value.email,
+            invitation_code: value.invitation_code,
             ip: value.ip,
             platform: value.platform,
             agent: value.agent,
@@ -34,6 +52,7 @@ impl From<RegisterRequestDto> for RegisterParams {
 pub struct LoginRequestDto {
     pub account: String,
     pub password: String,
+    #[schema(ignore)]
     pub ip: Option<String>,
     pub platform: Option<String>,
     #[schema(ignore)]
@@ -57,6 +76,212 @@ impl From<LoginRequestDto> for LoginParams {
 pub struct LoginResponseDto {
     pub user_id: Uuid,
     pub account: String,
+    /// Sent as `Authorization: Bearer <token>` by clients that don't keep the session cookie
+    pub token: String,
+    /// Exchanged at `/auth/refresh` for a new token once it expires
+    pub refresh_token: String,
+}
+
+impl From<LoginResult> for LoginResponseDto {
+    fn from(value: LoginResult) -> Self {
+        Self {
+            user_id: value.user.id,
+            account: value.user.account,
+            token: value.session.token,
+            refresh_token: value.refresh_token.token,
+        }
+    }
+}
+
+#[derive(ToSchema, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondFactorRequiredDto {
+    /// Sent to `/auth/loginWithSecondFactor` together with the code
+    pub challenge_token: String,
+    pub expired_at: Option<DateTime<Utc>>,
+}
+
+#[derive(ToSchema, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PasswordChangeRequiredDto {
+    /// Sent to `/auth/changeExpiredPassword` together with the new password
+    pub challenge_token: String,
+    pub expired_at: Option<DateTime<Utc>>,
+}
+
+#[derive(ToSchema, Serialize, Debug, Clone)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum LoginOutcomeDto {
+    Authenticated(LoginResponseDto),
+    SecondFactorRequired(SecondFactorRequiredDto),
+    PasswordChangeRequired(PasswordChangeRequiredDto),
+    /// Registered with an email of a restricted domain, not let in before the link mailed to it
+    /// is opened
+    EmailVerificationRequired,
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeExpiredPasswordRequestDto {
+    pub challenge_token: String,
+    /// RSA encrypted like the password of a login
+    pub new_password: String,
+}
+
+impl From<ChangeExpiredPasswordRequestDto> for ChangeExpiredPasswordParams {
+    fn from(value: ChangeExpiredPasswordRequestDto) -> Self {
+        Self {
+            challenge_token: value.challenge_token,
+            new_password: value.new_password,
+        }
+    }
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecondFactorLoginRequestDto {
+    pub challenge_token: String,
+    /// A TOTP code or one of the recovery codes
+    pub code: String,
+}
+
+impl From<SecondFactorLoginRequestDto> for SecondFactorLoginParams {
+    fn from(value: SecondFactorLoginRequestDto) -> Self {
+        Self {
+            challenge_token: value.challenge_token,
+            code: value.code,
+        }
+    }
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshRequestDto {
+    pub refresh_token: String,
+}
+
+impl From<RefreshRequestDto> for RefreshParams {
+    fn from(value: RefreshRequestDto) -> Self {
+        Self {
+            refresh_token: value.refresh_token,
+        }
+    }
+}
+
+#[derive(ToSchema, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshResponseDto {
+    pub token: String,
+    pub refresh_token: String,
+}
+
+#[derive(ToSchema, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistrationSettingsDto {
+    pub mode: RegistrationMode,
+    /// Email domains that may register if registration is domain restricted
+    pub domains: Vec<String>,
+}
+
+impl From<RegistrationSettings> for RegistrationSettingsDto {
+    fn from(value: RegistrationSettings) -> Self {
+        Self {
+            mode: value.mode,
+            domains: value.domains,
+        }
+    }
+}
+
+impl From<RegistrationSettingsDto> for RegistrationSettings {
+    fn from(value: RegistrationSettingsDto) -> Self {
+        Self {
+            mode: value.mode,
+            domains: value.domains,
+        }
+    }
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyEmailDto {
+    pub token: String,
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForgotPasswordDto {
+    pub email: String,
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestEmailLoginDto {
+    pub email: String,
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailLinkLoginRequestDto {
+    /// Token of the mailed login link
+    pub token: String,
+    #[schema(ignore)]
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    #[schema(ignore)]
+    pub agent: Option<String>,
+}
+
+impl From<EmailLinkLoginRequestDto> for EmailLinkLoginParams {
+    fn from(value: EmailLinkLoginRequestDto) -> Self {
+        Self {
+            token: value.token,
+            ip: value.ip,
+            platform: value.platform,
+            agent: value.agent,
+        }
+    }
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailCodeLoginRequestDto {
+    pub email: String,
+    /// Code mailed along with the login link
+    pub code: String,
+    #[schema(ignore)]
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    #[schema(ignore)]
+    pub agent: Option<String>,
+}
+
+impl From<EmailCodeLoginRequestDto> for EmailCodeLoginParams {
+    fn from(value: EmailCodeLoginRequestDto) -> Self {
+        Self {
+            email: value.email,
+            code: value.code,
+            ip: value.ip,
+            platform: value.platform,
+            agent: value.agent,
+        }
+    }
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetPasswordDto {
+    pub token: String,
+    /// RSA encrypted like the password of `login`
+    pub password: String,
+}
+
+impl From<ResetPasswordDto> for ResetPasswordParams {
+    fn from(value: ResetPasswordDto) -> Self {
+        Self {
+            token: value.token,
+            password: value.password,
+        }
+    }
 }
 
 #[derive(ToSchema, Deserialize)]
@@ -75,6 +300,32 @@ impl From<AssignUserPermissionsDto> for AssignUserPermissionsParams {
     }
 }
 
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionDenialsDto {
+    subject_kind: PermissionSubjectKind,
+    subject_id: Uuid,
+    permission_id_list: Vec<Uuid>,
+}
+
+impl From<PermissionDenialsDto> for PermissionDenialsParams {
+    fn from(value: PermissionDenialsDto) -> Self {
+        Self {
+            subject_kind: value.subject_kind,
+            subject_id: value.subject_id,
+            permission_id_list: value.permission_id_list,
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryPermissionDenialsDto {
+    pub subject_kind: PermissionSubjectKind,
+    pub subject_id: Uuid,
+}
+
 #[derive(Deserialize, IntoParams)]
 #[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
@@ -82,6 +333,15 @@ pub struct QueryUserPermissionsDto {
     pub user_id: Uuid,
 }
 
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(rename_all = "camelCase")]
+pub struct ExplainPermissionDto {
+    pub user_id: Uuid,
+    /// Code of the permission
+    pub code: String,
+}
+
 #[derive(Deserialize, IntoParams)]
 #[into_params(parameter_in = Query)]
 #[serde(rename_all = "camelCase")]
@@ -119,3 +379,40 @@ pub struct QueryPermissionGroupPermissionsDto {
 
 #[derive(Serialize, ToSchema)]
 pub struct GroupChainPermissionsDto(pub Vec<GroupPermissionChainNode>);
+
+/// Query the identity provider redirects back to `/auth/oauth/{provider}/callback` with.
+#[derive(Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct OAuthCallbackDto {
+    pub code: String,
+    pub state: String,
+}
+
+#[derive(ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpersonateRequestDto {
+    pub user_id: Uuid,
+    /// Why the user is impersonated, shown to them with the impersonation
+    pub reason: Option<String>,
+}
+
+#[derive(ToSchema, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpersonationResponseDto {
+    pub user_id: Uuid,
+    pub account: String,
+    /// Sent as `Authorization: Bearer <token>`, the session cookie stays that of the impersonator
+    pub token: String,
+    pub expired_at: Option<DateTime<Utc>>,
+}
+
+impl From<ImpersonationResult> for ImpersonationResponseDto {
+    fn from(value: ImpersonationResult) -> Self {
+        Self {
+            user_id: value.user.id,
+            account: value.user.account,
+            token: value.session.token,
+            expired_at: value.session.expired_at,
+        }
+    }
+}
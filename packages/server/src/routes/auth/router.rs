@@ -1,35 +1,92 @@
-use app::services::auth::AuthService;
-use axum::{Json, extract::Query};
-use axum_extra::{TypedHeader, extract::cookie::Cookie, headers::UserAgent};
-use shared::enums::OperationPermission;
+use std::net::SocketAddr;
+
+use app::{
+    error::{AppError, AppException},
+    models::permission::Permission,
+    services::{
+        auth::{
+            AuthService,
+            explain_permission::PermissionExplanation,
+            impersonate::ImpersonateParams,
+            login::LoginOutcome,
+            oauth::{OAuthLoginParams, StartOAuthLoginParams},
+        },
+        user::UserService,
+    },
+};
+use axum::{
+    Json,
+    extract::{ConnectInfo, Path, Query},
+};
+use axum_extra::{
+    TypedHeader,
+    extract::{
+        CookieJar,
+        cookie::{Cookie, SameSite},
+    },
+    headers::UserAgent,
+};
 use time::Duration;
 use utoipa::OpenApi;
 
 use crate::{
+    error::ServerExceptionCode,
     extractors::{
         app_service::AppService,
         helper::Helper,
-        session::{SESSION_ID_KEY, Session},
+        session::{InteractiveSession, SESSION_ID_KEY, Session, session_cookie},
     },
     init_router,
     response::{ApiResponse, Null, ResponseJson},
     result::ServerResult,
     routes::auth::dto::{
-        LoginRequestDto, LoginResponseDto, QueryDepartmentPermissionsDto, QueryGroupPermissionsDto,
-        QueryPermissionGroupPermissionsDto, QueryUserPermissionsDto, RegisterRequestDto,
+        ExplainPermissionDto, LoginOutcomeDto, LoginRequestDto, LoginResponseDto,
+        QueryDepartmentPermissionsDto, QueryGroupPermissionsDto,
+        QueryPermissionGroupPermissionsDto, QueryUserPermissionsDto, RefreshRequestDto,
+        RefreshResponseDto, RegisterRequestDto, SecondFactorLoginRequestDto,
+        SecondFactorRequiredDto,
     },
+    settings::Settings,
 };
 
-use super::dto::{AssignUserPermissionsDto, QueryRoleGroupPermissionsDto, QueryRolePermissionsDto};
+use super::dto::{
+    AssignUserPermissionsDto, ChangeExpiredPasswordRequestDto, EmailCodeLoginRequestDto,
+    EmailLinkLoginRequestDto, ForgotPasswordDto, ImpersonateRequestDto, ImpersonationResponseDto,
+    OAuthCallbackDto, PasswordChangeRequiredDto, PermissionDenialsDto, QueryPermissionDenialsDto,
+    QueryRoleGroupPermissionsDto, QueryRolePermissionsDto, RegistrationSettingsDto,
+    RequestEmailLoginDto, ResetPasswordDto, VerifyEmailDto,
+};
+
+/// Cookie binding an OAuth login to the browser that started it.
+const OAUTH_STATE_KEY: &str = "oauth_state";
 
 #[derive(OpenApi)]
 #[openapi(paths(
     register,
+    query_registration_settings,
+    update_registration_settings,
     login,
+    login_with_second_factor,
+    change_expired_password,
+    request_email_login,
+    login_with_email_link,
+    login_with_email_code,
+    oauth_start,
+    oauth_callback,
+    refresh,
     logout,
     logout_all,
+    impersonate,
+    stop_impersonation,
+    verify_email,
+    forgot_password,
+    reset_password,
     assign_user_permissions,
+    deny_permissions,
+    delete_permission_denials,
+    query_permission_denials,
     query_user_permissions,
+    explain_permission,
     query_user_group_permissions,
     query_department_permissions,
     query_role_permissions,
@@ -39,11 +96,30 @@ use super::dto::{AssignUserPermissionsDto, QueryRoleGroupPermissionsDto, QueryRo
 pub(crate) struct ApiDoc;
 init_router!(
     register,
+    query_registration_settings,
+    update_registration_settings,
     login,
+    login_with_second_factor,
+    change_expired_password,
+    request_email_login,
+    login_with_email_link,
+    login_with_email_code,
+    oauth_start,
+    oauth_callback,
+    refresh,
     logout,
     logout_all,
+    impersonate,
+    stop_impersonation,
+    verify_email,
+    forgot_password,
+    reset_password,
     assign_user_permissions,
+    deny_permissions,
+    delete_permission_denials,
+    query_permission_denials,
     query_user_permissions,
+    explain_permission,
     query_user_group_permissions,
     query_department_permissions,
     query_role_permissions,
@@ -54,77 +130,319 @@ init_router!(
 /// Register
 #[utoipa::path(
     operation_id = "register",
+    description = "Register and login, unless the email has to be verified first",
     post,
     path = "/register",
     request_body = RegisterRequestDto,
     responses(
-        (status = OK, description = "ok", body = ResponseJson<LoginResponseDto>)
+        (status = OK, description = "ok", body = ResponseJson<LoginOutcomeDto>)
     )
 )]
 pub async fn register(
     helper: Helper,
     auth_service: AppService<AuthService>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     TypedHeader(user_agent): TypedHeader<UserAgent>,
     Json(mut params): Json<RegisterRequestDto>,
 ) -> ServerResult<ApiResponse> {
+    params.ip = Some(addr.ip().to_string());
     params.agent = Some(user_agent.to_string());
     params.password = helper.decrypt_rsa(&params.password)?;
 
-    let (auth_token_id, user) = auth_service.register(params.into()).await?;
+    let outcome = auth_service.register(params.into()).await?;
 
-    let mut cookie = Cookie::new(SESSION_ID_KEY, auth_token_id.to_string());
-    cookie.set_path("/");
-    cookie.set_http_only(true);
+    Ok(login_outcome_response(outcome))
+}
+
+/// Query registration settings
+#[utoipa::path(
+    operation_id = "queryRegistrationSettings",
+    description = "Who may register, for the registration form to ask for what is needed",
+    get,
+    path = "/queryRegistrationSettings",
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<RegistrationSettingsDto>)
+    )
+)]
+pub async fn query_registration_settings(
+    auth_service: AppService<AuthService>,
+) -> ServerResult<ApiResponse> {
+    let settings = auth_service.query_registration_settings().await?;
 
-    let response_dto = LoginResponseDto {
-        user_id: user.id,
-        account: user.account,
-    };
+    Ok(ApiResponse::json(RegistrationSettingsDto::from(settings)))
+}
 
-    let mut response = ApiResponse::default();
-    response.set_cookie(cookie);
-    response.set_body_json(response_dto);
+/// Update registration settings
+#[utoipa::path(
+    operation_id = "updateRegistrationSettings",
+    description = "Update registration settings",
+    patch,
+    path = "/updateRegistrationSettings",
+    request_body = RegistrationSettingsDto,
+    security(("session" = ["update_registration_settings"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+pub async fn update_registration_settings(
+    auth_service: AppService<AuthService>,
+    Json(params): Json<RegistrationSettingsDto>,
+) -> ServerResult<ApiResponse> {
+    auth_service
+        .update_registration_settings(params.into())
+        .await?;
 
-    Ok(response)
+    Ok(ApiResponse::null())
 }
 
 /// Login
 #[utoipa::path(
     operation_id = "login",
+    description = "Login, answered with a challenge instead of a session if the user has a second factor",
     post,
     path = "/login",
     request_body = LoginRequestDto,
     responses(
-        (status = OK, description = "ok", body = ResponseJson<LoginResponseDto>)
+        (status = OK, description = "ok", body = ResponseJson<LoginOutcomeDto>)
     )
 )]
 pub async fn login(
     helper: Helper,
     auth_service: AppService<AuthService>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     TypedHeader(user_agent): TypedHeader<UserAgent>,
     Json(mut params): Json<LoginRequestDto>,
 ) -> ServerResult<ApiResponse> {
+    params.ip = Some(addr.ip().to_string());
     params.agent = Some(user_agent.to_string());
     params.password = helper.decrypt_rsa(&params.password)?;
 
-    let (auth_token_id, user) = auth_service.login(params.into()).await?;
+    let outcome = auth_service.login(params.into()).await?;
 
-    let mut cookie = Cookie::new(SESSION_ID_KEY, auth_token_id.to_string());
-    cookie.set_path("/");
-    cookie.set_http_only(true);
+    Ok(login_outcome_response(outcome))
+}
 
-    let response_dto = LoginResponseDto {
-        user_id: user.id,
-        account: user.account,
-    };
+/// Login with second factor
+#[utoipa::path(
+    operation_id = "loginWithSecondFactor",
+    description = "Finish a login with the challenge token and a TOTP or recovery code",
+    post,
+    path = "/loginWithSecondFactor",
+    request_body = SecondFactorLoginRequestDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<LoginResponseDto>)
+    )
+)]
+pub async fn login_with_second_factor(
+    auth_service: AppService<AuthService>,
+    Json(params): Json<SecondFactorLoginRequestDto>,
+) -> ServerResult<ApiResponse> {
+    let result = auth_service.login_with_second_factor(params.into()).await?;
+
+    let cookie = session_cookie(result.session.id, Settings::get().session.idle_timeout());
 
     let mut response = ApiResponse::default();
     response.set_cookie(cookie);
-    response.set_body_json(response_dto);
+    response.set_body_json(LoginResponseDto::from(result));
+
+    Ok(response)
+}
+
+/// Change expired password
+#[utoipa::path(
+    operation_id = "changeExpiredPassword",
+    description = "Continue a login whose password has expired by setting a new one",
+    post,
+    path = "/changeExpiredPassword",
+    request_body = ChangeExpiredPasswordRequestDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<LoginOutcomeDto>)
+    )
+)]
+pub async fn change_expired_password(
+    helper: Helper,
+    auth_service: AppService<AuthService>,
+    Json(mut params): Json<ChangeExpiredPasswordRequestDto>,
+) -> ServerResult<ApiResponse> {
+    params.new_password = helper.decrypt_rsa(&params.new_password)?;
+
+    let outcome = auth_service.change_expired_password(params.into()).await?;
+
+    Ok(login_outcome_response(outcome))
+}
+
+/// Request email login
+#[utoipa::path(
+    operation_id = "requestEmailLogin",
+    description = "Mail a login link and code to a verified address, answering the same whether it is known or not",
+    post,
+    path = "/requestEmailLogin",
+    request_body = RequestEmailLoginDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+pub async fn request_email_login(
+    auth_service: AppService<AuthService>,
+    Json(params): Json<RequestEmailLoginDto>,
+) -> ServerResult<ApiResponse> {
+    auth_service.request_email_login(&params.email).await?;
+
+    Ok(ApiResponse::null())
+}
+
+/// Login with email link
+#[utoipa::path(
+    operation_id = "loginWithEmailLink",
+    description = "Login with the token of a mailed login link",
+    post,
+    path = "/loginWithEmailLink",
+    request_body = EmailLinkLoginRequestDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<LoginOutcomeDto>)
+    )
+)]
+pub async fn login_with_email_link(
+    auth_service: AppService<AuthService>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    TypedHeader(user_agent): TypedHeader<UserAgent>,
+    Json(mut params): Json<EmailLinkLoginRequestDto>,
+) -> ServerResult<ApiResponse> {
+    params.ip = Some(addr.ip().to_string());
+    params.agent = Some(user_agent.to_string());
+
+    let outcome = auth_service.login_with_email_link(params.into()).await?;
+
+    Ok(login_outcome_response(outcome))
+}
+
+/// Login with email code
+#[utoipa::path(
+    operation_id = "loginWithEmailCode",
+    description = "Login with the code mailed along with a login link",
+    post,
+    path = "/loginWithEmailCode",
+    request_body = EmailCodeLoginRequestDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<LoginOutcomeDto>)
+    )
+)]
+pub async fn login_with_email_code(
+    auth_service: AppService<AuthService>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    TypedHeader(user_agent): TypedHeader<UserAgent>,
+    Json(mut params): Json<EmailCodeLoginRequestDto>,
+) -> ServerResult<ApiResponse> {
+    params.ip = Some(addr.ip().to_string());
+    params.agent = Some(user_agent.to_string());
+
+    let outcome = auth_service.login_with_email_code(params.into()).await?;
+
+    Ok(login_outcome_response(outcome))
+}
+
+/// Start OAuth login
+#[utoipa::path(
+    operation_id = "oauthStart",
+    description = "Redirect to the identity provider, linking the identity to the current user if there is a session",
+    get,
+    path = "/oauth/{provider}/start",
+    params(("provider" = String, Path, description = "Name of the identity provider")),
+    responses(
+        (status = FOUND, description = "redirect to the identity provider")
+    )
+)]
+pub async fn oauth_start(
+    session: Option<InteractiveSession>,
+    auth_service: AppService<AuthService>,
+    Path(provider): Path<String>,
+) -> ServerResult<ApiResponse> {
+    let authorization = auth_service
+        .start_oauth_login(StartOAuthLoginParams {
+            provider,
+            link_user_id: session.map(|x| x.payload.user_id),
+        })
+        .await?;
+
+    // Lax, so the cookie survives the top-level redirect back from the provider
+    let mut cookie = Cookie::new(OAUTH_STATE_KEY, authorization.state);
+    cookie.set_path("/");
+    cookie.set_http_only(true);
+    cookie.set_same_site(SameSite::Lax);
+    cookie.set_max_age(Duration::seconds(
+        (authorization.expired_at - chrono::Utc::now()).num_seconds(),
+    ));
+
+    let mut response = ApiResponse::redirect(&authorization.url);
+    response.set_cookie(cookie);
 
     Ok(response)
 }
 
+/// OAuth callback
+#[utoipa::path(
+    operation_id = "oauthCallback",
+    description = "Finish a login at the identity provider, creating the user on first sight",
+    get,
+    path = "/oauth/{provider}/callback",
+    params(
+        ("provider" = String, Path, description = "Name of the identity provider"),
+        OAuthCallbackDto,
+    ),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<LoginOutcomeDto>)
+    )
+)]
+pub async fn oauth_callback(
+    jar: CookieJar,
+    auth_service: AppService<AuthService>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    TypedHeader(user_agent): TypedHeader<UserAgent>,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthCallbackDto>,
+) -> ServerResult<ApiResponse> {
+    // A state from another browser would log this one in as someone else
+    if jar.get(OAUTH_STATE_KEY).map(|x| x.value()) != Some(query.state.as_str()) {
+        return Err(AppError::from(AppException::ExternalAuthenticationFailed).into());
+    }
+
+    let outcome = auth_service
+        .login_with_oauth(OAuthLoginParams {
+            provider,
+            code: query.code,
+            state: query.state,
+            ip: Some(addr.ip().to_string()),
+            platform: None,
+            agent: Some(user_agent.to_string()),
+        })
+        .await?;
+
+    Ok(login_outcome_response(outcome))
+}
+
+/// Refresh
+#[utoipa::path(
+    operation_id = "refresh",
+    description = "Exchange a refresh token for a new access token and refresh token",
+    post,
+    path = "/refresh",
+    request_body = RefreshRequestDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<RefreshResponseDto>)
+    )
+)]
+pub async fn refresh(
+    auth_service: AppService<AuthService>,
+    Json(params): Json<RefreshRequestDto>,
+) -> ServerResult<ApiResponse> {
+    let result = auth_service.refresh(params.into()).await?;
+
+    Ok(ApiResponse::json(RefreshResponseDto {
+        token: result.access_token,
+        refresh_token: result.refresh_token.token,
+    }))
+}
+
 /// Logout
 #[utoipa::path(
     operation_id = "logout",
@@ -163,7 +481,7 @@ pub async fn logout(
     )
 )]
 pub async fn logout_all(
-    auth_session: Session,
+    auth_session: InteractiveSession,
     auth_service: AppService<AuthService>,
 ) -> ServerResult<ApiResponse> {
     auth_service
@@ -181,6 +499,131 @@ pub async fn logout_all(
     Ok(ApiResponse::null())
 }
 
+/// Impersonate
+#[utoipa::path(
+    operation_id = "impersonate",
+    description = "Open a session as another user within the data scope of the caller, who holds no permission the caller lacks",
+    post,
+    path = "/impersonate",
+    request_body = ImpersonateRequestDto,
+    security(("session" = ["impersonate_user"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<ImpersonationResponseDto>)
+    )
+)]
+pub async fn impersonate(
+    session: Session,
+    user_service: AppService<UserService>,
+    auth_service: AppService<AuthService>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    TypedHeader(user_agent): TypedHeader<UserAgent>,
+    Json(params): Json<ImpersonateRequestDto>,
+) -> ServerResult<ApiResponse> {
+    session.assert_not_impersonating()?;
+    user_service
+        .assert_users_in_scope(&[params.user_id])
+        .await?;
+
+    let result = auth_service
+        .impersonate(ImpersonateParams {
+            impersonator_id: session.payload.user_id,
+            user_id: params.user_id,
+            reason: params.reason,
+            ip: Some(addr.ip().to_string()),
+            platform: None,
+            agent: Some(user_agent.to_string()),
+        })
+        .await?;
+
+    Ok(ApiResponse::json(ImpersonationResponseDto::from(result)))
+}
+
+/// Stop impersonation
+#[utoipa::path(
+    operation_id = "stopImpersonation",
+    description = "End the impersonation session the request is made with",
+    post,
+    path = "/stopImpersonation",
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+pub async fn stop_impersonation(
+    session: Session,
+    auth_service: AppService<AuthService>,
+) -> ServerResult<ApiResponse> {
+    if !session.is_impersonating() {
+        return Err(ServerExceptionCode::Forbidden.into());
+    }
+
+    auth_service.logout(session.session_id).await?;
+
+    Ok(ApiResponse::null())
+}
+
+/// Verify email
+#[utoipa::path(
+    operation_id = "verifyEmail",
+    description = "Verify an email address with the token mailed to it",
+    post,
+    path = "/verifyEmail",
+    request_body = VerifyEmailDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+pub async fn verify_email(
+    auth_service: AppService<AuthService>,
+    Json(params): Json<VerifyEmailDto>,
+) -> ServerResult<ApiResponse> {
+    auth_service.verify_email(&params.token).await?;
+
+    Ok(ApiResponse::null())
+}
+
+/// Forgot password
+#[utoipa::path(
+    operation_id = "forgotPassword",
+    description = "Mail a password reset link, answered the same whether or not the address is known",
+    post,
+    path = "/forgotPassword",
+    request_body = ForgotPasswordDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+pub async fn forgot_password(
+    auth_service: AppService<AuthService>,
+    Json(params): Json<ForgotPasswordDto>,
+) -> ServerResult<ApiResponse> {
+    auth_service.request_password_reset(&params.email).await?;
+
+    Ok(ApiResponse::null())
+}
+
+/// Reset password
+#[utoipa::path(
+    operation_id = "resetPassword",
+    description = "Set a new password with a mailed reset token, signing out every session",
+    post,
+    path = "/resetPassword",
+    request_body = ResetPasswordDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+pub async fn reset_password(
+    helper: Helper,
+    auth_service: AppService<AuthService>,
+    Json(mut params): Json<ResetPasswordDto>,
+) -> ServerResult<ApiResponse> {
+    params.password = helper.decrypt_rsa(&params.password)?;
+
+    auth_service.reset_password(params.into()).await?;
+
+    Ok(ApiResponse::null())
+}
+
 /// Assign user permissions
 #[utoipa::path(
     operation_id = "assignUserPermissions",
@@ -188,22 +631,87 @@ pub async fn logout_all(
     post,
     path = "/assignUserPermissions",
     request_body = AssignUserPermissionsDto,
+    security(("session" = ["assign_user_permissions"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn assign_user_permissions(
-    session: Session,
     auth_service: AppService<AuthService>,
     Json(params): Json<AssignUserPermissionsDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::AssignUserPermissions)?;
-
     auth_service.assign_user_permissions(params.into()).await?;
 
     Ok(ApiResponse::null())
 }
 
+/// Deny permissions
+#[utoipa::path(
+    operation_id = "denyPermissions",
+    description = "Deny permissions to a user, user group, department, role or role group. A denial applies to every user reaching the subject, and wins over all grants of the permission",
+    post,
+    path = "/denyPermissions",
+    request_body = PermissionDenialsDto,
+    security(("session" = ["create_permission_denial"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+pub async fn deny_permissions(
+    auth_service: AppService<AuthService>,
+    Json(params): Json<PermissionDenialsDto>,
+) -> ServerResult<ApiResponse> {
+    auth_service.deny_permissions(params.into()).await?;
+
+    Ok(ApiResponse::null())
+}
+
+/// Delete permission denials
+#[utoipa::path(
+    operation_id = "deletePermissionDenials",
+    description = "Lift denials of permissions to a subject",
+    delete,
+    path = "/deletePermissionDenials",
+    request_body = PermissionDenialsDto,
+    security(("session" = ["delete_permission_denial"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+pub async fn delete_permission_denials(
+    auth_service: AppService<AuthService>,
+    Json(params): Json<PermissionDenialsDto>,
+) -> ServerResult<ApiResponse> {
+    auth_service
+        .delete_permission_denials(params.into())
+        .await?;
+
+    Ok(ApiResponse::null())
+}
+
+/// Query permission denials
+#[utoipa::path(
+    operation_id = "queryPermissionDenials",
+    description = "Permissions denied to the subject itself",
+    get,
+    path = "/queryPermissionDenials",
+    params(QueryPermissionDenialsDto),
+    security(("session" = ["query_permission_denials"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Vec<Permission>>)
+    )
+)]
+pub async fn query_permission_denials(
+    auth_service: AppService<AuthService>,
+    Query(query): Query<QueryPermissionDenialsDto>,
+) -> ServerResult<ApiResponse> {
+    let permissions = auth_service
+        .query_permission_denials(query.subject_kind, query.subject_id)
+        .await?;
+
+    Ok(ApiResponse::json(permissions))
+}
+
 /// Query user permission
 #[utoipa::path(
     operation_id = "queryUserPermissions",
@@ -211,22 +719,43 @@ pub async fn assign_user_permissions(
     get,
     path = "/queryUserPermissions",
     params(QueryUserPermissionsDto),
+    security(("session" = ["query_user_permissions"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn query_user_permissions(
-    session: Session,
     auth_service: AppService<AuthService>,
     Query(query): Query<QueryUserPermissionsDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryUserPermissions)?;
-
     let res = auth_service.query_user_permissions(query.user_id).await?;
 
     Ok(ApiResponse::json(res))
 }
 
+/// Explain permission
+#[utoipa::path(
+    operation_id = "explainPermission",
+    description = "Every chain of grants through which the user has the permission, e.g. user → department → role → permission group → permission, and every chain denying it. Any denial wins over all grants",
+    get,
+    path = "/explainPermission",
+    params(ExplainPermissionDto),
+    security(("session" = ["query_user_permissions"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<PermissionExplanation>)
+    )
+)]
+pub async fn explain_permission(
+    auth_service: AppService<AuthService>,
+    Query(query): Query<ExplainPermissionDto>,
+) -> ServerResult<ApiResponse> {
+    let explanation = auth_service
+        .explain_permission(query.user_id, &query.code)
+        .await?;
+
+    Ok(ApiResponse::json(explanation))
+}
+
 /// Query user group permissions
 #[utoipa::path(
     operation_id = "queryUserGroupPermissions",
@@ -234,17 +763,15 @@ pub async fn query_user_permissions(
     get,
     path = "/queryUserGroupPermissions",
     params(QueryGroupPermissionsDto),
+    security(("session" = ["query_group_permissions"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn query_user_group_permissions(
-    session: Session,
     auth_service: AppService<AuthService>,
     Query(query): Query<QueryGroupPermissionsDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryGroupPermissions)?;
-
     let res = auth_service
         .query_user_group_permissions(query.user_group_id)
         .await?;
@@ -259,17 +786,15 @@ pub async fn query_user_group_permissions(
     get,
     path = "/queryDepartmentPermissions",
     params(QueryDepartmentPermissionsDto),
+    security(("session" = ["query_department_permissions"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Vec<Null>>)
     )
 )]
 pub async fn query_department_permissions(
-    session: Session,
     auth_service: AppService<AuthService>,
     Query(query): Query<QueryDepartmentPermissionsDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryDepartmentPermissions)?;
-
     let res = auth_service
         .query_department_permissions(query.department_id)
         .await?;
@@ -284,17 +809,15 @@ pub async fn query_department_permissions(
     get,
     path = "/queryRolePermissions",
     params(QueryRolePermissionsDto),
+    security(("session" = ["query_role_permissions"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Vec<Null>>)
     )
 )]
 pub async fn query_role_permissions(
-    session: Session,
     auth_service: AppService<AuthService>,
     Query(query): Query<QueryRolePermissionsDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryRolePermissions)?;
-
     let res = auth_service.query_role_permissions(query.role_id).await?;
 
     Ok(ApiResponse::json(res))
@@ -307,17 +830,15 @@ pub async fn query_role_permissions(
     get,
     path = "/queryRoleGroupPermissions",
     params(QueryRoleGroupPermissionsDto),
+    security(("session" = ["query_role_group_permissions"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Vec<Null>>)
     )
 )]
 pub async fn query_role_group_permissions(
-    session: Session,
     auth_service: AppService<AuthService>,
     Query(query): Query<QueryRoleGroupPermissionsDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryRolePermissions)?;
-
     let res = auth_service
         .query_role_group_permissions(query.role_group_id)
         .await?;
@@ -332,20 +853,48 @@ pub async fn query_role_group_permissions(
     get,
     path = "/queryPermissionGroupPermissions",
     params(QueryPermissionGroupPermissionsDto),
+    security(("session" = ["query_permission_group_permissions"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Vec<Null>>)
     )
 )]
 pub async fn query_permission_group_permissions(
-    session: Session,
     auth_service: AppService<AuthService>,
     Query(query): Query<QueryPermissionGroupPermissionsDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryPermissionGroupPermissions)?;
-
     let res = auth_service
         .query_permission_group_permissions(query.permission_group_id)
         .await?;
 
     Ok(ApiResponse::json(res))
 }
+
+/// Answer a login: the session cookie and tokens, or the challenge for the second factor.
+fn login_outcome_response(outcome: LoginOutcome) -> ApiResponse {
+    match outcome {
+        LoginOutcome::Authenticated(result) => {
+            let cookie = session_cookie(result.session.id, Settings::get().session.idle_timeout());
+
+            let mut response = ApiResponse::default();
+            response.set_cookie(cookie);
+            response.set_body_json(LoginOutcomeDto::Authenticated((*result).into()));
+
+            response
+        }
+        LoginOutcome::SecondFactorRequired { challenge_token } => ApiResponse::json(
+            LoginOutcomeDto::SecondFactorRequired(SecondFactorRequiredDto {
+                challenge_token: challenge_token.token,
+                expired_at: challenge_token.expired_at,
+            }),
+        ),
+        LoginOutcome::PasswordChangeRequired { challenge_token } => ApiResponse::json(
+            LoginOutcomeDto::PasswordChangeRequired(PasswordChangeRequiredDto {
+                challenge_token: challenge_token.token,
+                expired_at: challenge_token.expired_at,
+            }),
+        ),
+        LoginOutcome::EmailVerificationRequired => {
+            ApiResponse::json(LoginOutcomeDto::EmailVerificationRequired)
+        }
+    }
+}
@@ -1,11 +1,10 @@
 use app::{services::department::DepartmentService, utils::query::DisableOrder};
 use axum::Json;
-use shared::enums::OperationPermission;
 use utoipa::OpenApi;
 
 use crate::{
     dto::PageableQueryDto,
-    extractors::{app_service::AppService, session::Session},
+    extractors::app_service::AppService,
     init_router,
     response::{ApiResponse, Null, PaginatedData, ResponseJson},
     result::ServerResult,
@@ -38,17 +37,15 @@ init_router!(
     post,
     path = "/queryDepartmentsByPage",
     request_body = PageableQueryDto<FilterDepartmentsDto, DisableOrder>,
+    security(("session" = ["query_departments"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<PaginatedData<DepartmentDto>>)
     )
 )]
 pub async fn query_departments_by_page(
-    session: Session,
     department_service: AppService<DepartmentService>,
     Json(params): Json<PageableQueryDto<FilterDepartmentsDto, DisableOrder>>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryDepartments)?;
-
     let (records, total) = department_service
         .query_departments_by_page(params.into())
         .await?;
@@ -67,17 +64,15 @@ pub async fn query_departments_by_page(
     post,
     path = "/createDepartment",
     request_body = CreateDepartmentRequestDto,
+    security(("session" = ["create_department"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<CreateDepartmentResponseDto>)
     )
 )]
 pub async fn create_department(
-    session: Session,
     department_service: AppService<DepartmentService>,
     Json(params): Json<CreateDepartmentRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::CreateDepartment)?;
-
     let group_id = department_service.create_department(params.into()).await?;
 
     Ok(ApiResponse::json(CreateDepartmentResponseDto(group_id)))
@@ -90,17 +85,15 @@ pub async fn create_department(
     delete,
     path = "/deleteDepartments",
     request_body = DeleteDepartmentsRequestDto,
+    security(("session" = ["delete_department"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn delete_departments(
-    session: Session,
     department_service: AppService<DepartmentService>,
     Json(params): Json<DeleteDepartmentsRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::DeleteDepartment)?;
-
     department_service.delete_departments(params.into()).await?;
     Ok(ApiResponse::null())
 }
@@ -112,17 +105,15 @@ pub async fn delete_departments(
     patch,
     path = "/updateDepartment",
     request_body = UpdateDepartmentRequestDto,
+    security(("session" = ["update_department"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn update_department(
-    session: Session,
     department_service: AppService<DepartmentService>,
     Json(params): Json<UpdateDepartmentRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::UpdateDepartment)?;
-
     department_service.update_department(params.into()).await?;
     Ok(ApiResponse::null())
 }
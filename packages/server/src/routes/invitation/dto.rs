@@ -0,0 +1,115 @@
+use app::{
+    models::invitation::Invitation,
+    services::invitation::{
+        create_invitation::{CreateInvitationParams, InvitationCode},
+        delete_invitations::DeleteInvitationsParams,
+        query_invitations::FilterInvitationsParams,
+    },
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct InvitationDto {
+    pub id: Uuid,
+    pub note: Option<String>,
+    pub role_ids: Vec<Uuid>,
+    pub department_ids: Vec<Uuid>,
+    pub user_group_ids: Vec<Uuid>,
+    pub created_by: Option<Uuid>,
+    pub expired_at: Option<DateTime<Utc>>,
+    pub used_by: Option<Uuid>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Invitation> for InvitationDto {
+    fn from(value: Invitation) -> Self {
+        Self {
+            id: value.id,
+            note: value.note,
+            role_ids: value.role_ids,
+            department_ids: value.department_ids,
+            user_group_ids: value.user_group_ids,
+            created_by: value.created_by,
+            expired_at: value.expired_at,
+            used_by: value.used_by,
+            used_at: value.used_at,
+            created_at: value.created_at,
+        }
+    }
+}
+
+/// Invitation filter params
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct InvitationFilterDto {
+    pub used: Option<bool>,
+}
+
+impl From<InvitationFilterDto> for FilterInvitationsParams {
+    fn from(value: InvitationFilterDto) -> Self {
+        Self { used: value.used }
+    }
+}
+
+/// Invitation create params
+#[derive(Debug, ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct CreateInvitationRequestDto {
+    pub note: Option<String>,
+    #[serde(default)]
+    pub role_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub department_ids: Vec<Uuid>,
+    #[serde(default)]
+    pub user_group_ids: Vec<Uuid>,
+    /// Valid forever if missing
+    pub expired_at: Option<DateTime<Utc>>,
+}
+
+impl CreateInvitationRequestDto {
+    pub fn into_params(self, created_by: Uuid) -> CreateInvitationParams {
+        CreateInvitationParams {
+            note: self.note,
+            role_ids: self.role_ids,
+            department_ids: self.department_ids,
+            user_group_ids: self.user_group_ids,
+            expired_at: self.expired_at,
+            created_by: Some(created_by),
+        }
+    }
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct InvitationCodeDto {
+    pub id: Uuid,
+    /// Only returned once
+    pub code: String,
+}
+
+impl From<InvitationCode> for InvitationCodeDto {
+    fn from(value: InvitationCode) -> Self {
+        Self {
+            id: value.id,
+            code: value.code,
+        }
+    }
+}
+
+#[derive(Debug, ToSchema, Deserialize)]
+pub struct DeleteInvitationsRequestDto(Vec<Uuid>);
+
+impl From<DeleteInvitationsRequestDto> for DeleteInvitationsParams {
+    fn from(value: DeleteInvitationsRequestDto) -> Self {
+        Self(value.0)
+    }
+}
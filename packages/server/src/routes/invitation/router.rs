@@ -0,0 +1,97 @@
+use app::services::invitation::InvitationService;
+use axum::Json;
+use utoipa::OpenApi;
+
+use crate::{
+    dto::PageableQueryDto,
+    extractors::{app_service::AppService, session::Session},
+    init_router,
+    response::{ApiResponse, PaginatedData, ResponseJson, ResponseJsonNull},
+    result::ServerResult,
+};
+
+use super::dto::{
+    CreateInvitationRequestDto, DeleteInvitationsRequestDto, InvitationCodeDto, InvitationDto,
+    InvitationFilterDto,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(query_invitations_by_page, create_invitation, delete_invitations))]
+pub(crate) struct ApiDoc;
+init_router!(
+    query_invitations_by_page,
+    create_invitation,
+    delete_invitations
+);
+
+/// Query invitations by page
+#[utoipa::path(
+    operation_id = "queryInvitationsByPage",
+    description = "Query invitations by page",
+    post,
+    path = "/queryInvitationsByPage",
+    request_body = PageableQueryDto<InvitationFilterDto>,
+    security(("session" = ["query_invitations"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<PaginatedData<InvitationDto>>)
+    )
+)]
+pub async fn query_invitations_by_page(
+    invitation_service: AppService<InvitationService>,
+    Json(params): Json<PageableQueryDto<InvitationFilterDto>>,
+) -> ServerResult<ApiResponse> {
+    let (records, total) = invitation_service
+        .query_invitations_by_page(params.into())
+        .await?;
+    let records = records
+        .into_iter()
+        .map(InvitationDto::from)
+        .collect::<Vec<_>>();
+
+    Ok(ApiResponse::json(PaginatedData { records, total }))
+}
+
+/// Create invitation
+#[utoipa::path(
+    operation_id = "createInvitation",
+    description = "Issue an invitation code, which is only shown in the response",
+    post,
+    path = "/createInvitation",
+    request_body = CreateInvitationRequestDto,
+    security(("session" = ["create_invitation"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<InvitationCodeDto>)
+    )
+)]
+pub async fn create_invitation(
+    session: Session,
+    invitation_service: AppService<InvitationService>,
+    Json(params): Json<CreateInvitationRequestDto>,
+) -> ServerResult<ApiResponse> {
+    let invitation_code = invitation_service
+        .create_invitation(params.into_params(session.payload.user_id))
+        .await?;
+
+    Ok(ApiResponse::json(InvitationCodeDto::from(invitation_code)))
+}
+
+/// Delete invitations
+#[utoipa::path(
+    operation_id = "deleteInvitations",
+    description = "Delete invitations, users who registered with them keep their roles, departments and user groups",
+    delete,
+    path = "/deleteInvitations",
+    request_body = DeleteInvitationsRequestDto,
+    security(("session" = ["delete_invitation"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJsonNull)
+    )
+)]
+pub async fn delete_invitations(
+    invitation_service: AppService<InvitationService>,
+    Json(params): Json<DeleteInvitationsRequestDto>,
+) -> ServerResult<ApiResponse> {
+    invitation_service.delete_invitations(params.into()).await?;
+
+    Ok(ApiResponse::null())
+}
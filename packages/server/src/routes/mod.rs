@@ -1,6 +1,9 @@
 pub mod account_book;
+pub mod application;
 pub mod auth;
 pub mod department;
+pub mod invitation;
+pub mod oauth;
 pub mod permission;
 pub mod role;
 pub mod session;
@@ -0,0 +1,172 @@
+use app::{
+    models::application::Application,
+    services::auth::authorization_server::{
+        AuthorizationParams, AuthorizationPrompt, ClientCredentials, OAuthAccessToken,
+    },
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Authorization request, named as in RFC 6749 and RFC 7636
+#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuthorizeQueryDto {
+    pub response_type: String,
+    pub client_id: Uuid,
+    pub redirect_uri: String,
+    /// Space separated permission codes, everything the application may ask for if missing
+    pub scope: Option<String>,
+    pub state: Option<String>,
+    pub code_challenge: String,
+    /// Only `S256` is supported
+    pub code_challenge_method: String,
+}
+
+impl AuthorizeQueryDto {
+    pub fn into_params(self, user_id: Uuid) -> AuthorizationParams {
+        AuthorizationParams {
+            user_id,
+            response_type: self.response_type,
+            client_id: self.client_id,
+            redirect_uri: self.redirect_uri,
+            scope: self.scope,
+            state: self.state,
+            code_challenge: self.code_challenge,
+            code_challenge_method: self.code_challenge_method,
+        }
+    }
+}
+
+/// The user's answer to an authorization request
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AuthorizeRequestDto {
+    #[serde(flatten)]
+    pub request: AuthorizeQueryDto,
+    pub approved: bool,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ConsentApplicationDto {
+    pub app_id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl From<Application> for ConsentApplicationDto {
+    fn from(value: Application) -> Self {
+        Self {
+            app_id: value.app_id,
+            name: value.name,
+            description: value.description,
+        }
+    }
+}
+
+/// What the consent screen shows
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct AuthorizationPromptDto {
+    pub application: ConsentApplicationDto,
+    pub scopes: Vec<String>,
+    /// Whether the user already allowed every scope, the screen may then approve right away
+    pub consented: bool,
+}
+
+impl From<AuthorizationPrompt> for AuthorizationPromptDto {
+    fn from(value: AuthorizationPrompt) -> Self {
+        Self {
+            application: value.application.into(),
+            scopes: value.scopes,
+            consented: value.consented,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct AuthorizeResponseDto {
+    /// Where to send the user back to the application
+    pub redirect_uri: String,
+}
+
+/// Token request, form encoded as in RFC 6749
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TokenRequestDto {
+    /// `authorization_code` or `client_credentials`
+    pub grant_type: String,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub scope: Option<String>,
+    /// Unless the client authenticates with HTTP Basic
+    pub client_id: Option<Uuid>,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenResponseDto {
+    pub access_token: String,
+    pub token_type: &'static str,
+    pub expires_in: i64,
+    pub scope: String,
+}
+
+impl From<OAuthAccessToken> for TokenResponseDto {
+    fn from(value: OAuthAccessToken) -> Self {
+        let expires_in = value
+            .access_token
+            .expired_at
+            .map(|x| (x - Utc::now()).num_seconds())
+            .unwrap_or_default();
+
+        Self {
+            access_token: value.access_token.token,
+            token_type: "Bearer",
+            expires_in,
+            scope: value.scopes.join(" "),
+        }
+    }
+}
+
+/// Introspection or revocation request, form encoded as in RFC 7662 and RFC 7009
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TokenActionRequestDto {
+    /// Only access tokens are issued, so `token_type_hint` is not needed
+    pub token: String,
+    /// Unless the client authenticates with HTTP Basic
+    pub client_id: Option<Uuid>,
+    pub client_secret: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct IntrospectionResponseDto {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<Uuid>,
+    /// Id of the user the token acts for
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<&'static str>,
+}
+
+/// Client credentials from the form, when there is no HTTP Basic header.
+pub fn form_client_credentials(
+    client_id: Option<Uuid>,
+    client_secret: Option<String>,
+) -> Option<ClientCredentials> {
+    client_id.map(|client_id| ClientCredentials {
+        client_id,
+        client_secret,
+    })
+}
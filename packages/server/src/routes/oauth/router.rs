@@ -0,0 +1,227 @@
+use app::{
+    error::{AppError, AppException},
+    services::auth::{
+        AuthService,
+        authorization_server::{ClientCredentials, ExchangeAuthorizationCodeParams},
+    },
+};
+use axum::{
+    Form, Json,
+    extract::Query,
+    response::{IntoResponse, Response},
+};
+use axum_extra::{
+    TypedHeader,
+    headers::{Authorization, authorization::Basic},
+};
+use http::header::CACHE_CONTROL;
+use utoipa::OpenApi;
+
+use crate::{
+    error::OAuthError,
+    extractors::{app_service::AppService, session::InteractiveSession},
+    init_router,
+    response::{ApiResponse, Null, ResponseJson},
+    result::ServerResult,
+};
+
+use super::dto::{
+    AuthorizationPromptDto, AuthorizeQueryDto, AuthorizeRequestDto, AuthorizeResponseDto,
+    IntrospectionResponseDto, TokenActionRequestDto, TokenRequestDto, TokenResponseDto,
+    form_client_credentials,
+};
+
+#[derive(OpenApi)]
+#[openapi(paths(query_authorization, authorize, token, introspect, revoke))]
+pub(crate) struct ApiDoc;
+init_router!(query_authorization, authorize, token, introspect, revoke);
+
+/// Query authorization
+#[utoipa::path(
+    operation_id = "queryAuthorization",
+    description = "Check an authorization request and tell the consent screen what it asks for",
+    get,
+    path = "/authorize",
+    params(AuthorizeQueryDto),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<AuthorizationPromptDto>)
+    )
+)]
+pub async fn query_authorization(
+    session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+    Query(query): Query<AuthorizeQueryDto>,
+) -> ServerResult<ApiResponse> {
+    // Support staff may not consent for the user they impersonate
+    session.assert_not_impersonating()?;
+
+    let prompt = auth_service
+        .query_authorization_prompt(&query.into_params(session.payload.user_id))
+        .await?;
+
+    Ok(ApiResponse::json(AuthorizationPromptDto::from(prompt)))
+}
+
+/// Authorize
+#[utoipa::path(
+    operation_id = "authorize",
+    description = "Approve or deny an authorization request, returning where to send the user back to",
+    post,
+    path = "/authorize",
+    request_body = AuthorizeRequestDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<AuthorizeResponseDto>)
+    )
+)]
+pub async fn authorize(
+    session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+    Json(params): Json<AuthorizeRequestDto>,
+) -> ServerResult<ApiResponse> {
+    // Support staff may not consent for the user they impersonate
+    session.assert_not_impersonating()?;
+
+    let redirect_uri = auth_service
+        .authorize_application(
+            params.request.into_params(session.payload.user_id),
+            params.approved,
+        )
+        .await?;
+
+    Ok(ApiResponse::json(AuthorizeResponseDto { redirect_uri }))
+}
+
+/// Token
+#[utoipa::path(
+    operation_id = "token",
+    description = "Issue an access token for the authorization_code or client_credentials grant",
+    post,
+    path = "/token",
+    request_body(content = TokenRequestDto, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = OK, description = "ok", body = TokenResponseDto)
+    )
+)]
+pub async fn token(
+    auth_service: AppService<AuthService>,
+    basic: Option<TypedHeader<Authorization<Basic>>>,
+    Form(params): Form<TokenRequestDto>,
+) -> Result<Response, OAuthError> {
+    let client = client_credentials(basic, params.client_id, params.client_secret)?;
+
+    let access_token = match params.grant_type.as_str() {
+        "authorization_code" => {
+            let (Some(code), Some(redirect_uri), Some(code_verifier)) =
+                (params.code, params.redirect_uri, params.code_verifier)
+            else {
+                return Err(AppError::from(AppException::InvalidRequest).into());
+            };
+            auth_service
+                .exchange_authorization_code(ExchangeAuthorizationCodeParams {
+                    client,
+                    code,
+                    redirect_uri,
+                    code_verifier,
+                })
+                .await?
+        }
+        "client_credentials" => {
+            auth_service
+                .issue_client_credentials_token(client, params.scope.as_deref())
+                .await?
+        }
+        _ => return Err(AppError::from(AppException::UnsupportedGrantType).into()),
+    };
+
+    Ok((
+        [(CACHE_CONTROL, "no-store")],
+        Json(TokenResponseDto::from(access_token)),
+    )
+        .into_response())
+}
+
+/// Introspect
+#[utoipa::path(
+    operation_id = "introspect",
+    description = "Tell a confidential application whether an access token issued to it is active",
+    post,
+    path = "/introspect",
+    request_body(content = TokenActionRequestDto, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = OK, description = "ok", body = IntrospectionResponseDto)
+    )
+)]
+pub async fn introspect(
+    auth_service: AppService<AuthService>,
+    basic: Option<TypedHeader<Authorization<Basic>>>,
+    Form(params): Form<TokenActionRequestDto>,
+) -> Result<Response, OAuthError> {
+    let client = client_credentials(basic, params.client_id, params.client_secret)?;
+    let client_id = client.client_id;
+
+    let introspection = auth_service
+        .introspect_oauth_token(client, &params.token)
+        .await?;
+    let response = match introspection {
+        Some(introspection) => IntrospectionResponseDto {
+            active: true,
+            scope: introspection.payload.scope.map(|x| x.join(" ")),
+            client_id: Some(client_id),
+            sub: Some(introspection.payload.user_id),
+            exp: introspection.access_token.expired_at.map(|x| x.timestamp()),
+            token_type: Some("Bearer"),
+        },
+        None => IntrospectionResponseDto::default(),
+    };
+
+    Ok(Json(response).into_response())
+}
+
+/// Revoke
+#[utoipa::path(
+    operation_id = "revoke",
+    description = "Revoke an access token of the application, unknown tokens are ignored",
+    post,
+    path = "/revoke",
+    request_body(content = TokenActionRequestDto, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = OK, description = "ok", body = Null)
+    )
+)]
+pub async fn revoke(
+    auth_service: AppService<AuthService>,
+    basic: Option<TypedHeader<Authorization<Basic>>>,
+    Form(params): Form<TokenActionRequestDto>,
+) -> Result<Response, OAuthError> {
+    let client = client_credentials(basic, params.client_id, params.client_secret)?;
+
+    auth_service
+        .revoke_oauth_token(client, &params.token)
+        .await?;
+
+    Ok(().into_response())
+}
+
+/// The client authenticates with HTTP Basic, or else with `client_id` and `client_secret` in the
+/// form body.
+fn client_credentials(
+    basic: Option<TypedHeader<Authorization<Basic>>>,
+    client_id: Option<uuid::Uuid>,
+    client_secret: Option<String>,
+) -> Result<ClientCredentials, OAuthError> {
+    let credentials = match basic {
+        Some(TypedHeader(Authorization(basic))) => {
+            basic
+                .username()
+                .parse()
+                .ok()
+                .map(|client_id| ClientCredentials {
+                    client_id,
+                    client_secret: Some(basic.password().to_string()),
+                })
+        }
+        None => form_client_credentials(client_id, client_secret),
+    };
+
+    credentials.ok_or_else(|| AppError::from(AppException::InvalidClient).into())
+}
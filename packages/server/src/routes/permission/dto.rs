@@ -7,6 +7,7 @@ use app::{
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use shared::enums::DataScope;
 use utoipa::ToSchema;
 use uuid::Uuid;
 
@@ -16,6 +17,11 @@ pub struct CreatePermissionDto {
     pub code: String,
     pub description: Option<String>,
     pub parent_id: Option<Uuid>,
+    /// Required for data permissions
+    pub data_scope: Option<DataScope>,
+    /// Required for data permissions with a custom scope
+    #[serde(default)]
+    pub data_department_ids: Vec<Uuid>,
 }
 
 impl From<CreatePermissionDto> for CreatePermissionParams {
@@ -25,6 +31,8 @@ impl From<CreatePermissionDto> for CreatePermissionParams {
             code: dto.code,
             description: dto.description,
             parent_id: dto.parent_id,
+            data_scope: dto.data_scope,
+            data_department_ids: dto.data_department_ids,
         }
     }
 }
@@ -35,6 +43,8 @@ pub struct PermissionDto {
     pub kind: String,
     pub code: String,
     pub description: Option<String>,
+    pub data_scope: Option<DataScope>,
+    pub data_department_ids: Vec<Uuid>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -46,6 +56,8 @@ impl From<Permission> for PermissionDto {
             kind: value.kind,
             code: value.code,
             description: value.description,
+            data_scope: value.data_scope,
+            data_department_ids: value.data_department_ids,
             created_at: value.created_at.into(),
             updated_at: value.updated_at.into(),
         }
@@ -66,6 +78,8 @@ pub struct UpdatePermissionRequestDto {
     pub id: Uuid,
     pub code: Option<String>,
     pub description: Option<String>,
+    pub data_scope: Option<DataScope>,
+    pub data_department_ids: Option<Vec<Uuid>>,
 }
 
 impl From<UpdatePermissionRequestDto> for UpdatePermissionParams {
@@ -74,6 +88,8 @@ impl From<UpdatePermissionRequestDto> for UpdatePermissionParams {
             id: value.id,
             code: value.code,
             description: value.description,
+            data_scope: value.data_scope,
+            data_department_ids: value.data_department_ids,
         }
     }
 }
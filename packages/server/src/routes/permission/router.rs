@@ -1,11 +1,10 @@
 use app::services::permission::PermissionService;
 use axum::Json;
-use shared::enums::OperationPermission;
 use utoipa::OpenApi;
 
 use crate::{
     dto::PageableQueryDto,
-    extractors::{app_service::AppService, session::Session},
+    extractors::app_service::AppService,
     init_router,
     response::{ApiResponse, Null, PaginatedData, ResponseJson, ResponseJsonNull},
     result::ServerResult,
@@ -38,17 +37,15 @@ init_router!(
     get,
     path = "/queryPermissionsByPage",
     request_body = PageableQueryDto<FilterPermissionsDto>,
+    security(("session" = ["query_permissions"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<PaginatedData<PermissionDto>>)
     )
 )]
 pub async fn query_permissions_by_page(
-    session: Session,
     permission_service: AppService<PermissionService>,
     Json(params): Json<PageableQueryDto<FilterPermissionsDto>>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryPermissions)?;
-
     let (records, total) = permission_service
         .query_permissions_by_page(params.into())
         .await?;
@@ -68,17 +65,15 @@ pub async fn query_permissions_by_page(
     post,
     path = "/createPermission",
     request_body = CreatePermissionDto,
+    security(("session" = ["create_permission"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Uuid>)
     )
 )]
 pub async fn create_permission(
-    session: Session,
     permission_service: AppService<PermissionService>,
     Json(params): Json<CreatePermissionDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::CreatePermission)?;
-
     let user_id = permission_service.create_permission(params.into()).await?;
 
     Ok(ApiResponse::json(user_id))
@@ -91,17 +86,15 @@ pub async fn create_permission(
     delete,
     path = "/deletePermissions",
     request_body = DeletePermissionsRequestDto,
+    security(("session" = ["delete_permission"])),
     responses(
         (status = OK, description = "ok", body = ResponseJsonNull)
     )
 )]
 pub async fn delete_permissions(
-    session: Session,
     permission_service: AppService<PermissionService>,
     Json(params): Json<DeletePermissionsRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::DeletePermission)?;
-
     permission_service.delete_permissions(params.into()).await?;
 
     Ok(ApiResponse::null())
@@ -114,17 +107,15 @@ pub async fn delete_permissions(
     patch,
     path = "/updatePermission",
     request_body = UpdatePermissionRequestDto,
+    security(("session" = ["update_permission"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn update_permission(
-    session: Session,
     permission_service: AppService<PermissionService>,
     Json(params): Json<UpdatePermissionRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::UpdateGroup)?;
-
     permission_service.update_permission(params.into()).await?;
     Ok(ApiResponse::null())
 }
@@ -1,11 +1,10 @@
 use app::services::role::RoleService;
 use axum::Json;
-use shared::enums::OperationPermission;
 use utoipa::OpenApi;
 
 use crate::{
     dto::PageableQueryDto,
-    extractors::{app_service::AppService, session::Session},
+    extractors::app_service::AppService,
     init_router,
     response::{ApiResponse, Null, PaginatedData, ResponseJson, ResponseJsonNull},
     result::ServerResult,
@@ -29,17 +28,15 @@ init_router!(query_roles_by_page, create_role, delete_roles, update_role);
     post,
     path = "/queryRolesByPage",
     request_body = PageableQueryDto<RoleFilterDto>,
+    security(("session" = ["query_roles"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<PaginatedData<RoleDto>>)
     )
 )]
 pub async fn query_roles_by_page(
-    session: Session,
     role_service: AppService<RoleService>,
     Json(params): Json<PageableQueryDto<RoleFilterDto>>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryRoles)?;
-
     let (records, total) = role_service.query_roles_by_page(params.into()).await?;
     let records = records.into_iter().map(RoleDto::from).collect::<Vec<_>>();
 
@@ -53,17 +50,15 @@ pub async fn query_roles_by_page(
     post,
     path = "/createRole",
     request_body = CreateRoleRequestDto,
+    security(("session" = ["create_role"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Uuid>)
     )
 )]
 pub async fn create_role(
-    session: Session,
     role_service: AppService<RoleService>,
     Json(params): Json<CreateRoleRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::CreateRole)?;
-
     let id = role_service.create_role(params.into()).await?;
 
     Ok(ApiResponse::json(id))
@@ -76,17 +71,15 @@ pub async fn create_role(
     delete,
     path = "/deleteRoles",
     request_body = DeleteUsersRequestDto,
+    security(("session" = ["delete_role"])),
     responses(
         (status = OK, description = "ok", body = ResponseJsonNull)
     )
 )]
 pub async fn delete_roles(
-    session: Session,
     role_service: AppService<RoleService>,
     Json(params): Json<DeleteRolesRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::DeleteRole)?;
-
     role_service.delete_roles(params.into()).await?;
 
     Ok(ApiResponse::null())
@@ -99,17 +92,15 @@ pub async fn delete_roles(
     patch,
     path = "/updateRole",
     request_body = UpdateRoleRequestDto,
+    security(("session" = ["update_role"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn update_role(
-    session: Session,
     role_service: AppService<RoleService>,
     Json(params): Json<UpdateRoleRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::UpdateRole)?;
-
     role_service.update_role(params.into()).await?;
     Ok(ApiResponse::null())
 }
@@ -1,5 +1,19 @@
-use chrono::{DateTime, Utc};
+use app::{
+    models::{
+        auth_token::{AuthToken, SessionTokenPayload},
+        impersonation::Impersonation,
+    },
+    services::{
+        auth::totp::TotpEnrollment,
+        login_history::query_login_histories::FilterLoginHistoriesParams,
+    },
+};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use shared::{
+    enums::Gender,
+    user_agent::{DeviceKind, parse_user_agent},
+};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
@@ -19,7 +33,88 @@ pub struct SessionDto {
     pub id: Uuid,
     pub platform: Option<String>,
     pub agent: Option<String>,
+    /// Parsed from the user agent
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device: DeviceKind,
+    /// Name the user gave the device
+    pub device_name: Option<String>,
+    /// Where the session was signed in from
+    pub ip: Option<String>,
+    /// Recorded at most once a minute
+    pub last_seen_at: Option<DateTime<Utc>>,
+    pub last_seen_ip: Option<String>,
     pub created_at: DateTime<Utc>,
+    /// Whether this is the session the request was made with
+    pub current: bool,
+    /// Set if support staff opened the session to act as the user
+    pub impersonator_id: Option<Uuid>,
+}
+
+impl SessionDto {
+    pub fn new(token: AuthToken, current_session_id: Option<Uuid>) -> Self {
+        let user_agent = parse_user_agent(token.agent.as_deref().unwrap_or_default());
+        Self {
+            impersonator_id: serde_json::from_str::<SessionTokenPayload>(&token.payload)
+                .ok()
+                .and_then(|x| x.impersonator_id),
+            current: current_session_id == Some(token.id),
+            id: token.id,
+            platform: token.platform,
+            agent: token.agent,
+            browser: user_agent.browser,
+            os: user_agent.os,
+            device: user_agent.device,
+            device_name: token.device_name,
+            ip: token.ip,
+            last_seen_at: token.last_seen_at,
+            last_seen_ip: token.last_seen_ip,
+            created_at: token.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct RenameSessionDto {
+    pub id: Uuid,
+    /// Cleared if empty
+    pub device_name: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ImpersonationDto {
+    pub id: Uuid,
+    pub impersonator_id: Uuid,
+    pub impersonator_account: String,
+    pub session_id: Uuid,
+    pub reason: Option<String>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// Unset while the session lasts, and for sessions that expired instead of being ended
+    pub ended_at: Option<DateTime<Utc>>,
+}
+
+impl From<Impersonation> for ImpersonationDto {
+    fn from(value: Impersonation) -> Self {
+        Self {
+            id: value.id,
+            impersonator_id: value.impersonator_id,
+            impersonator_account: value.impersonator_account,
+            session_id: value.session_id,
+            reason: value.reason,
+            ip: value.ip,
+            platform: value.platform,
+            agent: value.agent,
+            started_at: value.started_at,
+            ended_at: value.ended_at,
+        }
+    }
 }
 
 #[derive(Deserialize, IntoParams)]
@@ -28,3 +123,137 @@ pub struct SessionDto {
 pub struct DeleteSessionDto {
     pub id: Uuid,
 }
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct CreatePersonalAccessTokenDto {
+    pub name: String,
+    /// Permission codes granted to the token, each must be held by the current user
+    pub permissions: Vec<String>,
+    pub expired_at: DateTime<Utc>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct CreatePersonalAccessTokenResponseDto {
+    pub id: Uuid,
+    /// Sent as `Authorization: Bearer <token>`, only returned once
+    pub token: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct PersonalAccessTokenDto {
+    pub id: Uuid,
+    pub name: Option<String>,
+    pub permissions: Vec<String>,
+    pub created_at: DateTime<Utc>,
+    pub expired_at: Option<DateTime<Utc>>,
+}
+
+impl From<AuthToken> for PersonalAccessTokenDto {
+    fn from(value: AuthToken) -> Self {
+        let payload = serde_json::from_str::<SessionTokenPayload>(&value.payload).unwrap();
+        Self {
+            id: value.id,
+            name: payload.name,
+            permissions: payload.scope.unwrap_or_default(),
+            created_at: value.created_at,
+            expired_at: value.expired_at,
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query, rename_all = "camelCase")]
+pub struct DeletePersonalAccessTokenDto {
+    pub id: Uuid,
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct SessionLoginHistoryFilterDto {
+    pub success: Option<bool>,
+}
+
+impl From<SessionLoginHistoryFilterDto> for FilterLoginHistoriesParams {
+    fn from(value: SessionLoginHistoryFilterDto) -> Self {
+        FilterLoginHistoriesParams {
+            success: value.success,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct TotpEnrollmentDto {
+    pub secret: String,
+    /// `otpauth://` URI to render as a QR code
+    pub uri: String,
+}
+
+impl From<TotpEnrollment> for TotpEnrollmentDto {
+    fn from(value: TotpEnrollment) -> Self {
+        Self {
+            secret: value.secret,
+            uri: value.uri,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct TotpCodeDto {
+    pub code: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct RecoveryCodesDto {
+    /// Each code signs in once in place of a TOTP code, only returned once
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct SendEmailVerificationDto {
+    pub email: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct UpdateProfileDto {
+    pub nickname: Option<String>,
+    pub real_name: Option<String>,
+    pub phone: Option<String>,
+    /// A changed email has to be verified again
+    pub email: Option<String>,
+    pub gender: Option<Gender>,
+    pub birthday: Option<NaiveDate>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct ChangePasswordDto {
+    /// RSA encrypted
+    pub old_password: String,
+    /// RSA encrypted
+    pub new_password: String,
+    /// Sign out every other session of the user
+    #[serde(default)]
+    pub revoke_other_sessions: bool,
+}
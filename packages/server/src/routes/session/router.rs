@@ -1,14 +1,44 @@
-use app::services::{auth::AuthService, auth_token::AuthTokenService, user::UserService};
-use axum::{extract::Query, response::IntoResponse};
+use app::{
+    models::auth_token::AuthTokenKind,
+    services::{
+        auth::{
+            AuthService, change_password::ChangePasswordParams,
+            personal_access_token::IssuePersonalAccessTokenParams,
+        },
+        auth_token::AuthTokenService,
+        impersonation::ImpersonationService,
+        login_history::{
+            LoginHistoryService,
+            query_login_histories::{FilterLoginHistoriesParams, LoginHistoryOrderField},
+        },
+        user::{UserService, update_user::UpdateUserParams},
+    },
+    utils::query::PageableQuery,
+};
+use axum::{Json, extract::Query, response::IntoResponse};
 use utoipa::OpenApi;
 
 use crate::{
+    dto::PageableQueryDto,
     error::ServerExceptionCode,
-    extractors::{app_service::AppService, session::Session},
+    extractors::{
+        app_service::AppService,
+        helper::Helper,
+        session::{InteractiveSession, Session},
+    },
     init_router,
-    response::{ApiResponse, Null, ResponseJson},
+    response::{ApiResponse, Null, PaginatedData, ResponseJson},
     result::ServerResult,
-    routes::session::dto::{DeleteSessionDto, SessionDto},
+    routes::{
+        session::dto::{
+            ChangePasswordDto, CreatePersonalAccessTokenDto, CreatePersonalAccessTokenResponseDto,
+            DeletePersonalAccessTokenDto, DeleteSessionDto, ImpersonationDto,
+            PersonalAccessTokenDto, RecoveryCodesDto, RenameSessionDto, SendEmailVerificationDto,
+            SessionDto, SessionLoginHistoryFilterDto, TotpCodeDto, TotpEnrollmentDto,
+            UpdateProfileDto,
+        },
+        user::dto::LoginHistoryDto,
+    },
 };
 
 use super::dto::SessionInfoDto;
@@ -18,14 +48,42 @@ use super::dto::SessionInfoDto;
     query_session,
     query_session_permissions,
     query_active_sessions,
-    delete_session
+    rename_session,
+    delete_session,
+    delete_other_sessions,
+    create_personal_access_token,
+    query_personal_access_tokens,
+    delete_personal_access_token,
+    query_login_histories_by_page,
+    query_impersonations,
+    enroll_totp,
+    enable_totp,
+    disable_totp,
+    regenerate_recovery_codes,
+    send_email_verification,
+    update_profile,
+    change_password
 ))]
 pub(crate) struct ApiDoc;
 init_router!(
     query_session,
     query_session_permissions,
     query_active_sessions,
-    delete_session
+    rename_session,
+    delete_session,
+    delete_other_sessions,
+    create_personal_access_token,
+    query_personal_access_tokens,
+    delete_personal_access_token,
+    query_login_histories_by_page,
+    query_impersonations,
+    enroll_totp,
+    enable_totp,
+    disable_totp,
+    regenerate_recovery_codes,
+    send_email_verification,
+    update_profile,
+    change_password
 );
 
 #[utoipa::path(
@@ -76,25 +134,53 @@ pub async fn query_session_permissions(
 /// Query active sessions
 pub async fn query_active_sessions(
     auth_token_service: AppService<AuthTokenService>,
-    auth_session: Session,
+    auth_session: InteractiveSession,
 ) -> ServerResult<ApiResponse> {
     let user_id = auth_session.payload.user_id;
     let tokens = auth_token_service
-        .query_auth_tokens_by_ref_id(user_id)
+        .query_auth_tokens_by_ref_id_and_kind(user_id, AuthTokenKind::Session)
         .await?;
     Ok(ApiResponse::json(
         tokens
             .into_iter()
-            .map(|token| SessionDto {
-                id: token.id,
-                platform: token.platform,
-                agent: token.agent,
-                created_at: token.created_at,
-            })
+            .map(|token| SessionDto::new(token, Some(auth_session.session_id)))
             .collect::<Vec<_>>(),
     ))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/renameSession",
+    request_body = RenameSessionDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+/// Rename session
+pub async fn rename_session(
+    auth_session: InteractiveSession,
+    auth_token_service: AppService<AuthTokenService>,
+    Json(params): Json<RenameSessionDto>,
+) -> ServerResult<ApiResponse> {
+    let target = auth_token_service.query_auth_token_by_id(params.id).await?;
+    let Some(target) = target.filter(|x| x.kind == AuthTokenKind::Session) else {
+        return Err(ServerExceptionCode::NotFound.into());
+    };
+    if target.ref_id != auth_session.payload.user_id {
+        return Err(ServerExceptionCode::Forbidden.into());
+    }
+
+    let device_name = params
+        .device_name
+        .map(|x| x.trim().to_string())
+        .filter(|x| !x.is_empty());
+    auth_token_service
+        .update_device_name(target.id, device_name)
+        .await?;
+
+    Ok(ApiResponse::null())
+}
+
 #[utoipa::path(
     delete,
     path = "/deleteSession",
@@ -105,21 +191,357 @@ pub async fn query_active_sessions(
 )]
 /// Delete session
 pub async fn delete_session(
-    auth_session: Session,
+    auth_session: InteractiveSession,
     auth_token_service: AppService<AuthTokenService>,
+    impersonation_service: AppService<ImpersonationService>,
     Query(query): Query<DeleteSessionDto>,
 ) -> ServerResult<impl IntoResponse> {
     let Some(target) = auth_token_service.query_auth_token_by_id(query.id).await? else {
         return Ok(ApiResponse::null());
     };
 
+    if target.kind != AuthTokenKind::Session {
+        return Ok(ApiResponse::null());
+    }
+
     let valid_user_id = auth_session.payload.user_id;
 
     if valid_user_id == target.ref_id {
         auth_token_service.delete_auth_token_by_id(query.id).await?;
+        impersonation_service
+            .end_impersonation_by_session_id(query.id)
+            .await?;
     } else {
         return Err(ServerExceptionCode::Forbidden.into());
     }
 
     Ok(ApiResponse::null())
 }
+
+#[utoipa::path(
+    delete,
+    path = "/deleteOtherSessions",
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+/// Delete every session but the current one
+pub async fn delete_other_sessions(
+    auth_session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+) -> ServerResult<ApiResponse> {
+    auth_session.assert_not_impersonating()?;
+
+    auth_service
+        .logout_others(auth_session.payload.user_id, auth_session.session_id)
+        .await?;
+
+    Ok(ApiResponse::null())
+}
+
+#[utoipa::path(
+    post,
+    path = "/createPersonalAccessToken",
+    request_body = CreatePersonalAccessTokenDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<CreatePersonalAccessTokenResponseDto>)
+    )
+)]
+/// Create personal access token
+pub async fn create_personal_access_token(
+    auth_session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+    Json(params): Json<CreatePersonalAccessTokenDto>,
+) -> ServerResult<ApiResponse> {
+    auth_session.assert_not_impersonating()?;
+
+    let auth_token = auth_service
+        .issue_personal_access_token(IssuePersonalAccessTokenParams {
+            user_id: auth_session.payload.user_id,
+            name: params.name,
+            permissions: params.permissions,
+            expired_at: params.expired_at,
+            caller_scope: auth_session.payload.scope.clone(),
+        })
+        .await?;
+
+    Ok(ApiResponse::json(CreatePersonalAccessTokenResponseDto {
+        id: auth_token.id,
+        token: auth_token.token,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/queryPersonalAccessTokens",
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Vec<PersonalAccessTokenDto>>)
+    )
+)]
+/// Query personal access tokens
+pub async fn query_personal_access_tokens(
+    auth_session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+) -> ServerResult<ApiResponse> {
+    let user_id = auth_session.payload.user_id;
+    let tokens = auth_service.query_personal_access_tokens(user_id).await?;
+
+    Ok(ApiResponse::json(
+        tokens
+            .into_iter()
+            .map(PersonalAccessTokenDto::from)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/deletePersonalAccessToken",
+    params(DeletePersonalAccessTokenDto),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+/// Delete personal access token
+pub async fn delete_personal_access_token(
+    auth_session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+    Query(query): Query<DeletePersonalAccessTokenDto>,
+) -> ServerResult<ApiResponse> {
+    let user_id = auth_session.payload.user_id;
+    auth_service
+        .delete_personal_access_token(user_id, query.id)
+        .await?;
+
+    Ok(ApiResponse::null())
+}
+
+#[utoipa::path(
+    post,
+    path = "/queryLoginHistoriesByPage",
+    request_body = PageableQueryDto<SessionLoginHistoryFilterDto, LoginHistoryOrderField>,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<PaginatedData<LoginHistoryDto>>)
+    )
+)]
+/// Query own login histories by page
+pub async fn query_login_histories_by_page(
+    auth_session: InteractiveSession,
+    login_history_service: AppService<LoginHistoryService>,
+    Json(params): Json<PageableQueryDto<SessionLoginHistoryFilterDto, LoginHistoryOrderField>>,
+) -> ServerResult<ApiResponse> {
+    let mut params: PageableQuery<FilterLoginHistoriesParams, LoginHistoryOrderField> =
+        params.into();
+    params.condition.get_or_insert_default().user_id = Some(auth_session.payload.user_id);
+
+    let (login_histories, total) = login_history_service
+        .query_login_histories_by_page(params)
+        .await?;
+    let records = login_histories
+        .into_iter()
+        .map(LoginHistoryDto::from)
+        .collect::<Vec<_>>();
+
+    Ok(ApiResponse::json(PaginatedData { records, total }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/queryImpersonations",
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Vec<ImpersonationDto>>)
+    )
+)]
+/// Query who acted as the session user, and when
+pub async fn query_impersonations(
+    auth_session: InteractiveSession,
+    impersonation_service: AppService<ImpersonationService>,
+) -> ServerResult<ApiResponse> {
+    let impersonations = impersonation_service
+        .query_impersonations_by_user_id(auth_session.payload.user_id)
+        .await?;
+
+    Ok(ApiResponse::json(
+        impersonations
+            .into_iter()
+            .map(ImpersonationDto::from)
+            .collect::<Vec<_>>(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/enrollTotp",
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<TotpEnrollmentDto>)
+    )
+)]
+/// Enroll TOTP, which stays disabled until a first code is confirmed with `enableTotp`
+pub async fn enroll_totp(
+    auth_session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+) -> ServerResult<ApiResponse> {
+    auth_session.assert_not_impersonating()?;
+
+    let enrollment = auth_service
+        .enroll_totp(auth_session.payload.user_id)
+        .await?;
+
+    Ok(ApiResponse::json(TotpEnrollmentDto::from(enrollment)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/enableTotp",
+    request_body = TotpCodeDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<RecoveryCodesDto>)
+    )
+)]
+/// Enable TOTP with a first code of the enrolled secret
+pub async fn enable_totp(
+    auth_session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+    Json(params): Json<TotpCodeDto>,
+) -> ServerResult<ApiResponse> {
+    auth_session.assert_not_impersonating()?;
+
+    let recovery_codes = auth_service
+        .enable_totp(auth_session.payload.user_id, &params.code)
+        .await?;
+
+    Ok(ApiResponse::json(RecoveryCodesDto { recovery_codes }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/disableTotp",
+    request_body = TotpCodeDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+/// Disable TOTP with a current or recovery code
+pub async fn disable_totp(
+    auth_session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+    Json(params): Json<TotpCodeDto>,
+) -> ServerResult<ApiResponse> {
+    auth_session.assert_not_impersonating()?;
+
+    auth_service
+        .disable_totp(auth_session.payload.user_id, &params.code)
+        .await?;
+
+    Ok(ApiResponse::null())
+}
+
+#[utoipa::path(
+    post,
+    path = "/regenerateRecoveryCodes",
+    request_body = TotpCodeDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<RecoveryCodesDto>)
+    )
+)]
+/// Replace the recovery codes, given a current TOTP code
+pub async fn regenerate_recovery_codes(
+    auth_session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+    Json(params): Json<TotpCodeDto>,
+) -> ServerResult<ApiResponse> {
+    auth_session.assert_not_impersonating()?;
+
+    let recovery_codes = auth_service
+        .regenerate_recovery_codes(auth_session.payload.user_id, &params.code)
+        .await?;
+
+    Ok(ApiResponse::json(RecoveryCodesDto { recovery_codes }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/sendEmailVerification",
+    request_body = SendEmailVerificationDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+/// Set the email of the session user, unverified until the mailed link is opened
+pub async fn send_email_verification(
+    auth_session: InteractiveSession,
+    auth_service: AppService<AuthService>,
+    Json(params): Json<SendEmailVerificationDto>,
+) -> ServerResult<ApiResponse> {
+    auth_service
+        .send_email_verification(auth_session.payload.user_id, &params.email)
+        .await?;
+
+    Ok(ApiResponse::null())
+}
+
+#[utoipa::path(
+    patch,
+    path = "/updateProfile",
+    request_body = UpdateProfileDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+/// Update the profile of the session user
+pub async fn update_profile(
+    auth_session: InteractiveSession,
+    user_service: AppService<UserService>,
+    Json(params): Json<UpdateProfileDto>,
+) -> ServerResult<ApiResponse> {
+    // A new email could be used to reset the password
+    if params.email.is_some() {
+        auth_session.assert_not_impersonating()?;
+    }
+
+    user_service
+        .update_user(UpdateUserParams {
+            id: auth_session.payload.user_id,
+            nickname: params.nickname,
+            real_name: params.real_name,
+            phone: params.phone,
+            email: params.email,
+            gender: params.gender,
+            birthday: params.birthday,
+            bio: params.bio,
+            avatar_url: params.avatar_url,
+        })
+        .await?;
+
+    Ok(ApiResponse::null())
+}
+
+#[utoipa::path(
+    post,
+    path = "/changePassword",
+    request_body = ChangePasswordDto,
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Null>)
+    )
+)]
+/// Change the password of the session user
+pub async fn change_password(
+    auth_session: InteractiveSession,
+    helper: Helper,
+    auth_service: AppService<AuthService>,
+    Json(params): Json<ChangePasswordDto>,
+) -> ServerResult<ApiResponse> {
+    auth_session.assert_not_impersonating()?;
+
+    auth_service
+        .change_password(ChangePasswordParams {
+            user_id: auth_session.payload.user_id,
+            session_id: auth_session.session_id,
+            old_password: helper.decrypt_rsa(&params.old_password)?,
+            new_password: helper.decrypt_rsa(&params.new_password)?,
+            revoke_other_sessions: params.revoke_other_sessions,
+        })
+        .await?;
+
+    Ok(ApiResponse::null())
+}
@@ -0,0 +1,43 @@
+use rsa::traits::PublicKeyParts;
+use serde::Serialize;
+use shared::utils::encode_base64_url;
+use utoipa::ToSchema;
+
+use crate::keyring::RsaKey;
+
+/// An RSA public key as a JSON Web Key, RFC 7517
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JwkDto {
+    /// Always `RSA`
+    pub kty: &'static str,
+    /// Prefixed to the ciphertext as `{kid}:{ciphertext}`
+    pub kid: String,
+    /// Always `enc`
+    #[serde(rename = "use")]
+    pub use_: &'static str,
+    /// Always `RSA1_5`, PKCS#1 v1.5 padding
+    pub alg: &'static str,
+    /// Modulus, base64url encoded
+    pub n: String,
+    /// Exponent, base64url encoded
+    pub e: String,
+}
+
+impl From<&RsaKey> for JwkDto {
+    fn from(value: &RsaKey) -> Self {
+        Self {
+            kty: "RSA",
+            kid: value.kid.clone(),
+            use_: "enc",
+            alg: "RSA1_5",
+            n: encode_base64_url(&value.public_key.n().to_bytes_be()),
+            e: encode_base64_url(&value.public_key.e().to_bytes_be()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct JwksDto {
+    /// Currently valid keys, the one to encrypt with first
+    pub keys: Vec<JwkDto>,
+}
@@ -1 +1,2 @@
+pub mod dto;
 pub mod router;
@@ -1,33 +1,36 @@
+use std::sync::Arc;
+
 use axum::Extension;
-use rsa::{RsaPublicKey, pkcs8::EncodePublicKey};
 use utoipa::OpenApi;
 
 use crate::{
     init_router,
+    keyring::RsaKeyring,
     response::{ApiResponse, ResponseJson},
     result::ServerResult,
 };
 
+use super::dto::{JwkDto, JwksDto};
+
 #[derive(OpenApi)]
 #[openapi(paths(query_system_rsa_public_key))]
 pub(crate) struct ApiDoc;
 init_router!(query_system_rsa_public_key);
 
-/// Query system rsa public key
+/// Query system rsa public keys
 #[utoipa::path(
     operation_id = "querySystemRsaPublicKey",
+    description = "Keys to encrypt passwords with, the ciphertext is sent as `{kid}:{base64 ciphertext}`",
     get,
     path = "/querySystemRsaPublicKey",
     responses(
-        (status = OK, description = "ok", body = ResponseJson<String>)
+        (status = OK, description = "ok", body = ResponseJson<JwksDto>)
     )
 )]
 pub async fn query_system_rsa_public_key(
-    Extension(public_key): Extension<RsaPublicKey>,
+    Extension(keyring): Extension<Arc<RsaKeyring>>,
 ) -> ServerResult<ApiResponse> {
-    let public_key_pem = public_key
-        .to_public_key_pem(rsa::pkcs8::LineEnding::LF)
-        .unwrap();
+    let keys = keyring.valid_keys().map(JwkDto::from).collect();
 
-    Ok(ApiResponse::json(public_key_pem))
+    Ok(ApiResponse::json(JwksDto { keys }))
 }
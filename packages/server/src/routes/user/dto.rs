@@ -1,11 +1,20 @@
 use app::{
-    models::user::User,
-    services::user::{delete_user::DeleteUsersParams, query_user::FilterUsersParams},
+    models::{
+        login_history::LoginHistory,
+        user::{LastLogin, User},
+    },
+    services::{
+        login_history::query_login_histories::FilterLoginHistoriesParams,
+        user::{
+            delete_user::DeleteUsersParams, query_user::FilterUsersParams,
+            update_user::UpdateUserParams,
+        },
+    },
 };
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 use shared::enums::Gender;
-use utoipa::ToSchema;
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 #[derive(Debug, ToSchema, Deserialize)]
@@ -29,6 +38,11 @@ pub struct UserDto {
     pub email_verified: bool,
     pub avatar_url: Option<String>,
     pub gender: Gender,
+    pub birthday: Option<NaiveDate>,
+    pub bio: Option<String>,
+    pub locked_until: Option<DateTime<Utc>>,
+    pub last_login: Option<LastLoginDto>,
+    pub totp_enabled: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -45,12 +59,38 @@ impl From<User> for UserDto {
             email_verified: value.email_verified,
             avatar_url: value.avatar_url,
             gender: value.gender,
+            birthday: value.birthday,
+            bio: value.bio,
+            locked_until: value.locked_until,
+            last_login: value.last_login.map(LastLoginDto::from),
+            totp_enabled: value.totp_enabled,
             created_at: value.created_at.into(),
             updated_at: value.updated_at.into(),
         }
     }
 }
 
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct LastLoginDto {
+    pub at: DateTime<Utc>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+}
+
+impl From<LastLogin> for LastLoginDto {
+    fn from(value: LastLogin) -> Self {
+        Self {
+            at: value.at,
+            ip: value.ip,
+            platform: value.platform,
+            agent: value.agent,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[schema(rename_all = "camelCase")]
@@ -66,6 +106,38 @@ impl From<UserFilterDto> for FilterUsersParams {
     }
 }
 
+#[derive(Debug, ToSchema, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct UpdateUserDto {
+    pub id: Uuid,
+    pub nickname: Option<String>,
+    pub real_name: Option<String>,
+    pub phone: Option<String>,
+    /// A changed email has to be verified again
+    pub email: Option<String>,
+    pub gender: Option<Gender>,
+    pub birthday: Option<NaiveDate>,
+    pub bio: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+impl From<UpdateUserDto> for UpdateUserParams {
+    fn from(value: UpdateUserDto) -> Self {
+        Self {
+            id: value.id,
+            nickname: value.nickname,
+            real_name: value.real_name,
+            phone: value.phone,
+            email: value.email,
+            gender: value.gender,
+            birthday: value.birthday,
+            bio: value.bio,
+            avatar_url: value.avatar_url,
+        }
+    }
+}
+
 /// User id list
 #[derive(Debug, ToSchema, Deserialize)]
 pub struct DeleteUsersRequestDto(Vec<Uuid>);
@@ -75,3 +147,76 @@ impl From<DeleteUsersRequestDto> for DeleteUsersParams {
         DeleteUsersParams(value.0)
     }
 }
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct UnlockUserDto {
+    pub id: Uuid,
+}
+
+#[derive(Debug, ToSchema, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct LoginHistoryDto {
+    pub id: Uuid,
+    pub user_id: Option<Uuid>,
+    pub account: String,
+    pub success: bool,
+    pub failure_reason: Option<String>,
+    pub ip: Option<String>,
+    pub platform: Option<String>,
+    pub agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<LoginHistory> for LoginHistoryDto {
+    fn from(value: LoginHistory) -> Self {
+        Self {
+            id: value.id,
+            user_id: value.user_id,
+            account: value.account,
+            success: value.success,
+            failure_reason: value.failure_reason,
+            ip: value.ip,
+            platform: value.platform,
+            agent: value.agent,
+            created_at: value.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct LoginHistoryFilterDto {
+    pub user_id: Option<Uuid>,
+    pub account: Option<String>,
+    pub success: Option<bool>,
+}
+
+impl From<LoginHistoryFilterDto> for FilterLoginHistoriesParams {
+    fn from(value: LoginHistoryFilterDto) -> Self {
+        FilterLoginHistoriesParams {
+            user_id: value.user_id,
+            account: value.account,
+            success: value.success,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+#[into_params(parameter_in = Query, rename_all = "camelCase")]
+pub struct QueryUserSessionsDto {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[schema(rename_all = "camelCase")]
+pub struct DeleteUserSessionsDto {
+    pub user_id: Uuid,
+    /// Only revoke this session, every session of the user if missing
+    pub id: Option<Uuid>,
+}
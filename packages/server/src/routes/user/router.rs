@@ -1,23 +1,54 @@
-use app::services::user::{UserService, create_user::CreateUserParams, query_user::UserOrderField};
-use axum::Json;
-use shared::enums::OperationPermission;
+use app::{
+    models::auth_token::AuthTokenKind,
+    services::{
+        auth::AuthService,
+        auth_token::AuthTokenService,
+        login_history::{LoginHistoryService, query_login_histories::LoginHistoryOrderField},
+        user::{
+            UserService, create_user::CreateUserParams, delete_user::DeleteUsersParams,
+            query_user::UserOrderField,
+        },
+    },
+};
+use axum::{Json, extract::Query};
 use utoipa::OpenApi;
 
 use crate::{
     dto::PageableQueryDto,
-    extractors::{app_service::AppService, helper::Helper, session::Session},
+    extractors::{app_service::AppService, helper::Helper, principal::Principal},
     init_router,
     response::{ApiResponse, PaginatedData, ResponseJson, ResponseJsonNull},
     result::ServerResult,
-    routes::user::dto::DeleteUsersRequestDto,
+    routes::{session::dto::SessionDto, user::dto::DeleteUsersRequestDto},
 };
 
-use super::dto::{CreateUserDto, UserDto, UserFilterDto};
+use super::dto::{
+    CreateUserDto, DeleteUserSessionsDto, LoginHistoryDto, LoginHistoryFilterDto,
+    QueryUserSessionsDto, UnlockUserDto, UpdateUserDto, UserDto, UserFilterDto,
+};
 
 #[derive(OpenApi)]
-#[openapi(paths(create_user, query_users_by_page, delete_users))]
+#[openapi(paths(
+    create_user,
+    query_users_by_page,
+    update_user,
+    delete_users,
+    unlock_user,
+    query_user_sessions,
+    delete_user_sessions,
+    query_login_histories_by_page
+))]
 pub(crate) struct ApiDoc;
-init_router!(create_user, query_users_by_page, delete_users);
+init_router!(
+    create_user,
+    query_users_by_page,
+    update_user,
+    delete_users,
+    unlock_user,
+    query_user_sessions,
+    delete_user_sessions,
+    query_login_histories_by_page
+);
 
 /// Query users by page
 #[utoipa::path(
@@ -25,18 +56,16 @@ init_router!(create_user, query_users_by_page, delete_users);
     post,
     path = "/queryUsersByPage",
     request_body = PageableQueryDto<UserFilterDto, UserOrderField>,
+    security(("session" = ["query_users"]), ("signature" = ["query_users"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<PaginatedData<UserDto>>)
     )
 )]
 #[axum::debug_handler]
 pub async fn query_users_by_page(
-    session: Session,
     user_service: AppService<UserService>,
     Json(params): Json<PageableQueryDto<UserFilterDto, UserOrderField>>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryUsers)?;
-
     let (users, total) = user_service.query_users_by_page(params.into()).await?;
     let records = users.into_iter().map(UserDto::from).collect::<Vec<_>>();
 
@@ -49,17 +78,16 @@ pub async fn query_users_by_page(
     post,
     path = "/createUser",
     request_body = CreateUserDto,
+    security(("session" = ["create_user"]), ("signature" = ["create_user"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Uuid>)
     )
 )]
 pub async fn create_user(
-    session: Session,
     util: Helper,
     user_service: AppService<UserService>,
     Json(params): Json<CreateUserDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::CreateUser)?;
     let password = util.decrypt_rsa(&params.password)?;
 
     let user_id = user_service
@@ -73,24 +101,176 @@ pub async fn create_user(
     Ok(ApiResponse::json(user_id))
 }
 
+/// Update user
+#[utoipa::path(
+    operation_id = "updateUser",
+    patch,
+    path = "/updateUser",
+    request_body = UpdateUserDto,
+    security(("session" = ["update_user"]), ("signature" = ["update_user"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJsonNull)
+    )
+)]
+pub async fn update_user(
+    user_service: AppService<UserService>,
+    Json(params): Json<UpdateUserDto>,
+) -> ServerResult<ApiResponse> {
+    user_service.assert_users_in_scope(&[params.id]).await?;
+
+    user_service.update_user(params.into()).await?;
+
+    Ok(ApiResponse::null())
+}
+
 /// Delete users
 #[utoipa::path(
     operation_id = "deleteUsers",
     delete,
     path = "/deleteUsers",
     request_body = DeleteUsersRequestDto,
+    security(("session" = ["delete_user"]), ("signature" = ["delete_user"])),
     responses(
         (status = OK, description = "ok", body = ResponseJsonNull)
     )
 )]
 pub async fn delete_users(
-    session: Session,
+    principal: Principal,
     user_service: AppService<UserService>,
     Json(params): Json<DeleteUsersRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::DeleteUser)?;
+    principal.assert_not_impersonating()?;
+
+    let params = DeleteUsersParams::from(params);
+    user_service.assert_users_in_scope(&params.0).await?;
+
+    user_service.delete_users(params).await?;
+
+    Ok(ApiResponse::null())
+}
+
+/// Unlock user
+#[utoipa::path(
+    operation_id = "unlockUser",
+    description = "Lift the lockout of a user and reset its failed login attempts",
+    post,
+    path = "/unlockUser",
+    request_body = UnlockUserDto,
+    security(("session" = ["unlock_user"]), ("signature" = ["unlock_user"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJsonNull)
+    )
+)]
+pub async fn unlock_user(
+    user_service: AppService<UserService>,
+    Json(params): Json<UnlockUserDto>,
+) -> ServerResult<ApiResponse> {
+    user_service.assert_users_in_scope(&[params.id]).await?;
 
-    user_service.delete_users(params.into()).await?;
+    user_service.unlock_user(params.id).await?;
 
     Ok(ApiResponse::null())
 }
+
+/// Query user sessions
+#[utoipa::path(
+    operation_id = "queryUserSessions",
+    get,
+    path = "/queryUserSessions",
+    params(QueryUserSessionsDto),
+    security(("session" = ["query_user_sessions"]), ("signature" = ["query_user_sessions"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<Vec<SessionDto>>)
+    )
+)]
+pub async fn query_user_sessions(
+    principal: Principal,
+    user_service: AppService<UserService>,
+    auth_token_service: AppService<AuthTokenService>,
+    Query(params): Query<QueryUserSessionsDto>,
+) -> ServerResult<ApiResponse> {
+    user_service
+        .assert_users_in_scope(&[params.user_id])
+        .await?;
+
+    let tokens = auth_token_service
+        .query_auth_tokens_by_ref_id_and_kind(params.user_id, AuthTokenKind::Session)
+        .await?;
+    let current_session_id = match &principal {
+        Principal::User(session) => Some(session.session_id),
+        Principal::Application(_) => None,
+    };
+    let sessions = tokens
+        .into_iter()
+        .map(|x| SessionDto::new(x, current_session_id))
+        .collect::<Vec<_>>();
+
+    Ok(ApiResponse::json(sessions))
+}
+
+/// Delete user sessions
+#[utoipa::path(
+    operation_id = "deleteUserSessions",
+    description = "Sign a user out of one or every session",
+    delete,
+    path = "/deleteUserSessions",
+    request_body = DeleteUserSessionsDto,
+    security(("session" = ["delete_user_sessions"]), ("signature" = ["delete_user_sessions"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJsonNull)
+    )
+)]
+pub async fn delete_user_sessions(
+    principal: Principal,
+    user_service: AppService<UserService>,
+    auth_service: AppService<AuthService>,
+    auth_token_service: AppService<AuthTokenService>,
+    Json(params): Json<DeleteUserSessionsDto>,
+) -> ServerResult<ApiResponse> {
+    principal.assert_not_impersonating()?;
+
+    user_service
+        .assert_users_in_scope(&[params.user_id])
+        .await?;
+
+    match params.id {
+        Some(id) => {
+            let target = auth_token_service.query_auth_token_by_id(id).await?;
+            let is_session = target
+                .is_some_and(|x| x.kind == AuthTokenKind::Session && x.ref_id == params.user_id);
+            if is_session {
+                auth_service.logout(id).await?;
+            }
+        }
+        None => auth_service.logout_sessions(params.user_id).await?,
+    }
+
+    Ok(ApiResponse::null())
+}
+
+/// Query login histories by page
+#[utoipa::path(
+    operation_id = "queryLoginHistoriesByPage",
+    description = "Query the login histories of all users",
+    post,
+    path = "/queryLoginHistoriesByPage",
+    request_body = PageableQueryDto<LoginHistoryFilterDto, LoginHistoryOrderField>,
+    security(("session" = ["query_login_histories"]), ("signature" = ["query_login_histories"])),
+    responses(
+        (status = OK, description = "ok", body = ResponseJson<PaginatedData<LoginHistoryDto>>)
+    )
+)]
+pub async fn query_login_histories_by_page(
+    login_history_service: AppService<LoginHistoryService>,
+    Json(params): Json<PageableQueryDto<LoginHistoryFilterDto, LoginHistoryOrderField>>,
+) -> ServerResult<ApiResponse> {
+    let (login_histories, total) = login_history_service
+        .query_login_histories_by_page(params.into())
+        .await?;
+    let records = login_histories
+        .into_iter()
+        .map(LoginHistoryDto::from)
+        .collect::<Vec<_>>();
+
+    Ok(ApiResponse::json(PaginatedData { records, total }))
+}
@@ -2,12 +2,11 @@ use app::services::user_group::{
     UserGroupService, create_user_group::CreateGroupParams, query_user_group::UserGroupsOrderField,
 };
 use axum::Json;
-use shared::enums::OperationPermission;
 use utoipa::OpenApi;
 
 use crate::{
     dto::PageableQueryDto,
-    extractors::{app_service::AppService, session::Session},
+    extractors::app_service::AppService,
     init_router,
     response::{ApiResponse, Null, PaginatedData, ResponseJson},
     result::ServerResult,
@@ -35,17 +34,15 @@ init_router!(
     post,
     path = "/queryGroupsByPage",
     request_body = PageableQueryDto<FilterGroupsDto, UserGroupsOrderField>,
+    security(("session" = ["query_groups"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<PaginatedData<GroupDto>>)
     )
 )]
 pub async fn query_groups_by_page(
-    session: Session,
     user_group_service: AppService<UserGroupService>,
     Json(params): Json<PageableQueryDto<FilterGroupsDto, UserGroupsOrderField>>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::QueryGroups)?;
-
     let (groups, total) = user_group_service
         .query_user_groups_by_page(params.into())
         .await?;
@@ -61,17 +58,15 @@ pub async fn query_groups_by_page(
     post,
     path = "/createGroup",
     request_body = CreateGroupRequestDto,
+    security(("session" = ["create_group"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<CreateGroupResponseDto>)
     )
 )]
 pub async fn create_user_group(
-    session: Session,
     user_group_service: AppService<UserGroupService>,
     Json(params): Json<CreateGroupRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::CreateGroup)?;
-
     let group_id = user_group_service
         .create_user_group(CreateGroupParams {
             name: params.name,
@@ -91,17 +86,15 @@ pub async fn create_user_group(
     delete,
     path = "/deleteGroups",
     request_body = DeleteGroupsRequestDto,
+    security(("session" = ["delete_group"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn delete_groups(
-    session: Session,
     user_group_service: AppService<UserGroupService>,
     Json(params): Json<DeleteGroupsRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::DeleteGroup)?;
-
     user_group_service.delete_user_groups(params.into()).await?;
     Ok(ApiResponse::null())
 }
@@ -113,17 +106,15 @@ pub async fn delete_groups(
     patch,
     path = "/updateGroup",
     request_body = UpdateGroupRequestDto,
+    security(("session" = ["update_group"])),
     responses(
         (status = OK, description = "ok", body = ResponseJson<Null>)
     )
 )]
 pub async fn update_group(
-    session: Session,
     user_group_service: AppService<UserGroupService>,
     Json(params): Json<UpdateGroupRequestDto>,
 ) -> ServerResult<ApiResponse> {
-    session.assert_has_permission(OperationPermission::UpdateGroup)?;
-
     user_group_service.update_user_group(params.into()).await?;
     Ok(ApiResponse::null())
 }
@@ -1,19 +1,52 @@
 use std::{path::PathBuf, sync::OnceLock};
 
+use app::config::{
+    AppSignatureConfig, AuthorizationServerConfig, JwtConfig, LoginConfig, MailConfig, OAuthConfig,
+    PasswordHashConfig, PasswordPolicyConfig, SessionConfig,
+};
 use serde::{Deserialize, Serialize};
 
-use crate::result::ServerResult;
+use crate::{
+    keyring::RsaKeyConfig, middlewares::ip_throttle::ThrottleConfig, result::ServerResult,
+};
 
 static SETTING: OnceLock<Settings> = OnceLock::new();
 
+const DEFAULT_RSA_KEY_ID: &str = "default";
+
 #[derive(Deserialize, Serialize, Debug)]
 pub struct Settings {
     pub database_url: String,
     pub server_port: u16,
-    pub private_key: String,
-    pub public_key: String,
+    #[serde(default)]
+    pub rsa_keys: Vec<RsaKeyConfig>,
+    /// Key pair of settings files from before `rsa_keys`, see `rsa_key_configs`.
+    #[serde(default, skip_serializing)]
+    pub private_key: Option<String>,
+    #[serde(default, skip_serializing)]
+    pub public_key: Option<String>,
     pub upload_dir: PathBuf,
     pub debug: Option<bool>,
+    #[serde(default)]
+    pub session: SessionConfig,
+    #[serde(skip_serializing)]
+    pub jwt: JwtConfig,
+    #[serde(default)]
+    pub login: LoginConfig,
+    #[serde(default)]
+    pub throttle: ThrottleConfig,
+    #[serde(default)]
+    pub mail: MailConfig,
+    #[serde(default)]
+    pub oauth: OAuthConfig,
+    #[serde(default)]
+    pub authorization_server: AuthorizationServerConfig,
+    #[serde(default)]
+    pub app_signature: AppSignatureConfig,
+    #[serde(default)]
+    pub password_policy: PasswordPolicyConfig,
+    #[serde(default)]
+    pub password_hash: PasswordHashConfig,
 }
 
 impl Settings {
@@ -33,6 +66,23 @@ impl Settings {
         SETTING.get().unwrap()
     }
 
+    /// The configured rsa keys, or else the legacy `private_key` and `public_key` pair as the
+    /// only key, with the kid `default`.
+    pub fn rsa_key_configs(&self) -> Vec<RsaKeyConfig> {
+        match (&self.private_key, &self.public_key) {
+            (Some(private_key), Some(public_key)) if self.rsa_keys.is_empty() => {
+                vec![RsaKeyConfig {
+                    kid: DEFAULT_RSA_KEY_ID.to_string(),
+                    private_key: private_key.clone(),
+                    public_key: public_key.clone(),
+                    not_before: None,
+                    not_after: None,
+                }]
+            }
+            _ => self.rsa_keys.clone(),
+        }
+    }
+
     pub fn json_pretty(&self, extra_indent: usize) -> String {
         serde_json::to_string_pretty(self)
             .unwrap()
@@ -32,6 +32,21 @@ pub enum PermissionKind {
     Data,
 }
 
+/// What a permission can be denied to. A denial applies to every user reaching the subject the
+/// same way a grant to it would.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, EnumString, Display, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
+pub enum PermissionSubjectKind {
+    User,
+    UserGroup,
+    Department,
+    Role,
+    RoleGroup,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, EnumString, Display, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "snake_case")]
@@ -55,6 +70,11 @@ pub enum OperationPermission {
     CreateUser,
     UpdateUser,
     DeleteUser,
+    UnlockUser,
+    QueryLoginHistories,
+    ImpersonateUser,
+    QueryUserSessions,
+    DeleteUserSessions,
 
     QueryRoles,
     CreateRole,
@@ -83,9 +103,76 @@ pub enum OperationPermission {
     QueryRolePermissions,
     QueryRoleGroupPermissions,
     QueryPermissionGroupPermissions,
+    QueryPermissionDenials,
+    CreatePermissionDenial,
+    DeletePermissionDenial,
+
+    QueryApplications,
+    CreateApplication,
+    UpdateApplication,
+    DeleteApplication,
+
+    QueryInvitations,
+    CreateInvitation,
+    DeleteInvitation,
+    UpdateRegistrationSettings,
+
+    QueryAccountBooks,
+    CreateAccountBook,
+    UpdateAccountBook,
+    DeleteAccountBook,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, EnumString, Display, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "snake_case")]
-pub enum DataPermission {}
+pub enum DataPermission {
+    DataScopeAll,
+    DataScopeDepartmentTree,
+    DataScopeDepartment,
+    DataScopeSelf,
+}
+
+/// Rows a data permission makes visible. A user with several data permissions sees the union of
+/// their scopes, and only their own rows without any.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, EnumString, Display, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
+pub enum DataScope {
+    /// Rows owned by the user.
+    SelfOnly,
+    /// Rows owned by members of the departments of the user.
+    Department,
+    /// Rows owned by members of the departments of the user, or of any department below them.
+    DepartmentTree,
+    /// Rows owned by members of the departments listed in the permission.
+    CustomDepartments,
+    All,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, EnumString, Display, ToSchema)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
+pub enum SettingName {
+    PermissionVersion,
+    RegistrationMode,
+    RegistrationDomains,
+}
+
+/// Who may register an account on their own.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, EnumString, Display, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
+pub enum RegistrationMode {
+    Open,
+    /// Only with an invitation code.
+    InviteOnly,
+    /// Accounts are only created by admins.
+    Disabled,
+    /// Only with an email address of one of the allowed domains, or an invitation code.
+    DomainRestricted,
+}
@@ -1,3 +1,4 @@
 pub mod enums;
 pub mod tree;
+pub mod user_agent;
 pub mod utils;
@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+use strum::{Display, EnumString};
+use utoipa::ToSchema;
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString, ToSchema,
+)]
+#[serde(rename_all = "camelCase")]
+#[strum(serialize_all = "snake_case")]
+pub enum DeviceKind {
+    Desktop,
+    Mobile,
+    Tablet,
+    Bot,
+    Unknown,
+}
+
+/// What a `User-Agent` header tells about the client, as far as it can be told.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UserAgentInfo {
+    /// Name and major version, e.g. `Chrome 126`.
+    pub browser: Option<String>,
+    /// Name and version if known, e.g. `Windows 10`.
+    pub os: Option<String>,
+    pub device: DeviceKind,
+}
+
+/// Browsers in the order they have to be checked in: most browsers also claim to be the ones
+/// they are built on, so `Edg/` must come before `Chrome/` and `Chrome/` before `Safari/`.
+const BROWSERS: &[(&str, &str)] = &[
+    ("Edg/", "Edge"),
+    ("EdgA/", "Edge"),
+    ("OPR/", "Opera"),
+    ("SamsungBrowser/", "Samsung Internet"),
+    ("YaBrowser/", "Yandex Browser"),
+    ("MicroMessenger/", "WeChat"),
+    ("Firefox/", "Firefox"),
+    ("FxiOS/", "Firefox"),
+    ("CriOS/", "Chrome"),
+    ("Chromium/", "Chromium"),
+    ("Chrome/", "Chrome"),
+    ("curl/", "curl"),
+    ("PostmanRuntime/", "Postman"),
+];
+
+const BOT_MARKERS: &[&str] = &["bot", "crawler", "spider", "slurp", "headless"];
+
+pub fn parse_user_agent(user_agent: &str) -> UserAgentInfo {
+    let lower = user_agent.to_lowercase();
+
+    UserAgentInfo {
+        browser: parse_browser(user_agent),
+        os: parse_os(user_agent),
+        device: if BOT_MARKERS.iter().any(|x| lower.contains(x)) {
+            DeviceKind::Bot
+        } else if lower.contains("ipad") || lower.contains("tablet") {
+            DeviceKind::Tablet
+        } else if lower.contains("android") {
+            // Android tablets leave `Mobile` out
+            if lower.contains("mobile") {
+                DeviceKind::Mobile
+            } else {
+                DeviceKind::Tablet
+            }
+        } else if lower.contains("mobile") || lower.contains("iphone") {
+            DeviceKind::Mobile
+        } else if ["windows", "macintosh", "x11", "cros"]
+            .iter()
+            .any(|x| lower.contains(x))
+        {
+            DeviceKind::Desktop
+        } else {
+            DeviceKind::Unknown
+        },
+    }
+}
+
+fn parse_browser(user_agent: &str) -> Option<String> {
+    let browser = BROWSERS.iter().find_map(|(marker, name)| {
+        token_version(user_agent, marker).map(|version| (*name, version))
+    });
+    // Safari carries its own version in `Version/`
+    let browser = browser.or_else(|| {
+        user_agent.contains("Safari/").then(|| {
+            (
+                "Safari",
+                token_version(user_agent, "Version/").unwrap_or_default(),
+            )
+        })
+    });
+
+    browser.map(|(name, version)| match version.split('.').next() {
+        Some(major) if !major.is_empty() => format!("{name} {major}"),
+        _ => name.to_string(),
+    })
+}
+
+fn parse_os(user_agent: &str) -> Option<String> {
+    if let Some(version) = token_version(user_agent, "Windows NT ") {
+        let name = match version {
+            "10.0" => "Windows 10",
+            "6.3" => "Windows 8.1",
+            "6.2" => "Windows 8",
+            "6.1" => "Windows 7",
+            _ => "Windows",
+        };
+        return Some(name.to_string());
+    }
+    for marker in ["iPhone OS ", "CPU OS "] {
+        if let Some(version) = token_version(user_agent, marker) {
+            return Some(format!("iOS {}", version.replace('_', ".")));
+        }
+    }
+    if let Some(version) = token_version(user_agent, "Android ") {
+        return Some(format!("Android {version}"));
+    }
+    if let Some(version) = token_version(user_agent, "Mac OS X ") {
+        return Some(format!("macOS {}", version.replace('_', ".")));
+    }
+
+    [
+        ("CrOS", "ChromeOS"),
+        ("Android", "Android"),
+        ("Linux", "Linux"),
+        ("Mac OS X", "macOS"),
+    ]
+    .iter()
+    .find(|(marker, _)| user_agent.contains(marker))
+    .map(|(_, name)| name.to_string())
+}
+
+/// The version following `marker`, up to the next space, `;` or `)`.
+fn token_version<'a>(user_agent: &'a str, marker: &str) -> Option<&'a str> {
+    let start = user_agent.find(marker)? + marker.len();
+    let rest = &user_agent[start..];
+    let end = rest.find([' ', ';', ')']).unwrap_or(rest.len());
+    let version = &rest[..end];
+
+    version
+        .starts_with(|x: char| x.is_ascii_digit())
+        .then_some(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(browser: Option<&str>, os: Option<&str>, device: DeviceKind) -> UserAgentInfo {
+        UserAgentInfo {
+            browser: browser.map(ToString::to_string),
+            os: os.map(ToString::to_string),
+            device,
+        }
+    }
+
+    #[test]
+    fn parses_desktop_browsers() {
+        assert_eq!(
+            parse_user_agent(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36"
+            ),
+            info(Some("Chrome 126"), Some("Windows 10"), DeviceKind::Desktop)
+        );
+        assert_eq!(
+            parse_user_agent(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36 Edg/126.0.2592.87"
+            ),
+            info(Some("Edge 126"), Some("Windows 10"), DeviceKind::Desktop)
+        );
+        assert_eq!(
+            parse_user_agent(
+                "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:127.0) Gecko/20100101 Firefox/127.0"
+            ),
+            info(Some("Firefox 127"), Some("Linux"), DeviceKind::Desktop)
+        );
+        assert_eq!(
+            parse_user_agent(
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Safari/605.1.15"
+            ),
+            info(
+                Some("Safari 17"),
+                Some("macOS 10.15.7"),
+                DeviceKind::Desktop
+            )
+        );
+    }
+
+    #[test]
+    fn parses_mobile_and_tablet_devices() {
+        assert_eq!(
+            parse_user_agent(
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1"
+            ),
+            info(Some("Safari 17"), Some("iOS 17.5"), DeviceKind::Mobile)
+        );
+        assert_eq!(
+            parse_user_agent(
+                "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Mobile Safari/537.36"
+            ),
+            info(Some("Chrome 126"), Some("Android 14"), DeviceKind::Mobile)
+        );
+        // Android tablets leave `Mobile` out
+        assert_eq!(
+            parse_user_agent(
+                "Mozilla/5.0 (Linux; Android 13; SM-X710) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36"
+            ),
+            info(Some("Chrome 126"), Some("Android 13"), DeviceKind::Tablet)
+        );
+        assert_eq!(
+            parse_user_agent(
+                "Mozilla/5.0 (iPad; CPU OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) CriOS/126.0.6478.54 Mobile/15E148 Safari/604.1"
+            ),
+            info(Some("Chrome 126"), Some("iOS 17.5"), DeviceKind::Tablet)
+        );
+    }
+
+    #[test]
+    fn parses_bots_and_tools() {
+        assert_eq!(
+            parse_user_agent(
+                "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)"
+            ),
+            info(None, None, DeviceKind::Bot)
+        );
+        assert_eq!(
+            parse_user_agent("curl/8.5.0"),
+            info(Some("curl 8"), None, DeviceKind::Unknown)
+        );
+    }
+
+    #[test]
+    fn tolerates_unknown_user_agents() {
+        assert_eq!(parse_user_agent(""), info(None, None, DeviceKind::Unknown));
+        // Markers without a version are not trusted
+        assert_eq!(
+            parse_user_agent("Chrome/ (Windows NT x)"),
+            info(None, None, DeviceKind::Desktop)
+        );
+    }
+}
@@ -1,36 +1,195 @@
 use std::{io::Write, path::Path};
 
 use argon2::{
-    Argon2,
+    ARGON2ID_IDENT, Algorithm, Argon2, KeyId, Params, ParamsBuilder, Version,
     password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString, rand_core::OsRng},
 };
-use base64::{Engine, prelude::BASE64_STANDARD};
+use base64::{
+    Engine,
+    prelude::{BASE64_STANDARD, BASE64_URL_SAFE_NO_PAD},
+};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use rand::{Rng, RngCore};
 use rsa::{
     Pkcs1v15Encrypt, RsaPrivateKey, RsaPublicKey,
     pkcs1v15::{Signature, SigningKey, VerifyingKey},
     signature::{SignatureEncoding, SignerMut, Verifier},
 };
-use sha2::Sha256;
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
 use tokio::io::AsyncReadExt;
 
-pub fn hash_password(password: &str) -> String {
+/// Argon2id cost of new password hashes, and the pepper mixed into them.
+#[derive(Clone, Copy, Debug)]
+pub struct PasswordHashParams<'a> {
+    /// Memory in KiB.
+    pub memory_cost: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    /// Secret kept out of the database, without it leaked hashes can't be cracked.
+    pub pepper: Option<&'a [u8]>,
+}
+
+impl Default for PasswordHashParams<'_> {
+    fn default() -> Self {
+        Self {
+            memory_cost: Params::DEFAULT_M_COST,
+            iterations: Params::DEFAULT_T_COST,
+            parallelism: Params::DEFAULT_P_COST,
+            pepper: None,
+        }
+    }
+}
+
+pub fn hash_password(password: &str, params: &PasswordHashParams) -> String {
     let password = password.as_bytes();
     let salt = SaltString::generate(&mut OsRng);
-    let argon2 = Argon2::default();
+    let mut builder = ParamsBuilder::new();
+    builder
+        .m_cost(params.memory_cost)
+        .t_cost(params.iterations)
+        .p_cost(params.parallelism);
+    if let Some(pepper) = params.pepper {
+        builder.keyid(pepper_key_id(pepper));
+    }
+    let argon2 = argon2_context(builder.build().unwrap(), params.pepper);
     let password_hash = argon2.hash_password(password, &salt).unwrap().to_string();
     password_hash
 }
 
-pub fn verify_password(password: &str, password_hash: &str) -> bool {
+/// Hashes made with a pepper carry its key id and are verified with it, older ones without.
+pub fn verify_password(password: &str, password_hash: &str, pepper: Option<&[u8]>) -> bool {
     let password = password.as_bytes();
     let Ok(parsed_hash) = PasswordHash::new(password_hash) else {
         return false;
     };
-    Argon2::default()
+    let pepper = match (parsed_hash.params.get("keyid"), pepper) {
+        (Some(_), Some(pepper)) => Some(pepper),
+        (Some(_), None) => return false,
+        (None, _) => None,
+    };
+    argon2_context(Params::default(), pepper)
         .verify_password(password, &parsed_hash)
         .is_ok()
 }
 
+/// Whether a hash was made with other parameters or another pepper than the current ones, so it
+/// should be made again while the password is at hand.
+pub fn password_needs_rehash(password_hash: &str, params: &PasswordHashParams) -> bool {
+    let Ok(parsed_hash) = PasswordHash::new(password_hash) else {
+        return true;
+    };
+    let Ok(hash_params) = Params::try_from(&parsed_hash) else {
+        return true;
+    };
+    let key_id = params.pepper.map(pepper_key_id).unwrap_or(KeyId::EMPTY);
+
+    parsed_hash.algorithm != ARGON2ID_IDENT
+        || parsed_hash.version != Some(Version::V0x13.into())
+        || hash_params.m_cost() != params.memory_cost
+        || hash_params.t_cost() != params.iterations
+        || hash_params.p_cost() != params.parallelism
+        || hash_params.keyid() != key_id.as_bytes()
+}
+
+fn argon2_context(params: Params, pepper: Option<&[u8]>) -> Argon2<'_> {
+    match pepper {
+        Some(pepper) => {
+            Argon2::new_with_secret(pepper, Algorithm::Argon2id, Version::V0x13, params).unwrap()
+        }
+        None => Argon2::new(Algorithm::Argon2id, Version::V0x13, params),
+    }
+}
+
+/// Names the pepper in the hashes made with it, without giving it away.
+fn pepper_key_id(pepper: &[u8]) -> KeyId {
+    KeyId::new(&Sha256::digest(pepper)[..4]).unwrap()
+}
+
+/// Random URL safe string of `len` random bytes.
+pub fn generate_random_token(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    BASE64_URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// RFC 7636 `S256` code challenge of a PKCE code verifier.
+pub fn pkce_challenge(code_verifier: &str) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+const TOTP_PERIOD: i64 = 30;
+const TOTP_DIGITS: u32 = 6;
+
+/// Random 160 bit TOTP secret, base32 encoded without padding as authenticator apps expect.
+pub fn generate_totp_secret() -> String {
+    let mut secret = [0u8; 20];
+    rand::thread_rng().fill_bytes(&mut secret);
+    BASE32_NOPAD.encode(&secret)
+}
+
+/// `otpauth://` URI of a TOTP secret, usually rendered as a QR code for authenticator apps.
+pub fn totp_uri(issuer: &str, account: &str, secret: &str) -> String {
+    let issuer = encode_url(issuer);
+    let account = encode_url(account);
+    format!(
+        "otpauth://totp/{issuer}:{account}?secret={secret}&issuer={issuer}&algorithm=SHA1&digits={TOTP_DIGITS}&period={TOTP_PERIOD}"
+    )
+}
+
+/// RFC 6238 time step a unix timestamp falls into.
+pub fn totp_step(timestamp: i64) -> i64 {
+    timestamp.div_euclid(TOTP_PERIOD)
+}
+
+/// RFC 4226 one-time code of a base32 secret at the given time step.
+pub fn totp_code(secret: &str, step: i64) -> Result<String, anyhow::Error> {
+    let key = BASE32_NOPAD.decode(secret.as_bytes())?;
+    let mut mac = Hmac::<Sha1>::new_from_slice(&key)?;
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes(digest[offset..offset + 4].try_into()?) & 0x7fff_ffff;
+
+    Ok(format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS),
+        width = TOTP_DIGITS as usize
+    ))
+}
+
+/// Time step at which `code` is valid, tolerating one step of clock drift either way.
+pub fn verify_totp(secret: &str, code: &str, timestamp: i64) -> Option<i64> {
+    let step = totp_step(timestamp);
+    (step - 1..=step + 1).find(|&x| totp_code(secret, x).is_ok_and(|expected| expected == code))
+}
+
+/// Random code of `digits` decimal digits, leading zeros included.
+pub fn generate_numeric_code(digits: u32) -> String {
+    let code = rand::thread_rng().gen_range(0..10u64.pow(digits));
+    format!("{code:0width$}", width = digits as usize)
+}
+
+/// Random recovery code such as `ABCDEFGH-IJKLMNOP`.
+pub fn generate_recovery_code() -> String {
+    let mut code = [0u8; 10];
+    rand::thread_rng().fill_bytes(&mut code);
+    format!(
+        "{}-{}",
+        BASE32_NOPAD.encode(&code[..5]),
+        BASE32_NOPAD.encode(&code[5..])
+    )
+}
+
+pub fn generate_rsa_key(bits: usize) -> Result<RsaPrivateKey, anyhow::Error> {
+    let mut rng = rand::thread_rng();
+    let priv_key = RsaPrivateKey::new(&mut rng, bits)?;
+
+    Ok(priv_key)
+}
+
 pub fn sign_rsa(priv_key: &RsaPrivateKey, plaintext: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
     let mut signing_key = SigningKey::<Sha256>::new(priv_key.to_owned());
     let signature = signing_key.sign(plaintext);
@@ -101,6 +260,10 @@ pub fn encode_base64(input: &[u8]) -> String {
     BASE64_STANDARD.encode(input)
 }
 
+pub fn encode_base64_url(input: &[u8]) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(input)
+}
+
 pub fn decode_url(input: &str) -> Result<String, anyhow::Error> {
     Ok(urlencoding::decode(input)?.to_string())
 }
@@ -109,6 +272,28 @@ pub fn encode_url(input: &str) -> String {
     urlencoding::encode(input).to_string()
 }
 
+/// Lowercase hex SHA-256 digest.
+pub fn hash_sha256(input: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(input))
+}
+
+/// Lowercase hex HMAC-SHA256 of `message`.
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(message);
+    format!("{:x}", mac.finalize().into_bytes())
+}
+
+/// Check a hex HMAC-SHA256 of `message` in constant time.
+pub fn verify_hmac_sha256(key: &[u8], message: &[u8], signature: &str) -> bool {
+    let Ok(signature) = data_encoding::HEXLOWER_PERMISSIVE.decode(signature.as_bytes()) else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(message);
+    mac.verify_slice(&signature).is_ok()
+}
+
 pub fn hash_blake3(input: &[u8]) -> String {
     blake3::hash(input).to_string()
 }
@@ -137,3 +322,140 @@ pub async fn hash_file_md5(path: &Path) -> String {
 
     format!("{:x}", digest)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cheap enough for tests, the costs are only compared.
+    const TEST_HASH_PARAMS: PasswordHashParams = PasswordHashParams {
+        memory_cost: 64,
+        iterations: 1,
+        parallelism: 1,
+        pepper: None,
+    };
+
+    #[test]
+    fn hash_with_current_params_is_kept() {
+        let hash = hash_password("secret", &TEST_HASH_PARAMS);
+        assert!(!password_needs_rehash(&hash, &TEST_HASH_PARAMS));
+
+        let params = PasswordHashParams {
+            pepper: Some(b"pepper"),
+            ..TEST_HASH_PARAMS
+        };
+        let hash = hash_password("secret", &params);
+        assert!(!password_needs_rehash(&hash, &params));
+    }
+
+    #[test]
+    fn hash_with_other_costs_needs_rehash() {
+        let hash = hash_password("secret", &TEST_HASH_PARAMS);
+
+        for params in [
+            PasswordHashParams {
+                memory_cost: 128,
+                ..TEST_HASH_PARAMS
+            },
+            PasswordHashParams {
+                iterations: 2,
+                ..TEST_HASH_PARAMS
+            },
+            PasswordHashParams {
+                parallelism: 2,
+                ..TEST_HASH_PARAMS
+            },
+        ] {
+            assert!(password_needs_rehash(&hash, &params), "{params:?}");
+        }
+    }
+
+    #[test]
+    fn hash_with_other_pepper_needs_rehash() {
+        let peppered = PasswordHashParams {
+            pepper: Some(b"pepper"),
+            ..TEST_HASH_PARAMS
+        };
+        let other_pepper = PasswordHashParams {
+            pepper: Some(b"other pepper"),
+            ..TEST_HASH_PARAMS
+        };
+
+        let hash = hash_password("secret", &TEST_HASH_PARAMS);
+        assert!(password_needs_rehash(&hash, &peppered));
+
+        let hash = hash_password("secret", &peppered);
+        assert!(password_needs_rehash(&hash, &TEST_HASH_PARAMS));
+        assert!(password_needs_rehash(&hash, &other_pepper));
+    }
+
+    #[test]
+    fn hash_of_other_algorithm_needs_rehash() {
+        let params = Params::new(64, 1, 1, None).unwrap();
+        let salt = SaltString::generate(&mut OsRng);
+        let argon2i_hash = Argon2::new(Algorithm::Argon2i, Version::V0x13, params)
+            .hash_password(b"secret", &salt)
+            .unwrap()
+            .to_string();
+
+        assert!(password_needs_rehash(&argon2i_hash, &TEST_HASH_PARAMS));
+        assert!(password_needs_rehash("not a hash", &TEST_HASH_PARAMS));
+    }
+
+    /// `12345678901234567890`, the secret of the RFC 6238 test vectors.
+    const RFC_SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[test]
+    fn totp_code_matches_rfc_vectors() {
+        assert_eq!(totp_code(RFC_SECRET, totp_step(59)).unwrap(), "287082");
+        assert_eq!(
+            totp_code(RFC_SECRET, totp_step(1111111109)).unwrap(),
+            "081804"
+        );
+        assert_eq!(
+            totp_code(RFC_SECRET, totp_step(2000000000)).unwrap(),
+            "279037"
+        );
+    }
+
+    #[test]
+    fn totp_step_boundaries() {
+        assert_eq!(totp_step(0), 0);
+        assert_eq!(totp_step(29), 0);
+        assert_eq!(totp_step(30), 1);
+        assert_eq!(totp_step(-1), -1);
+    }
+
+    #[test]
+    fn verify_totp_tolerates_one_step_of_skew() {
+        let timestamp = 1111111109;
+        let step = totp_step(timestamp);
+
+        for skew in -1..=1 {
+            let code = totp_code(RFC_SECRET, step + skew).unwrap();
+            assert_eq!(verify_totp(RFC_SECRET, &code, timestamp), Some(step + skew));
+        }
+        for skew in [-2, 2] {
+            let code = totp_code(RFC_SECRET, step + skew).unwrap();
+            assert_eq!(verify_totp(RFC_SECRET, &code, timestamp), None);
+        }
+    }
+
+    #[test]
+    fn verify_totp_window_follows_the_clock() {
+        let code = totp_code(RFC_SECRET, 10).unwrap();
+
+        // Valid from the start of the step before to the end of the step after
+        assert_eq!(verify_totp(RFC_SECRET, &code, 9 * 30 - 1), None);
+        assert_eq!(verify_totp(RFC_SECRET, &code, 9 * 30), Some(10));
+        assert_eq!(verify_totp(RFC_SECRET, &code, 12 * 30 - 1), Some(10));
+        assert_eq!(verify_totp(RFC_SECRET, &code, 12 * 30), None);
+    }
+
+    #[test]
+    fn verify_totp_rejects_malformed_input() {
+        assert_eq!(verify_totp(RFC_SECRET, "", 59), None);
+        assert_eq!(verify_totp(RFC_SECRET, "2870820", 59), None);
+        assert_eq!(verify_totp("not base32!", "287082", 59), None);
+    }
+}